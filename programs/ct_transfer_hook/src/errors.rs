@@ -0,0 +1,17 @@
+use anchor_lang::prelude::*;
+
+#[error_code]
+pub enum ErrorCode {
+    #[msg("Mint is not a conditional token of this market")]
+    NotMarketMint,
+    #[msg("Mint does not use this transfer hook")]
+    InvalidTransferHook,
+    #[msg("Hook called outside of a token transfer")]
+    NotTransferring,
+    #[msg("Conditional token transfers are frozen")]
+    TransfersFrozen,
+    #[msg("Market finalized, conditional tokens can only be redeemed")]
+    MarketFinalized,
+    #[msg("Recipient not on the market allowlist")]
+    RecipientNotAllowed,
+}
@@ -0,0 +1,64 @@
+// the market owner manages who may receive the conditional tokens of a restricted market
+use anchor_lang::prelude::*;
+use market_program::MarketConfig;
+
+use crate::states::*;
+
+#[derive(Accounts)]
+#[instruction(wallet: Pubkey)]
+pub struct AddToAllowlist<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(has_one = owner)]
+    pub market_config: Account<'info, MarketConfig>,
+
+    #[account(
+        init,
+        seeds = [
+            ALLOWLIST_SEED.as_bytes(),
+            market_config.key().as_ref(),
+            wallet.as_ref(),
+        ],
+        bump,
+        payer = owner,
+        space = 8 + AllowlistEntry::INIT_SPACE,
+    )]
+    pub allowlist_entry: Account<'info, AllowlistEntry>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn add_to_allowlist(ctx: Context<AddToAllowlist>, wallet: Pubkey) -> Result<()> {
+    let allowlist_entry = &mut ctx.accounts.allowlist_entry;
+    allowlist_entry.bump = ctx.bumps.allowlist_entry;
+    allowlist_entry.market_config = ctx.accounts.market_config.key();
+    allowlist_entry.wallet = wallet;
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct RemoveFromAllowlist<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(has_one = owner)]
+    pub market_config: Account<'info, MarketConfig>,
+
+    #[account(
+        mut,
+        close = owner,
+        has_one = market_config,
+        seeds = [
+            ALLOWLIST_SEED.as_bytes(),
+            market_config.key().as_ref(),
+            allowlist_entry.wallet.as_ref(),
+        ],
+        bump = allowlist_entry.bump,
+    )]
+    pub allowlist_entry: Account<'info, AllowlistEntry>,
+}
+
+pub fn remove_from_allowlist(_ctx: Context<RemoveFromAllowlist>) -> Result<()> {
+    Ok(())
+}
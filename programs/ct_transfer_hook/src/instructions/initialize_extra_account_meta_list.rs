@@ -0,0 +1,96 @@
+// the market program creates the lists of both mints by cpi when it initializes a market.
+// permissionless, the list only depends on the mint and its market so anyone can create it
+// for markets initialized before that. Conditional tokens cannot be transferred without it.
+use anchor_lang::prelude::*;
+use anchor_spl::token_2022::spl_token_2022::{
+    extension::{transfer_hook::TransferHook, BaseStateWithExtensions, StateWithExtensions},
+    state::Mint,
+};
+use anchor_spl::token_interface::Mint as MintAccount;
+use market_program::MarketConfig;
+use spl_tlv_account_resolution::{account::ExtraAccountMeta, seeds::Seed, state::ExtraAccountMetaList};
+use spl_transfer_hook_interface::instruction::ExecuteInstruction;
+
+use crate::errors::ErrorCode;
+use crate::states::*;
+
+#[derive(Accounts)]
+pub struct InitializeExtraAccountMetaList<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    #[account(
+        constraint = market_config.ct1_mint == mint.key() || market_config.ct2_mint == mint.key() @ ErrorCode::NotMarketMint
+    )]
+    pub market_config: Account<'info, MarketConfig>,
+
+    pub mint: InterfaceAccount<'info, MintAccount>,
+
+    /// CHECK: the extra account meta list, written in the handler
+    #[account(
+        init,
+        seeds = [EXTRA_ACCOUNT_METAS_SEED.as_bytes(), mint.key().as_ref()],
+        bump,
+        space = ExtraAccountMetaList::size_of(extra_account_metas(market_config.key())?.len())?,
+        payer = payer,
+    )]
+    pub extra_account_meta_list: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+// the accounts the token program appends to every execute call, after
+// source, mint, destination, owner and the meta list itself (indexes 0 to 4)
+fn extra_account_metas(market_config: Pubkey) -> Result<Vec<ExtraAccountMeta>> {
+    Ok(vec![
+        // 5: the market the mint belongs to
+        ExtraAccountMeta::new_with_pubkey(&market_config, false, false)?,
+        // 6: the allowlist entry of the destination's owner, read at byte 32 of the token account
+        ExtraAccountMeta::new_with_seeds(
+            &[
+                Seed::Literal {
+                    bytes: ALLOWLIST_SEED.as_bytes().to_vec(),
+                },
+                Seed::AccountKey { index: 5 },
+                Seed::AccountData {
+                    account_index: 2,
+                    data_index: 32,
+                    length: 32,
+                },
+            ],
+            false,
+            false,
+        )?,
+    ])
+}
+
+pub fn initialize_extra_account_meta_list(ctx: Context<InitializeExtraAccountMetaList>) -> Result<()> {
+    {
+        let mint_info = ctx.accounts.mint.to_account_info();
+        let mint_data = mint_info.try_borrow_data()?;
+        let mint = StateWithExtensions::<Mint>::unpack(&mint_data)?;
+        let program_id: Option<Pubkey> = mint.get_extension::<TransferHook>()?.program_id.into();
+        if program_id != Some(crate::id()) {
+            return Err(ErrorCode::InvalidTransferHook.into());
+        }
+    }
+
+    ExtraAccountMetaList::init::<ExecuteInstruction>(
+        &mut ctx.accounts.extra_account_meta_list.try_borrow_mut_data()?,
+        &extra_account_metas(ctx.accounts.market_config.key())?,
+    )?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use anchor_lang::Discriminator;
+
+    #[test]
+    fn market_program_cpi_uses_this_discriminator() {
+        assert_eq!(
+            market_program::INITIALIZE_EXTRA_ACCOUNT_META_LIST_DISCRIMINATOR,
+            crate::instruction::InitializeExtraAccountMetaList::DISCRIMINATOR
+        );
+    }
+}
@@ -0,0 +1,8 @@
+pub mod initialize_extra_account_meta_list;
+pub use initialize_extra_account_meta_list::*;
+
+pub mod allowlist;
+pub use allowlist::*;
+
+pub mod transfer_hook;
+pub use transfer_hook::*;
@@ -0,0 +1,72 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_2022::spl_token_2022::{
+    extension::{transfer_hook::TransferHookAccount, BaseStateWithExtensions, StateWithExtensions},
+    state::Account as SplTokenAccount,
+};
+use anchor_spl::token_interface::{Mint, TokenAccount};
+use market_program::{MarketConfig, MarketStatus};
+
+use crate::errors::ErrorCode;
+use crate::states::*;
+
+// the account order is fixed by the transfer hook interface
+#[derive(Accounts)]
+pub struct TransferHook<'info> {
+    #[account(token::mint = mint)]
+    pub source_token: InterfaceAccount<'info, TokenAccount>,
+    pub mint: InterfaceAccount<'info, Mint>,
+    #[account(token::mint = mint)]
+    pub destination_token: InterfaceAccount<'info, TokenAccount>,
+    /// CHECK: the source owner or delegate, checked by the token program
+    pub owner: UncheckedAccount<'info>,
+
+    /// CHECK: the extra account meta list of the mint
+    #[account(
+        seeds = [EXTRA_ACCOUNT_METAS_SEED.as_bytes(), mint.key().as_ref()],
+        bump,
+    )]
+    pub extra_account_meta_list: UncheckedAccount<'info>,
+
+    #[account(
+        constraint = market_config.ct1_mint == mint.key() || market_config.ct2_mint == mint.key() @ ErrorCode::NotMarketMint
+    )]
+    pub market_config: Account<'info, MarketConfig>,
+
+    /// CHECK: the destination owner's allowlist entry, may not exist for unrestricted markets
+    #[account(
+        seeds = [
+            ALLOWLIST_SEED.as_bytes(),
+            market_config.key().as_ref(),
+            destination_token.owner.as_ref(),
+        ],
+        bump,
+    )]
+    pub allowlist_entry: UncheckedAccount<'info>,
+}
+
+pub fn transfer_hook(ctx: Context<TransferHook>, _amount: u64) -> Result<()> {
+    // only the token program may run the hook, it flags the source account while transferring
+    {
+        let source_info = ctx.accounts.source_token.to_account_info();
+        let source_data = source_info.try_borrow_data()?;
+        let source = StateWithExtensions::<SplTokenAccount>::unpack(&source_data)?;
+        if !bool::from(source.get_extension::<TransferHookAccount>()?.transferring) {
+            return Err(ErrorCode::NotTransferring.into());
+        }
+    }
+
+    let market_config = &ctx.accounts.market_config;
+    if market_config.transfers_frozen {
+        return Err(ErrorCode::TransfersFrozen.into());
+    }
+    if market_config.status == MarketStatus::Finalized {
+        return Err(ErrorCode::MarketFinalized.into());
+    }
+    if market_config.restricted {
+        let allowlist_entry = &ctx.accounts.allowlist_entry;
+        if allowlist_entry.owner != &crate::id() || allowlist_entry.data_is_empty() {
+            return Err(ErrorCode::RecipientNotAllowed.into());
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,32 @@
+// transfer hook of the conditional token mints, keeps secondary transfers within the market's rules.
+use anchor_lang::prelude::*;
+use spl_transfer_hook_interface::instruction::ExecuteInstruction;
+use spl_discriminator::SplDiscriminate;
+
+pub mod errors;
+pub mod instructions;
+pub mod states;
+
+pub use instructions::*;
+pub use states::*;
+
+
+declare_id!("HN7ixWKPXRgtcCkh3JWpvR8XxCVSpKoKggFnxmxEubKk");
+#[program]
+pub mod ct_transfer_hook {
+    use super::*;
+    pub fn initialize_extra_account_meta_list(ctx: Context<InitializeExtraAccountMetaList>) -> Result<()> {
+        instructions::initialize_extra_account_meta_list(ctx)
+    }
+    pub fn add_to_allowlist(ctx: Context<AddToAllowlist>, wallet: Pubkey) -> Result<()> {
+        instructions::add_to_allowlist(ctx, wallet)
+    }
+    pub fn remove_from_allowlist(ctx: Context<RemoveFromAllowlist>) -> Result<()> {
+        instructions::remove_from_allowlist(ctx)
+    }
+    // called by the token-2022 program on every transfer of a conditional token
+    #[instruction(discriminator = ExecuteInstruction::SPL_DISCRIMINATOR_SLICE)]
+    pub fn transfer_hook(ctx: Context<TransferHook>, amount: u64) -> Result<()> {
+        instructions::transfer_hook(ctx, amount)
+    }
+}
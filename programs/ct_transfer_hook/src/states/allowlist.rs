@@ -0,0 +1,14 @@
+use anchor_lang::prelude::*;
+
+pub const ALLOWLIST_SEED: &str = market_program::ALLOWLIST_SEED;
+pub const EXTRA_ACCOUNT_METAS_SEED: &str = market_program::EXTRA_ACCOUNT_METAS_SEED;
+
+/// A wallet allowed to receive conditional tokens of a restricted market
+#[account]
+#[derive(Default, Debug)]
+#[derive(InitSpace)]
+pub struct AllowlistEntry {
+    pub bump: u8,
+    pub market_config: Pubkey,
+    pub wallet: Pubkey,
+}
@@ -0,0 +1,2 @@
+pub mod allowlist;
+pub use allowlist::*;
@@ -46,4 +46,68 @@ pub enum ErrorCode {
     InvalidFeeModel,
     #[msg("Fee is zero")]
     NoFeeCollect,
+    #[msg("Market already resolved")]
+    MarketAlreadyResolved,
+    #[msg("Invalid resolution report")]
+    InvalidResolutionReport,
+    #[msg("Instruction not allowed in the current market status")]
+    InvalidMarketStatus,
+    #[msg("Illegal market status transition")]
+    InvalidStatusTransition,
+    #[msg("Invalid trading window")]
+    InvalidTradingWindow,
+    #[msg("Trading not open yet")]
+    TradingNotOpen,
+    #[msg("Trading halted before close")]
+    TradingHalted,
+    #[msg("Market metadata too long")]
+    MetadataTooLong,
+    #[msg("Market not on this registry page")]
+    InvalidRegistryPage,
+    #[msg("Market metadata cannot change after trading opens")]
+    MetadataFrozen,
+    #[msg("Bet below the collateral minimum")]
+    BetTooSmall,
+    #[msg("Bet exceeds the collateral open interest cap")]
+    OpenInterestExceeded,
+    #[msg("Market authority already migrated")]
+    AuthorityAlreadyMigrated,
+    #[msg("Vault balance does not cover its liabilities")]
+    VaultInsolvent,
+    #[msg("No treasury set for this collateral")]
+    TreasuryNotSet,
+    #[msg("Vault state already at the current version")]
+    VaultStateUpToDate,
+    #[msg("Winning conditional tokens are still outstanding")]
+    ClaimsOutstanding,
+    #[msg("Claim deadline passed")]
+    ClaimDeadlinePassed,
+    #[msg("Claim deadline not reached")]
+    ClaimDeadlineNotReached,
+    #[msg("Market already swept")]
+    AlreadySwept,
+    #[msg("Winning conditional tokens must be redeemed first")]
+    PositionNotEmpty,
+    #[msg("Claim accounts do not belong to this market or holder")]
+    InvalidClaimAccount,
+    #[msg("Too many entries in one batch")]
+    BatchTooLarge,
+    #[msg("Batch accounts do not match their entry")]
+    InvalidBatchAccount,
+    #[msg("Market config already at the current version")]
+    MarketConfigUpToDate,
+    #[msg("Start prices not recorded yet")]
+    StartPricePending,
+    #[msg("Registered markets must pass their registry page")]
+    RegistryPageMissing,
+    #[msg("Token-2022 conditional tokens need the transfer hook and their extra account meta lists")]
+    TransferHookAccountsMissing,
+    #[msg("Close the market's resolution config on the oracle adapter first")]
+    ResolutionConfigOpen,
+    #[msg("The market has a resolution config on the oracle adapter")]
+    ResolutionConfigExists,
+    #[msg("Fallback grace period not elapsed")]
+    FallbackGracePeriodActive,
+    #[msg("Restricted markets only accept bets from allowlisted wallets")]
+    BettorNotAllowed,
 }
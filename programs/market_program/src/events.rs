@@ -0,0 +1,215 @@
+/// Events emitted by the market program through `emit_cpi!`,
+/// enough for an indexer to rebuild the market state without parsing logs.
+use anchor_lang::prelude::*;
+
+use crate::states::{MarketCategory, MarketOutcome, MarketStatus};
+
+#[event]
+pub struct MarketCreated {
+    pub market_config: Pubkey,
+    pub index: u64,
+    pub category: MarketCategory,
+    pub owner: Pubkey,
+    pub name: String,
+    pub created_at: i64,
+    pub trading_opens_at: i64,
+    pub trading_closes_at: i64,
+    pub resolves_at: i64,
+    pub claim_window: Option<i64>,
+    pub metadata_uri: String,
+    pub rules_hash: [u8; 32],
+}
+
+#[event]
+pub struct MarketInitialized {
+    pub market_config: Pubkey,
+    pub vault_state: Pubkey,
+    pub vault: Pubkey,
+    pub creator: Pubkey,
+    pub collateral_mint: Pubkey,
+    pub ct1_mint: Pubkey,
+    pub ct2_mint: Pubkey,
+}
+
+#[event]
+pub struct BetBought {
+    pub market_config: Pubkey,
+    pub vault_state: Pubkey,
+    pub user: Pubkey,
+    pub collateral_mint: Pubkey,
+    pub ct1_mint: Pubkey,
+    pub ct2_mint: Pubkey,
+    /// collateral the vault received after the transfer fee, also the conditional tokens of each outcome minted
+    pub amount: u64,
+    /// vault collateral balance after the bet
+    pub vault_balance: u64,
+}
+
+#[event]
+pub struct BetSold {
+    pub market_config: Pubkey,
+    pub vault_state: Pubkey,
+    pub user: Pubkey,
+    pub collateral_mint: Pubkey,
+    pub ct1_mint: Pubkey,
+    pub ct2_mint: Pubkey,
+    /// conditional tokens of each outcome burned and collateral withdrawn
+    pub amount: u64,
+    /// collateral the user received after the transfer fee
+    pub collateral_received: u64,
+    /// vault collateral balance after the sale
+    pub vault_balance: u64,
+}
+
+#[event]
+pub struct RewardClaimed {
+    pub market_config: Pubkey,
+    pub vault_state: Pubkey,
+    pub user: Pubkey,
+    pub collateral_mint: Pubkey,
+    pub ct_mint: Pubkey,
+    /// conditional tokens burned
+    pub amount: u64,
+    /// collateral paid out
+    pub payout: u64,
+    /// collateral the user received after the transfer fee
+    pub collateral_received: u64,
+    /// vault collateral balance after the claim
+    pub vault_balance: u64,
+}
+
+#[event]
+pub struct MarketResolved {
+    pub market_config: Pubkey,
+    pub vault_state: Pubkey,
+    pub resolution_record: Pubkey,
+    pub outcome: MarketOutcome,
+    pub winning_ct_mint: Pubkey,
+    pub ct1_payout_bps: u16,
+    pub ct2_payout_bps: u16,
+    pub price: i64,
+    pub exponent: i32,
+    /// vault collateral balance at resolution
+    pub vault_balance: u64,
+}
+
+#[event]
+pub struct MarketStatusChanged {
+    pub market_config: Pubkey,
+    pub from: MarketStatus,
+    pub to: MarketStatus,
+}
+
+#[event]
+pub struct MarketMetadataUpdated {
+    pub market_config: Pubkey,
+    pub owner: Pubkey,
+    pub name: String,
+    pub description: String,
+    pub category: MarketCategory,
+    pub metadata_uri: String,
+    pub rules_hash: [u8; 32],
+}
+
+#[event]
+pub struct TransferRulesUpdated {
+    pub market_config: Pubkey,
+    pub transfers_frozen: bool,
+    pub restricted: bool,
+}
+
+#[event]
+pub struct CollateralInfoUpdated {
+    pub collateral_info: Pubkey,
+    pub mint: Pubkey,
+    pub enabled: bool,
+    pub max_open_interest: u64,
+    pub min_bet: u64,
+    pub allowed_extensions: u64,
+    pub treasury: Pubkey,
+}
+
+#[event]
+pub struct ConfigUpdated {
+    pub market_config: Pubkey,
+    pub owner: Pubkey,
+    pub param: u8,
+    pub value: u64,
+}
+
+#[event]
+pub struct PermissionChanged {
+    pub permission: Pubkey,
+    pub permission_authority: Pubkey,
+    /// true when the permission was created, false when it was closed
+    pub granted: bool,
+}
+
+#[event]
+pub struct MarketAuthorityMigrated {
+    pub market_config: Pubkey,
+    pub vault_state: Pubkey,
+    pub legacy_authority: Pubkey,
+    pub authority: Pubkey,
+}
+
+#[event]
+pub struct VaultReconciled {
+    pub market_config: Pubkey,
+    pub vault_state: Pubkey,
+    /// the vault's real token balance before any sweep
+    pub vault_balance: u64,
+    pub tracked_balance: u64,
+    /// collateral owed to conditional token holders
+    pub liability: u64,
+    pub surplus: u64,
+    pub shortfall: u64,
+    pub swept: u64,
+}
+
+#[event]
+pub struct PositionClosed {
+    pub market_config: Pubkey,
+    pub vault_state: Pubkey,
+    pub user: Pubkey,
+    /// conditional tokens burned without a payout, i.e. the user's realized loss
+    pub ct1_burned: u64,
+    pub ct2_burned: u64,
+    pub ct1_account_closed: bool,
+    pub ct2_account_closed: bool,
+}
+
+#[event]
+pub struct Swept {
+    pub market_config: Pubkey,
+    pub vault_state: Pubkey,
+    pub treasury: Pubkey,
+    /// unclaimed collateral moved out of the vault after the claim deadline
+    pub amount: u64,
+}
+
+#[event]
+pub struct VaultStateMigrated {
+    pub market_config: Pubkey,
+    pub vault_state: Pubkey,
+    pub from_version: u8,
+    pub to_version: u8,
+}
+
+#[event]
+pub struct MarketConfigMigrated {
+    pub market_config: Pubkey,
+    pub from_version: u8,
+    pub to_version: u8,
+}
+
+#[event]
+pub struct MarketClosed {
+    pub market_config: Pubkey,
+    pub vault_state: Pubkey,
+    pub owner: Pubkey,
+    /// collateral left in the vault that was sent to the treasury
+    pub dust: u64,
+    pub ct1_mint_closed: bool,
+    pub ct2_mint_closed: bool,
+}
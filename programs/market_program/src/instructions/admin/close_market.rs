@@ -0,0 +1,216 @@
+use crate::error::ErrorCode;
+use crate::events::*;
+use crate::states::*;
+use crate::utils::*;
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{close_account, CloseAccount, Mint, TokenAccount, TokenInterface};
+
+// permissionless, once nothing can be redeemed any more (or the market was cancelled) the market's accounts are closed
+// and their rent goes back to the market owner
+#[event_cpi]
+#[derive(Accounts)]
+pub struct CloseMarket<'info> {
+    /// CHECK: only receives the rent, must be the market owner who paid for the config and,
+    /// as initialize requires, for the vault state, vault and mints
+    #[account(mut, address = market_config.owner @ ErrorCode::InvalidOwner)]
+    pub owner: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        close = owner,
+        has_one = vault_state,
+        has_one = vault,
+        has_one = ct1_mint,
+        has_one = ct2_mint,
+    )]
+    pub market_config: Box<Account<'info, MarketConfig>>,
+
+    // the registry entry is marked closed, markets created before the registry have none
+    #[account(
+        mut,
+        seeds = [
+            MARKET_REGISTRY_SEED.as_bytes(),
+            &MarketRegistryPage::page_of(market_config.market_index).to_be_bytes()
+        ],
+        bump = market_registry_page.bump,
+    )]
+    pub market_registry_page: Option<Box<Account<'info, MarketRegistryPage>>>,
+
+    #[account(
+        mut,
+        close = owner,
+        seeds = [
+            VAULT_STATE_SEED.as_bytes(),
+            market_config.key().as_ref(),
+            collateral_mint.key().as_ref(),
+        ],
+        bump,
+        has_one = market_config,
+        has_one = vault,
+    )]
+    pub vault_state: AccountLoader<'info, VaultState>,
+
+    /// CHECK: the market's authority pda, owns the vault and may close the mints
+    #[account(
+        seeds = [
+            crate::AUTH_SEED.as_bytes(),
+            vault_state.key().as_ref(),
+        ],
+        bump = vault_state.load()?.auth_bump,
+    )]
+    pub authority: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        token::mint = collateral_mint,
+        token::authority = authority,
+        token::token_program = collateral_token_program,
+    )]
+    pub vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(mut, mint::token_program = token_program)]
+    pub ct1_mint: Box<InterfaceAccount<'info, Mint>>,
+    #[account(mut, mint::token_program = token_program)]
+    pub ct2_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    // receives the transfer fees withheld in the vault
+    #[account(mut, mint::token_program = collateral_token_program)]
+    pub collateral_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        seeds = [
+            COLLATERAL_INFO_SEED.as_bytes(),
+            collateral_mint.key().as_ref(),
+        ],
+        bump = collateral_info.bump,
+    )]
+    pub collateral_info: Box<Account<'info, CollateralInfo>>,
+
+    /// CHECK: the adapter's resolution config of this market, must already be closed
+    #[account(
+        seeds = [ADAPTER_RESOLUTION_CONFIG_SEED.as_bytes(), market_config.key().as_ref()],
+        bump,
+        seeds::program = ORACLE_ADAPTER_PROGRAM_ID,
+    )]
+    pub resolution_config: UncheckedAccount<'info>,
+    /// CHECK: the adapter's barrier tracker of this market, must already be closed or never have existed
+    #[account(
+        seeds = [ADAPTER_BARRIER_TRACKER_SEED.as_bytes(), market_config.key().as_ref()],
+        bump,
+        seeds::program = ORACLE_ADAPTER_PROGRAM_ID,
+    )]
+    pub barrier_tracker: UncheckedAccount<'info>,
+
+    // only needed when rounding dust or donations are left in the vault
+    #[account(
+        mut,
+        address = collateral_info.treasury @ ErrorCode::TreasuryNotSet,
+        token::mint = collateral_mint,
+        token::token_program = collateral_token_program,
+    )]
+    pub treasury: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub collateral_token_program: Interface<'info, TokenInterface>,
+}
+
+impl<'info> CloseMarket<'info> {
+    fn close(
+        &self,
+        token_program: &Interface<'info, TokenInterface>,
+        account: AccountInfo<'info>,
+        authority_seeds: &[&[u8]],
+    ) -> Result<()> {
+        close_account(CpiContext::new_with_signer(
+            token_program.to_account_info(),
+            CloseAccount {
+                account,
+                destination: self.owner.to_account_info(),
+                authority: self.authority.to_account_info(),
+            },
+            &[authority_seeds],
+        ))
+    }
+}
+
+pub fn close_market(ctx: Context<CloseMarket>) -> Result<()> {
+    ctx.accounts
+        .market_config
+        .status
+        .require_one_of(&[MarketStatus::Finalized, MarketStatus::Invalid, MarketStatus::Cancelled])?;
+    // the adapter's accounts point at the market config, which must not be closed before them.
+    // lamports sent to a closed address don't count, only the adapter can give it data again
+    for account in [&ctx.accounts.resolution_config, &ctx.accounts.barrier_tracker] {
+        if !account.data_is_empty() {
+            return Err(ErrorCode::ResolutionConfigOpen.into());
+        }
+    }
+
+    let vault_state_key = ctx.accounts.vault_state.key();
+    let auth_bump = {
+        let vault_state = ctx.accounts.vault_state.load()?;
+        let claims_closed = ctx.accounts.market_config.claims_closed(Clock::get()?.unix_timestamp);
+        if !claims_closed && vault_state.has_outstanding_claims(ctx.accounts.ct1_mint.supply, ctx.accounts.ct2_mint.supply) {
+            return Err(ErrorCode::ClaimsOutstanding.into());
+        }
+        vault_state.auth_bump
+    };
+    let authority_seeds: &[&[u8]] = &[crate::AUTH_SEED.as_bytes(), vault_state_key.as_ref(), &[auth_bump]];
+
+    // no position can claim what is left, e.g. unclaimed winnings after the deadline,
+    // it has to go before the vault can be closed
+    let dust = ctx.accounts.vault.amount;
+    if dust > 0 {
+        let treasury = ctx.accounts.treasury.as_ref().ok_or(ErrorCode::TreasuryNotSet)?;
+        transfer_from_collateral_vault_to_user(
+            ctx.accounts.authority.to_account_info(),
+            ctx.accounts.vault.to_account_info(),
+            treasury.to_account_info(),
+            ctx.accounts.collateral_mint.to_account_info(),
+            ctx.accounts.collateral_token_program.to_account_info(),
+            dust,
+            ctx.accounts.collateral_mint.decimals,
+            &[authority_seeds],
+        )?;
+    }
+    // transfer fee collateral leaves the fees of every transfer into the vault withheld in it
+    harvest_withheld_fees(
+        ctx.accounts.collateral_token_program.to_account_info(),
+        ctx.accounts.collateral_mint.to_account_info(),
+        ctx.accounts.vault.to_account_info(),
+    )?;
+    ctx.accounts.close(
+        &ctx.accounts.collateral_token_program,
+        ctx.accounts.vault.to_account_info(),
+        authority_seeds,
+    )?;
+
+    // losing tokens nobody burned keep their mint alive, as do mints created without a close authority
+    let mut mints_closed = [false; 2];
+    for (closed, mint) in mints_closed.iter_mut().zip([&ctx.accounts.ct1_mint, &ctx.accounts.ct2_mint]) {
+        let mint_info = mint.to_account_info();
+        if mint.supply == 0 && mint_close_authority(&mint_info)? == Some(ctx.accounts.authority.key()) {
+            ctx.accounts.close(&ctx.accounts.token_program, mint_info, authority_seeds)?;
+            *closed = true;
+        }
+    }
+
+    let market_config_key = ctx.accounts.market_config.key();
+    if let Some(entry) = MarketRegistryPage::sync(
+        ctx.accounts.market_registry_page.as_deref_mut().map(|page| &mut **page),
+        market_config_key,
+        &ctx.accounts.market_config,
+    )? {
+        entry.closed = true;
+    }
+
+    emit_cpi!(MarketClosed {
+        market_config: ctx.accounts.market_config.key(),
+        vault_state: vault_state_key,
+        owner: ctx.accounts.owner.key(),
+        dust,
+        ct1_mint_closed: mints_closed[0],
+        ct2_mint_closed: mints_closed[1],
+    });
+    Ok(())
+}
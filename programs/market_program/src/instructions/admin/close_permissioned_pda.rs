@@ -1,6 +1,8 @@
 use anchor_lang::prelude::*;
 use crate::states::*;
+use crate::events::*;
 
+#[event_cpi]
 #[derive(Accounts)]
 pub struct ClosePermissionPda<'info> {
     #[account(
@@ -28,6 +30,11 @@ pub struct ClosePermissionPda<'info> {
 }
 
 
-pub fn close_permission_pda(_ctx: Context<ClosePermissionPda>) -> Result<()> {
+pub fn close_permission_pda(ctx: Context<ClosePermissionPda>) -> Result<()> {
+    emit_cpi!(PermissionChanged {
+        permission: ctx.accounts.permission.key(),
+        permission_authority: ctx.accounts.permission_authority.key(),
+        granted: false,
+    });
     Ok(())
 }
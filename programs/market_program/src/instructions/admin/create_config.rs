@@ -1,35 +1,95 @@
 use std::ops::DerefMut;
 use anchor_lang::prelude::*;
 use crate::states::*;
+use crate::events::*;
 // config_account account validation and create_config instruction handler
+#[event_cpi]
 #[derive(Accounts)]
-#[instruction(index: u16)]
 pub struct CreateMarketConfig<'info> {
     #[account(mut)]
     pub signer: Signer<'info>,
+    // the counter only ever increments, so creating it on first use is safe
     #[account(
         init_if_needed,
+        seeds = [MARKET_COUNTER_SEED.as_bytes()],
+        bump,
+        payer = signer,
+        space = 8 + MarketCounter::INIT_SPACE,
+    )]
+    pub market_counter: Account<'info, MarketCounter>,
+    #[account(
+        init,
         seeds = [
-            MARKET_CONFIG_SEED.as_bytes(),
-            &index.to_be_bytes()
+            INDEXED_MARKET_CONFIG_SEED.as_bytes(),
+            &market_counter.count.to_be_bytes()
         ],
         bump,
         payer = signer,
         space = 8 + MarketConfig::INIT_SPACE,
     )]
     pub market_config: Account<'info, MarketConfig>,
+    #[account(
+        init_if_needed,
+        seeds = [
+            MARKET_REGISTRY_SEED.as_bytes(),
+            &MarketRegistryPage::page_of(market_counter.count).to_be_bytes()
+        ],
+        bump,
+        payer = signer,
+        space = 8 + MarketRegistryPage::INIT_SPACE,
+    )]
+    pub market_registry_page: Account<'info, MarketRegistryPage>,
 
     pub system_program: Program<'info, System>,
 }
 // called by the admin
-pub fn create_market_config(ctx: Context<CreateMarketConfig>, index: u16, name: String, description: String, expiration: i64) -> Result<()> {
+pub fn create_market_config(ctx: Context<CreateMarketConfig>, name: String, description: String, category: MarketCategory, metadata: MarketMetadata, window: TradingWindow) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+    window.validate(now)?;
+    validate_metadata(&name, &description)?;
+    metadata.validate()?;
+
+    let market_counter = ctx.accounts.market_counter.deref_mut();
+    market_counter.bump = ctx.bumps.market_counter;
+    let index = market_counter.next_index()?;
+
     let market_config = ctx.accounts.market_config.deref_mut();
     market_config.bump = ctx.bumps.market_config;
-    market_config.index = index;
+    market_config.market_index = index;
     market_config.owner = ctx.accounts.signer.key();
     market_config.name = name;
     market_config.description = description;
-    market_config.created_at = Clock::get()?.unix_timestamp;
-    market_config.expiration = expiration;
+    market_config.created_at = now;
+    market_config.trading_opens_at = window.trading_opens_at;
+    market_config.trading_closes_at = window.trading_closes_at;
+    market_config.resolves_at = window.resolves_at;
+    market_config.halt_buffer = window.halt_buffer;
+    market_config.claim_window = window.claim_window.unwrap_or_default();
+    market_config.version = MARKET_CONFIG_VERSION;
+    market_config.registered = true;
+    market_config.category = category;
+    market_config.metadata = metadata;
+
+    let market_config_key = ctx.accounts.market_config.key();
+    let entry = RegistryEntry::from_config(market_config_key, &ctx.accounts.market_config);
+    let market_registry_page = ctx.accounts.market_registry_page.deref_mut();
+    market_registry_page.bump = ctx.bumps.market_registry_page;
+    market_registry_page.page = MarketRegistryPage::page_of(index);
+    market_registry_page.push(index, entry)?;
+
+    emit_cpi!(MarketCreated {
+        market_config: market_config_key,
+        index,
+        category,
+        owner: ctx.accounts.signer.key(),
+        name: ctx.accounts.market_config.name.clone(),
+        created_at: now,
+        trading_opens_at: window.trading_opens_at,
+        trading_closes_at: window.trading_closes_at,
+        resolves_at: window.resolves_at,
+        claim_window: window.claim_window,
+        metadata_uri: ctx.accounts.market_config.metadata.metadata_uri.clone(),
+        rules_hash: ctx.accounts.market_config.metadata.rules_hash,
+    });
     Ok(())
-}
\ No newline at end of file
+}
@@ -1,7 +1,9 @@
 use crate::states::*;
+use crate::events::*;
 use anchor_lang::prelude::*;
 use std::ops::DerefMut;
 
+#[event_cpi]
 #[derive(Accounts)]
 pub struct CreatePermissionPda<'info> {
     #[account(
@@ -32,5 +34,11 @@ pub struct CreatePermissionPda<'info> {
 pub fn create_permission_pda(ctx: Context<CreatePermissionPda>) -> Result<()> {
     let permission = ctx.accounts.permission.deref_mut();
     permission.authority = ctx.accounts.permission_authority.key();
+
+    emit_cpi!(PermissionChanged {
+        permission: ctx.accounts.permission.key(),
+        permission_authority: ctx.accounts.permission_authority.key(),
+        granted: true,
+    });
     Ok(())
 }
@@ -0,0 +1,39 @@
+use crate::states::*;
+use crate::events::*;
+use anchor_lang::prelude::*;
+
+// moves a resolved market to finalized, after which its outcome can no longer change
+#[event_cpi]
+#[derive(Accounts)]
+pub struct FinalizeMarket<'info> {
+    /// The market config owner
+    pub owner: Signer<'info>,
+
+    #[account(mut, has_one = owner)]
+    pub market_config: Account<'info, MarketConfig>,
+
+    // the registry entry follows the status change, markets created before the registry have none
+    #[account(
+        mut,
+        seeds = [
+            MARKET_REGISTRY_SEED.as_bytes(),
+            &MarketRegistryPage::page_of(market_config.market_index).to_be_bytes()
+        ],
+        bump = market_registry_page.bump,
+    )]
+    pub market_registry_page: Option<Account<'info, MarketRegistryPage>>,
+}
+
+pub fn finalize_market(ctx: Context<FinalizeMarket>) -> Result<()> {
+    let market_config = &mut ctx.accounts.market_config;
+    let from = market_config.status;
+    market_config.status.transition(MarketStatus::Finalized)?;
+    MarketRegistryPage::sync(ctx.accounts.market_registry_page.as_deref_mut(), market_config.key(), market_config)?;
+
+    emit_cpi!(MarketStatusChanged {
+        market_config: market_config.key(),
+        from,
+        to: market_config.status,
+    });
+    Ok(())
+}
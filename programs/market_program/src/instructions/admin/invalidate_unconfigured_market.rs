@@ -0,0 +1,117 @@
+use std::ops::DerefMut;
+
+use crate::states::*;
+use crate::error::ErrorCode;
+use crate::events::*;
+use anchor_lang::prelude::*;
+
+
+// invalidate_unconfigured_market account validation, permissionless fallback for markets
+// the oracle adapter can never resolve because their resolution config was never created
+#[event_cpi]
+#[derive(Accounts)]
+pub struct InvalidateUnconfiguredMarket<'info> {
+    /// pays for the resolution record
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    #[account(mut)]
+    pub market_config: Account<'info, MarketConfig>,
+
+    // the registry entry follows the status change, markets created before the registry have none
+    #[account(
+        mut,
+        seeds = [
+            MARKET_REGISTRY_SEED.as_bytes(),
+            &MarketRegistryPage::page_of(market_config.market_index).to_be_bytes()
+        ],
+        bump = market_registry_page.bump,
+    )]
+    pub market_registry_page: Option<Account<'info, MarketRegistryPage>>,
+
+    #[account(
+        mut,
+        constraint = vault_state.key() == market_config.vault_state @ ErrorCode::InvalidVault
+    )]
+    pub vault_state: AccountLoader<'info, VaultState>,
+
+    /// CHECK: the adapter's resolution config of this market, must never have been created
+    #[account(
+        seeds = [ADAPTER_RESOLUTION_CONFIG_SEED.as_bytes(), market_config.key().as_ref()],
+        bump,
+        seeds::program = ORACLE_ADAPTER_PROGRAM_ID,
+    )]
+    pub resolution_config: UncheckedAccount<'info>,
+
+    #[account(
+        init,
+        seeds = [
+            RESOLUTION_RECORD_SEED.as_bytes(),
+            market_config.key().as_ref(),
+        ],
+        bump,
+        payer = payer,
+        space = 8 + ResolutionRecord::INIT_SPACE,
+    )]
+    pub resolution_record: Account<'info, ResolutionRecord>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn invalidate_unconfigured_market(ctx: Context<InvalidateUnconfiguredMarket>) -> Result<()> {
+    // a configured market is resolved or force invalidated through the adapter
+    if !ctx.accounts.resolution_config.data_is_empty() {
+        return Err(ErrorCode::ResolutionConfigExists.into());
+    }
+
+    let clock = Clock::get()?;
+    let market_config_key = ctx.accounts.market_config.key();
+    let market_config = ctx.accounts.market_config.deref_mut();
+    if market_config.status.is_redeemable() {
+        return Err(ErrorCode::MarketAlreadyResolved.into());
+    }
+    if clock.unix_timestamp < market_config.resolves_at.saturating_add(UNCONFIGURED_MARKET_GRACE_PERIOD) {
+        return Err(ErrorCode::FallbackGracePeriodActive.into());
+    }
+    market_config.sync_status(clock.unix_timestamp)?;
+    market_config.status.require_one_of(&[MarketStatus::Closed])?;
+
+    let outcome = MarketOutcome::Invalid;
+    let (ct1_payout_bps, ct2_payout_bps) = outcome.payout_bps();
+
+    let mut vault_state = ctx.accounts.vault_state.load_mut()?;
+    vault_state.resolve_market(Pubkey::default(), ct1_payout_bps, ct2_payout_bps)?;
+    market_config.status.transition(MarketStatus::Invalid)?;
+    market_config.market_resolution = true;
+    market_config.resolved_at = clock.unix_timestamp;
+    MarketRegistryPage::sync(ctx.accounts.market_registry_page.as_deref_mut(), market_config_key, market_config)?;
+
+    // no price was observed, the price fields stay zeroed
+    let resolution_record = ctx.accounts.resolution_record.deref_mut();
+    resolution_record.bump = ctx.bumps.resolution_record;
+    resolution_record.market_config = market_config_key;
+    resolution_record.vault_state = ctx.accounts.vault_state.key();
+    resolution_record.source = ResolutionSource::NoResolutionConfig;
+    resolution_record.publish_time = clock.unix_timestamp;
+    resolution_record.slot = clock.slot;
+    resolution_record.resolved_at = clock.unix_timestamp;
+    resolution_record.resolver = ctx.accounts.payer.key();
+    resolution_record.outcome = outcome;
+    resolution_record.winning_ct_mint = Pubkey::default();
+    resolution_record.ct1_payout_bps = ct1_payout_bps;
+    resolution_record.ct2_payout_bps = ct2_payout_bps;
+
+    emit_cpi!(MarketResolved {
+        market_config: market_config_key,
+        vault_state: ctx.accounts.vault_state.key(),
+        resolution_record: ctx.accounts.resolution_record.key(),
+        outcome,
+        winning_ct_mint: Pubkey::default(),
+        ct1_payout_bps,
+        ct2_payout_bps,
+        price: 0,
+        exponent: 0,
+        vault_balance: vault_state.vault_collateral_balance,
+    });
+    Ok(())
+}
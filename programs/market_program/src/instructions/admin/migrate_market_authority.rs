@@ -0,0 +1,180 @@
+use crate::error::ErrorCode;
+use crate::events::*;
+use crate::states::*;
+use crate::utils::*;
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::program_option::COption;
+use anchor_spl::metadata::{update_metadata_accounts_v2, Metadata, UpdateMetadataAccountsV2};
+use anchor_spl::token_2022::spl_token_2022::instruction::AuthorityType;
+use spl_token_2022::extension::ExtensionType;
+use anchor_spl::token_interface::{
+    set_authority, spl_pod::optional_keys::OptionalNonZeroPubkey, token_metadata_update_authority,
+    Mint, SetAuthority, TokenAccount, TokenInterface, TokenMetadataUpdateAuthority,
+};
+
+// permissionless, moves a market created under the global authority onto its own authority pda
+#[event_cpi]
+#[derive(Accounts)]
+pub struct MigrateMarketAuthority<'info> {
+    #[account(
+        has_one = vault_state,
+        has_one = vault,
+        has_one = ct1_mint,
+        has_one = ct2_mint,
+    )]
+    pub market_config: Box<Account<'info, MarketConfig>>,
+
+    #[account(mut)]
+    pub vault_state: AccountLoader<'info, VaultState>,
+
+    /// CHECK: the global authority pda that markets used before per market authorities
+    #[account(
+        seeds = [
+            crate::AUTH_SEED.as_bytes(),
+        ],
+        bump,
+    )]
+    pub legacy_authority: UncheckedAccount<'info>,
+
+    /// CHECK: the market's own authority pda, takes over from the legacy authority
+    #[account(
+        seeds = [
+            crate::AUTH_SEED.as_bytes(),
+            vault_state.key().as_ref(),
+        ],
+        bump,
+    )]
+    pub authority: UncheckedAccount<'info>,
+
+    #[account(mut, mint::token_program = token_program)]
+    pub ct1_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(mut, mint::token_program = token_program)]
+    pub ct2_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(mut, token::token_program = collateral_token_program)]
+    pub vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub collateral_token_program: Interface<'info, TokenInterface>,
+
+    // only needed for conditional tokens on the legacy token program that carry metaplex metadata
+    /// CHECK: the metaplex metadata account of ct1, validated by the metadata program
+    #[account(mut)]
+    pub ct1_metadata: Option<UncheckedAccount<'info>>,
+    /// CHECK: the metaplex metadata account of ct2, validated by the metadata program
+    #[account(mut)]
+    pub ct2_metadata: Option<UncheckedAccount<'info>>,
+    pub metadata_program: Option<Program<'info, Metadata>>,
+}
+
+impl<'info> MigrateMarketAuthority<'info> {
+    fn set_authority(
+        &self,
+        token_program: &AccountInfo<'info>,
+        account_or_mint: &AccountInfo<'info>,
+        authority_type: AuthorityType,
+        legacy_seeds: &[&[u8]],
+    ) -> Result<()> {
+        set_authority(
+            CpiContext::new_with_signer(
+                token_program.clone(),
+                SetAuthority {
+                    current_authority: self.legacy_authority.to_account_info(),
+                    account_or_mint: account_or_mint.clone(),
+                },
+                &[legacy_seeds],
+            ),
+            authority_type,
+            Some(self.authority.key()),
+        )
+    }
+
+    /// Hands every authority the legacy pda holds over a conditional token mint to the market authority
+    fn migrate_ct_mint(
+        &self,
+        mint: &AccountInfo<'info>,
+        metadata: Option<&UncheckedAccount<'info>>,
+        legacy_seeds: &[&[u8]],
+    ) -> Result<()> {
+        let token_program = self.token_program.to_account_info();
+        // the first conditional token mints had no extensions, only move the authorities a mint has
+        let extensions = mint_extension_types(mint)?;
+        if extensions.contains(&ExtensionType::MetadataPointer) {
+            self.set_authority(&token_program, mint, AuthorityType::MetadataPointer, legacy_seeds)?;
+        }
+        if extensions.contains(&ExtensionType::TransferHook) {
+            self.set_authority(&token_program, mint, AuthorityType::TransferHookProgramId, legacy_seeds)?;
+        }
+        if extensions.contains(&ExtensionType::TokenMetadata) {
+            token_metadata_update_authority(
+                CpiContext::new_with_signer(
+                    token_program.clone(),
+                    TokenMetadataUpdateAuthority {
+                        program_id: token_program.clone(),
+                        metadata: mint.clone(),
+                        current_authority: self.legacy_authority.to_account_info(),
+                        new_authority: self.authority.to_account_info(),
+                    },
+                    &[legacy_seeds],
+                ),
+                OptionalNonZeroPubkey::try_from(Some(self.authority.key()))?,
+            )?;
+        } else if let (Some(metadata), Some(metadata_program)) = (metadata, &self.metadata_program) {
+            update_metadata_accounts_v2(
+                CpiContext::new_with_signer(
+                    metadata_program.to_account_info(),
+                    UpdateMetadataAccountsV2 {
+                        metadata: metadata.to_account_info(),
+                        update_authority: self.legacy_authority.to_account_info(),
+                    },
+                    &[legacy_seeds],
+                ),
+                Some(self.authority.key()),
+                None,
+                None,
+                None,
+            )?;
+        }
+        self.set_authority(&token_program, mint, AuthorityType::MintTokens, legacy_seeds)
+    }
+}
+
+pub fn migrate_market_authority(ctx: Context<MigrateMarketAuthority>) -> Result<()> {
+    let legacy_authority = ctx.accounts.legacy_authority.key();
+    // markets created with per market authorities, or already migrated, have nothing to move
+    for mint in [&ctx.accounts.ct1_mint, &ctx.accounts.ct2_mint] {
+        if mint.mint_authority != COption::Some(legacy_authority) {
+            return Err(ErrorCode::AuthorityAlreadyMigrated.into());
+        }
+    }
+    require_keys_eq!(ctx.accounts.vault.owner, legacy_authority, ErrorCode::AuthorityAlreadyMigrated);
+
+    let legacy_seeds: &[&[u8]] = &[crate::AUTH_SEED.as_bytes(), &[ctx.bumps.legacy_authority]];
+    ctx.accounts.migrate_ct_mint(
+        &ctx.accounts.ct1_mint.to_account_info(),
+        ctx.accounts.ct1_metadata.as_ref(),
+        legacy_seeds,
+    )?;
+    ctx.accounts.migrate_ct_mint(
+        &ctx.accounts.ct2_mint.to_account_info(),
+        ctx.accounts.ct2_metadata.as_ref(),
+        legacy_seeds,
+    )?;
+    ctx.accounts.set_authority(
+        &ctx.accounts.collateral_token_program.to_account_info(),
+        &ctx.accounts.vault.to_account_info(),
+        AuthorityType::AccountOwner,
+        legacy_seeds,
+    )?;
+
+    ctx.accounts.vault_state.load_mut()?.auth_bump = ctx.bumps.authority;
+
+    emit_cpi!(MarketAuthorityMigrated {
+        market_config: ctx.accounts.market_config.key(),
+        vault_state: ctx.accounts.vault_state.key(),
+        legacy_authority,
+        authority: ctx.accounts.authority.key(),
+    });
+    Ok(())
+}
@@ -0,0 +1,54 @@
+use crate::events::*;
+use crate::states::*;
+use anchor_lang::{prelude::*, system_program};
+
+// permissionless, grows a market config written by the first deployed program to the current layout
+// and fills in the fields it lacks, the payer funds the extra rent
+#[event_cpi]
+#[derive(Accounts)]
+pub struct MigrateMarketConfig<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    /// CHECK: may still be too small to deserialize, the discriminator is checked when it is deserialized
+    #[account(mut, owner = crate::ID)]
+    pub market_config: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn migrate_market_config(ctx: Context<MigrateMarketConfig>) -> Result<()> {
+    let market_config_info = ctx.accounts.market_config.to_account_info();
+    let len = 8 + MarketConfig::INIT_SPACE;
+    if market_config_info.data_len() < len {
+        let required_lamports = Rent::get()?
+            .minimum_balance(len)
+            .saturating_sub(market_config_info.lamports());
+        if required_lamports > 0 {
+            system_program::transfer(
+                CpiContext::new(
+                    ctx.accounts.system_program.to_account_info(),
+                    system_program::Transfer {
+                        from: ctx.accounts.payer.to_account_info(),
+                        to: market_config_info.clone(),
+                    },
+                ),
+                required_lamports,
+            )?;
+        }
+        // the appended bytes are zeroed, which reads as the default category and empty metadata
+        market_config_info.resize(len)?;
+    }
+
+    let mut market_config = MarketConfig::try_deserialize(&mut &market_config_info.try_borrow_data()?[..])?;
+    let from_version = market_config.version;
+    market_config.upgrade()?;
+    market_config.try_serialize(&mut &mut market_config_info.try_borrow_mut_data()?[..])?;
+
+    emit_cpi!(MarketConfigMigrated {
+        market_config: market_config_info.key(),
+        from_version,
+        to_version: market_config.version,
+    });
+    Ok(())
+}
@@ -0,0 +1,61 @@
+use crate::events::*;
+use crate::states::*;
+use crate::utils::*;
+use anchor_lang::{prelude::*, system_program};
+
+// permissionless, grows a vault state written by an older program to the current layout,
+// the payer funds the extra rent
+#[event_cpi]
+#[derive(Accounts)]
+pub struct MigrateVaultState<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    #[account(has_one = vault_state)]
+    pub market_config: Box<Account<'info, MarketConfig>>,
+
+    /// CHECK: may still be too small for AccountLoader, owner and discriminator are checked in the handler
+    #[account(mut)]
+    pub vault_state: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn migrate_vault_state(ctx: Context<MigrateVaultState>) -> Result<()> {
+    let vault_state_info = ctx.accounts.vault_state.to_account_info();
+    // checks the owner and the VaultState discriminator
+    AccountLoad::<VaultState>::try_from(&vault_state_info)?;
+
+    if vault_state_info.data_len() < VaultState::LEN {
+        let required_lamports = Rent::get()?
+            .minimum_balance(VaultState::LEN)
+            .saturating_sub(vault_state_info.lamports());
+        if required_lamports > 0 {
+            system_program::transfer(
+                CpiContext::new(
+                    ctx.accounts.system_program.to_account_info(),
+                    system_program::Transfer {
+                        from: ctx.accounts.payer.to_account_info(),
+                        to: vault_state_info.clone(),
+                    },
+                ),
+                required_lamports,
+            )?;
+        }
+        // the appended bytes are zeroed
+        vault_state_info.resize(VaultState::LEN)?;
+    }
+
+    let vault_state_loader = AccountLoad::<VaultState>::try_from(&vault_state_info)?;
+    let mut vault_state = vault_state_loader.load_mut()?;
+    let from_version = vault_state.version;
+    vault_state.upgrade()?;
+
+    emit_cpi!(VaultStateMigrated {
+        market_config: ctx.accounts.market_config.key(),
+        vault_state: ctx.accounts.vault_state.key(),
+        from_version,
+        to_version: vault_state.version,
+    });
+    Ok(())
+}
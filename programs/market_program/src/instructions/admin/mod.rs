@@ -11,4 +11,46 @@ pub mod update_market_config;
 pub use update_market_config::*;
 
 pub mod resolve_market;
-pub use resolve_market::*;
\ No newline at end of file
+pub use resolve_market::*;
+
+pub mod invalidate_unconfigured_market;
+pub use invalidate_unconfigured_market::*;
+
+pub mod set_start_price_pending;
+pub use set_start_price_pending::*;
+
+pub mod finalize_market;
+pub use finalize_market::*;
+
+pub mod cancel_market;
+pub use cancel_market::*;
+
+pub mod update_market_metadata;
+pub use update_market_metadata::*;
+
+pub mod sync_registry_entry;
+pub use sync_registry_entry::*;
+
+pub mod set_transfer_rules;
+pub use set_transfer_rules::*;
+
+pub mod set_collateral_info;
+pub use set_collateral_info::*;
+
+pub mod migrate_market_authority;
+pub use migrate_market_authority::*;
+
+pub mod reconcile_vault;
+pub use reconcile_vault::*;
+
+pub mod migrate_vault_state;
+pub use migrate_vault_state::*;
+
+pub mod migrate_market_config;
+pub use migrate_market_config::*;
+
+pub mod close_market;
+pub use close_market::*;
+
+pub mod sweep_unclaimed;
+pub use sweep_unclaimed::*;
@@ -0,0 +1,124 @@
+use crate::error::ErrorCode;
+use crate::events::*;
+use crate::states::*;
+use crate::utils::*;
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+// permissionless, compares a vault's real balance with what it tracks and owes,
+// and optionally sweeps collateral nobody can redeem (e.g. direct donations) to the treasury
+#[event_cpi]
+#[derive(Accounts)]
+pub struct ReconcileVault<'info> {
+    #[account(
+        has_one = vault_state,
+        has_one = vault,
+        has_one = ct1_mint,
+        has_one = ct2_mint,
+    )]
+    pub market_config: Box<Account<'info, MarketConfig>>,
+
+    #[account(
+        seeds = [
+            VAULT_STATE_SEED.as_bytes(),
+            market_config.key().as_ref(),
+            collateral_mint.key().as_ref(),
+        ],
+        bump,
+        has_one = market_config,
+        has_one = vault,
+    )]
+    pub vault_state: AccountLoader<'info, VaultState>,
+
+    /// CHECK: the market's authority pda, signs the sweep
+    #[account(
+        seeds = [
+            crate::AUTH_SEED.as_bytes(),
+            vault_state.key().as_ref(),
+        ],
+        bump = vault_state.load()?.auth_bump,
+    )]
+    pub authority: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        token::mint = collateral_mint,
+        token::authority = authority,
+        token::token_program = collateral_token_program,
+    )]
+    pub vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    pub ct1_mint: Box<InterfaceAccount<'info, Mint>>,
+    pub ct2_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(mint::token_program = collateral_token_program)]
+    pub collateral_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        seeds = [
+            COLLATERAL_INFO_SEED.as_bytes(),
+            collateral_mint.key().as_ref(),
+        ],
+        bump = collateral_info.bump,
+    )]
+    pub collateral_info: Box<Account<'info, CollateralInfo>>,
+
+    // only needed to sweep
+    #[account(
+        mut,
+        address = collateral_info.treasury @ ErrorCode::TreasuryNotSet,
+        token::mint = collateral_mint,
+        token::token_program = collateral_token_program,
+    )]
+    pub treasury: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+
+    pub collateral_token_program: Interface<'info, TokenInterface>,
+}
+
+pub fn reconcile_vault(ctx: Context<ReconcileVault>, sweep: bool) -> Result<()> {
+    let vault_state_key = ctx.accounts.vault_state.key();
+    let vault_state = ctx.accounts.vault_state.load()?;
+
+    let vault_balance = ctx.accounts.vault.amount;
+    let tracked_balance = vault_state.vault_collateral_balance;
+    let liability = vault_state.liability(ctx.accounts.ct1_mint.supply, ctx.accounts.ct2_mint.supply)?;
+    // anything above both the tracked balance and the liability belongs to no position
+    let reserved = tracked_balance.max(liability);
+    let surplus = vault_balance.saturating_sub(reserved);
+    let shortfall = reserved.saturating_sub(vault_balance);
+
+    let mut swept = 0;
+    if sweep && surplus > 0 {
+        let treasury = ctx.accounts.treasury.as_ref().ok_or(ErrorCode::TreasuryNotSet)?;
+        transfer_from_collateral_vault_to_user(
+            ctx.accounts.authority.to_account_info(),
+            ctx.accounts.vault.to_account_info(),
+            treasury.to_account_info(),
+            ctx.accounts.collateral_mint.to_account_info(),
+            ctx.accounts.collateral_token_program.to_account_info(),
+            surplus,
+            ctx.accounts.collateral_mint.decimals,
+            &[&[crate::AUTH_SEED.as_bytes(), vault_state_key.as_ref(), &[vault_state.auth_bump]]],
+        )?;
+        swept = surplus;
+
+        ctx.accounts.vault.reload()?;
+        vault_state.check_solvency(
+            ctx.accounts.vault.amount,
+            ctx.accounts.ct1_mint.supply,
+            ctx.accounts.ct2_mint.supply,
+        )?;
+    }
+
+    emit_cpi!(VaultReconciled {
+        market_config: ctx.accounts.market_config.key(),
+        vault_state: vault_state_key,
+        vault_balance,
+        tracked_balance,
+        liability,
+        surplus,
+        shortfall,
+        swept,
+    });
+    Ok(())
+}
@@ -1,42 +1,132 @@
 use std::ops::DerefMut;
 
 use crate::states::*;
+use crate::error::ErrorCode;
+use crate::events::*;
 use anchor_lang::prelude::*;
 
 
-// config_account account validation and create_config instruction handler
+// resolve_market account validation, only reachable through the oracle adapter cpi
+#[event_cpi]
 #[derive(Accounts)]
-#[instruction(index: u16)]
 pub struct ResolveMarket<'info> {
-    // TODO : change this key with the oracle adapter contract
-    /// CHECK: oracle adapter pda account
+    /// pays for the resolution record, forwarded by the oracle adapter
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    /// the oracle adapter pda, it can only sign through the adapter program
     #[account(
-        seeds = [b"oracle-adapter-pda"],
-        bump = oracle_adapter_pda.bump,
-        owner = pubkey!("11111111111111111111111111111111")
+        seeds = [ORACLE_AUTHORITY_SEED.as_bytes()],
+        bump,
+        seeds::program = ORACLE_ADAPTER_PROGRAM_ID,
     )]
-    pub oracle_adapter_pda: Account<'info, OracleAuthority>,
+    pub oracle_authority: Signer<'info>,
 
     #[account(mut)]
     pub market_config: Account<'info, MarketConfig>,
 
-    #[account(mut)]
+    // the registry entry follows the status change, markets created before the registry have none
+    #[account(
+        mut,
+        seeds = [
+            MARKET_REGISTRY_SEED.as_bytes(),
+            &MarketRegistryPage::page_of(market_config.market_index).to_be_bytes()
+        ],
+        bump = market_registry_page.bump,
+    )]
+    pub market_registry_page: Option<Account<'info, MarketRegistryPage>>,
+
+    #[account(
+        mut,
+        constraint = vault_state.key() == market_config.vault_state @ ErrorCode::InvalidVault
+    )]
     pub vault_state: AccountLoader<'info, VaultState>,
+
+    #[account(
+        init,
+        seeds = [
+            RESOLUTION_RECORD_SEED.as_bytes(),
+            market_config.key().as_ref(),
+        ],
+        bump,
+        payer = payer,
+        space = 8 + ResolutionRecord::INIT_SPACE,
+    )]
+    pub resolution_record: Account<'info, ResolutionRecord>,
+
+    pub system_program: Program<'info, System>,
 }
-// called by the admin
-pub fn resolve_market(ctx: Context<ResolveMarket>) -> Result<()> {
-    let mut vault_state = ctx.accounts.vault_state.load_init()?;
-    vault_state.resolve_market(ctx.accounts.market_config.ct1_mint)?;
+// called by the oracle adapter
+pub fn resolve_market(ctx: Context<ResolveMarket>, report: ResolutionReport) -> Result<()> {
+    let clock = Clock::get()?;
+    if report.publish_time > clock.unix_timestamp
+        || report.benchmark_publish_time > clock.unix_timestamp
+    {
+        return Err(ErrorCode::InvalidResolutionReport.into());
+    }
 
+    let market_config_key = ctx.accounts.market_config.key();
     let market_config = ctx.accounts.market_config.deref_mut();
+    if market_config.status.is_redeemable() {
+        return Err(ErrorCode::MarketAlreadyResolved.into());
+    }
+    // trading ends at trading_closes_at, only a touched barrier ends it before that
+    market_config.sync_status(clock.unix_timestamp)?;
+    if market_config.status == MarketStatus::Trading && report.source == ResolutionSource::PythBarrier {
+        market_config.status.transition(MarketStatus::Closed)?;
+    }
+    market_config.status.require_one_of(&[MarketStatus::Closed])?;
+
+    let winning_ct_mint = match report.outcome {
+        MarketOutcome::Yes => market_config.ct1_mint,
+        MarketOutcome::No => market_config.ct2_mint,
+        MarketOutcome::Invalid => Pubkey::default(),
+    };
+    let (ct1_payout_bps, ct2_payout_bps) = report.outcome.payout_bps();
+
+    let mut vault_state = ctx.accounts.vault_state.load_mut()?;
+    vault_state.resolve_market(winning_ct_mint, ct1_payout_bps, ct2_payout_bps)?;
+    market_config.status.transition(match report.outcome {
+        MarketOutcome::Invalid => MarketStatus::Invalid,
+        _ => MarketStatus::Resolved,
+    })?;
     market_config.market_resolution = true;
+    market_config.resolved_at = clock.unix_timestamp;
+    MarketRegistryPage::sync(ctx.accounts.market_registry_page.as_deref_mut(), market_config_key, market_config)?;
 
-    let oracle_authority = ctx.accounts.oracle_adapter_pda.deref_mut();
-    if oracle_authority.resolution_price > oracle_authority.target_price {
-        vault_state.resolve_market(ctx.accounts.market_config.ct1_mint)?;
-    } else {
-        vault_state.resolve_market(ctx.accounts.market_config.ct2_mint)?;
-    }
+    let resolution_record = ctx.accounts.resolution_record.deref_mut();
+    resolution_record.bump = ctx.bumps.resolution_record;
+    resolution_record.market_config = market_config_key;
+    resolution_record.vault_state = ctx.accounts.vault_state.key();
+    resolution_record.source = report.source;
+    resolution_record.feed_id = report.feed_id;
+    resolution_record.price = report.price;
+    resolution_record.conf = report.conf;
+    resolution_record.exponent = report.exponent;
+    resolution_record.publish_time = report.publish_time;
+    resolution_record.benchmark_feed_id = report.benchmark_feed_id;
+    resolution_record.benchmark_price = report.benchmark_price;
+    resolution_record.benchmark_exponent = report.benchmark_exponent;
+    resolution_record.benchmark_publish_time = report.benchmark_publish_time;
+    resolution_record.slot = clock.slot;
+    resolution_record.resolved_at = clock.unix_timestamp;
+    resolution_record.resolver = ctx.accounts.payer.key();
+    resolution_record.outcome = report.outcome;
+    resolution_record.winning_ct_mint = winning_ct_mint;
+    resolution_record.ct1_payout_bps = ct1_payout_bps;
+    resolution_record.ct2_payout_bps = ct2_payout_bps;
 
+    emit_cpi!(MarketResolved {
+        market_config: market_config_key,
+        vault_state: ctx.accounts.vault_state.key(),
+        resolution_record: ctx.accounts.resolution_record.key(),
+        outcome: report.outcome,
+        winning_ct_mint,
+        ct1_payout_bps,
+        ct2_payout_bps,
+        price: report.price,
+        exponent: report.exponent,
+        vault_balance: vault_state.vault_collateral_balance,
+    });
     Ok(())
 }
@@ -0,0 +1,55 @@
+use crate::program::MarketProgram;
+use crate::states::*;
+use crate::events::*;
+use crate::error::ErrorCode;
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::Mint;
+use std::ops::DerefMut;
+
+// lists a collateral mint or changes its limits, the program's upgrade authority acts as the protocol admin
+#[event_cpi]
+#[derive(Accounts)]
+pub struct SetCollateralInfo<'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    #[account(constraint = market_program.programdata_address()? == Some(program_data.key()))]
+    pub market_program: Program<'info, MarketProgram>,
+
+    #[account(constraint = program_data.upgrade_authority_address == Some(admin.key()) @ ErrorCode::InvalidOwner)]
+    pub program_data: Account<'info, ProgramData>,
+
+    pub collateral_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        init_if_needed,
+        seeds = [
+            COLLATERAL_INFO_SEED.as_bytes(),
+            collateral_mint.key().as_ref(),
+        ],
+        bump,
+        payer = admin,
+        space = 8 + CollateralInfo::INIT_SPACE,
+    )]
+    pub collateral_info: Account<'info, CollateralInfo>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn set_collateral_info(ctx: Context<SetCollateralInfo>, params: CollateralParams) -> Result<()> {
+    let collateral_info = ctx.accounts.collateral_info.deref_mut();
+    collateral_info.bump = ctx.bumps.collateral_info;
+    collateral_info.mint = ctx.accounts.collateral_mint.key();
+    collateral_info.set(params);
+
+    emit_cpi!(CollateralInfoUpdated {
+        collateral_info: ctx.accounts.collateral_info.key(),
+        mint: ctx.accounts.collateral_mint.key(),
+        enabled: params.enabled,
+        max_open_interest: params.max_open_interest,
+        min_bet: params.min_bet,
+        allowed_extensions: params.allowed_extensions,
+        treasury: params.treasury,
+    });
+    Ok(())
+}
@@ -0,0 +1,22 @@
+use crate::states::*;
+use anchor_lang::prelude::*;
+
+// called by the oracle adapter, a relative performance market cannot trade until its start prices are recorded
+#[derive(Accounts)]
+pub struct SetStartPricePending<'info> {
+    /// the oracle adapter pda, it can only sign through the adapter program
+    #[account(
+        seeds = [ORACLE_AUTHORITY_SEED.as_bytes()],
+        bump,
+        seeds::program = ORACLE_ADAPTER_PROGRAM_ID,
+    )]
+    pub oracle_authority: Signer<'info>,
+
+    #[account(mut)]
+    pub market_config: Account<'info, MarketConfig>,
+}
+
+pub fn set_start_price_pending(ctx: Context<SetStartPricePending>, pending: bool) -> Result<()> {
+    ctx.accounts.market_config.start_price_pending = pending;
+    Ok(())
+}
@@ -0,0 +1,27 @@
+use crate::states::*;
+use crate::events::*;
+use anchor_lang::prelude::*;
+
+// the rules the conditional token transfer hook enforces on secondary transfers
+#[event_cpi]
+#[derive(Accounts)]
+pub struct SetTransferRules<'info> {
+    /// The market config owner
+    pub owner: Signer<'info>,
+
+    #[account(mut, has_one = owner)]
+    pub market_config: Account<'info, MarketConfig>,
+}
+
+pub fn set_transfer_rules(ctx: Context<SetTransferRules>, transfers_frozen: bool, restricted: bool) -> Result<()> {
+    let market_config = &mut ctx.accounts.market_config;
+    market_config.transfers_frozen = transfers_frozen;
+    market_config.restricted = restricted;
+
+    emit_cpi!(TransferRulesUpdated {
+        market_config: market_config.key(),
+        transfers_frozen,
+        restricted,
+    });
+    Ok(())
+}
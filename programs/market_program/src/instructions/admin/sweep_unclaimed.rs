@@ -0,0 +1,120 @@
+use crate::error::ErrorCode;
+use crate::events::*;
+use crate::program::MarketProgram;
+use crate::states::*;
+use crate::utils::*;
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+// once a resolved market's claim deadline has passed, the protocol admin moves whatever
+// collateral is left in the vault to the treasury, late claims fail from then on
+#[event_cpi]
+#[derive(Accounts)]
+pub struct SweepUnclaimed<'info> {
+    pub admin: Signer<'info>,
+
+    #[account(constraint = market_program.programdata_address()? == Some(program_data.key()))]
+    pub market_program: Program<'info, MarketProgram>,
+
+    #[account(constraint = program_data.upgrade_authority_address == Some(admin.key()) @ ErrorCode::InvalidOwner)]
+    pub program_data: Account<'info, ProgramData>,
+
+    #[account(
+        mut,
+        has_one = vault_state,
+        has_one = vault,
+    )]
+    pub market_config: Box<Account<'info, MarketConfig>>,
+
+    #[account(
+        mut,
+        seeds = [
+            VAULT_STATE_SEED.as_bytes(),
+            market_config.key().as_ref(),
+            collateral_mint.key().as_ref(),
+        ],
+        bump,
+        has_one = market_config,
+        has_one = vault,
+    )]
+    pub vault_state: AccountLoader<'info, VaultState>,
+
+    /// CHECK: the market's authority pda, signs the sweep
+    #[account(
+        seeds = [
+            crate::AUTH_SEED.as_bytes(),
+            vault_state.key().as_ref(),
+        ],
+        bump = vault_state.load()?.auth_bump,
+    )]
+    pub authority: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        token::mint = collateral_mint,
+        token::authority = authority,
+        token::token_program = collateral_token_program,
+    )]
+    pub vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(mint::token_program = collateral_token_program)]
+    pub collateral_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        seeds = [
+            COLLATERAL_INFO_SEED.as_bytes(),
+            collateral_mint.key().as_ref(),
+        ],
+        bump = collateral_info.bump,
+    )]
+    pub collateral_info: Box<Account<'info, CollateralInfo>>,
+
+    #[account(
+        mut,
+        address = collateral_info.treasury @ ErrorCode::TreasuryNotSet,
+        token::mint = collateral_mint,
+        token::token_program = collateral_token_program,
+    )]
+    pub treasury: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    pub collateral_token_program: Interface<'info, TokenInterface>,
+}
+
+pub fn sweep_unclaimed(ctx: Context<SweepUnclaimed>) -> Result<()> {
+    let market_config = &ctx.accounts.market_config;
+    if !market_config.status.is_redeemable() {
+        return Err(ErrorCode::MarketNotResolved.into());
+    }
+    if market_config.swept {
+        return Err(ErrorCode::AlreadySwept.into());
+    }
+    if !market_config.claims_closed(Clock::get()?.unix_timestamp) {
+        return Err(ErrorCode::ClaimDeadlineNotReached.into());
+    }
+
+    let vault_state_key = ctx.accounts.vault_state.key();
+    let mut vault_state = ctx.accounts.vault_state.load_mut()?;
+    let amount = ctx.accounts.vault.amount;
+    if amount > 0 {
+        transfer_from_collateral_vault_to_user(
+            ctx.accounts.authority.to_account_info(),
+            ctx.accounts.vault.to_account_info(),
+            ctx.accounts.treasury.to_account_info(),
+            ctx.accounts.collateral_mint.to_account_info(),
+            ctx.accounts.collateral_token_program.to_account_info(),
+            amount,
+            ctx.accounts.collateral_mint.decimals,
+            &[&[crate::AUTH_SEED.as_bytes(), vault_state_key.as_ref(), &[vault_state.auth_bump]]],
+        )?;
+    }
+    vault_state.vault_collateral_balance = 0;
+    ctx.accounts.market_config.swept = true;
+
+    emit_cpi!(Swept {
+        market_config: ctx.accounts.market_config.key(),
+        vault_state: vault_state_key,
+        treasury: ctx.accounts.treasury.key(),
+        amount,
+    });
+    Ok(())
+}
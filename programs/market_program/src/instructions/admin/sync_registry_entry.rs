@@ -0,0 +1,28 @@
+use crate::states::*;
+use anchor_lang::prelude::*;
+
+// permissionless crank that copies a market's current status into its registry entry
+#[derive(Accounts)]
+pub struct SyncRegistryEntry<'info> {
+    #[account(mut)]
+    pub market_config: Account<'info, MarketConfig>,
+
+    #[account(
+        mut,
+        seeds = [
+            MARKET_REGISTRY_SEED.as_bytes(),
+            &MarketRegistryPage::page_of(market_config.market_index).to_be_bytes()
+        ],
+        bump = market_registry_page.bump,
+    )]
+    pub market_registry_page: Account<'info, MarketRegistryPage>,
+}
+
+pub fn sync_registry_entry(ctx: Context<SyncRegistryEntry>) -> Result<()> {
+    let market_config = &mut ctx.accounts.market_config;
+    // pick up trading opening and closing even if nobody traded since
+    market_config.sync_status(Clock::get()?.unix_timestamp)?;
+
+    MarketRegistryPage::sync(Some(&mut ctx.accounts.market_registry_page), market_config.key(), market_config)?;
+    Ok(())
+}
@@ -1,8 +1,10 @@
 // can only be called by the owner of the market config
 // or the deployed oracle adapter contract.
 use crate::states::*;
+use crate::events::*;
 use anchor_lang::prelude::*;
 
+#[event_cpi]
 #[derive(Accounts)]
 pub struct UpdateMarketConfig<'info> {
     /// The market config owner or admin
@@ -35,6 +37,12 @@ pub fn update_market_config(ctx: Context<UpdateMarketConfig>, param: u8, value:
         // _ => return err!(ErrorCode::InvalidInput),// TODO: custom error 
     // }
 
+    emit_cpi!(ConfigUpdated {
+        market_config: market_config.key(),
+        owner: ctx.accounts.owner.key(),
+        param,
+        value,
+    });
     Ok(())
 
 }
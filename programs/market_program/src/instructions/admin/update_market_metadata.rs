@@ -0,0 +1,49 @@
+use crate::states::*;
+use crate::events::*;
+use anchor_lang::prelude::*;
+
+// the only way to change a market's metadata after creation, allowed until trading opens
+#[event_cpi]
+#[derive(Accounts)]
+pub struct UpdateMarketMetadata<'info> {
+    /// The market config owner
+    pub owner: Signer<'info>,
+
+    #[account(mut, has_one = owner)]
+    pub market_config: Account<'info, MarketConfig>,
+}
+
+pub fn update_market_metadata(
+    ctx: Context<UpdateMarketMetadata>,
+    name: Option<String>,
+    description: Option<String>,
+    category: Option<MarketCategory>,
+    metadata: Option<MarketMetadata>,
+) -> Result<()> {
+    let market_config = &mut ctx.accounts.market_config;
+    market_config.require_metadata_mutable(Clock::get()?.unix_timestamp)?;
+
+    let name = name.unwrap_or_else(|| market_config.name.clone());
+    let description = description.unwrap_or_else(|| market_config.description.clone());
+    validate_metadata(&name, &description)?;
+    market_config.name = name;
+    market_config.description = description;
+    if let Some(category) = category {
+        market_config.category = category;
+    }
+    if let Some(metadata) = metadata {
+        metadata.validate()?;
+        market_config.metadata = metadata;
+    }
+
+    emit_cpi!(MarketMetadataUpdated {
+        market_config: market_config.key(),
+        owner: ctx.accounts.owner.key(),
+        name: market_config.name.clone(),
+        description: market_config.description.clone(),
+        category: market_config.category,
+        metadata_uri: market_config.metadata.metadata_uri.clone(),
+        rules_hash: market_config.metadata.rules_hash,
+    });
+    Ok(())
+}
@@ -0,0 +1,305 @@
+// buy_bet and sell_bet across several markets of the same collateral in one instruction,
+// the per-market accounts come from remaining_accounts and are checked here instead of by anchor
+
+use crate::utils::*;
+use crate::states::*;
+use crate::error::ErrorCode;
+use crate::events::*;
+use anchor_lang::prelude::*;
+use anchor_spl::associated_token::{self, get_associated_token_address_with_program_id, AssociatedToken};
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+/// remaining accounts per entry, in this order:
+/// market_config, vault_state, authority, vault, ct1_mint, ct2_mint, ct1_account, ct2_account
+/// batch_buy_bet takes the bettor's allowlist entries of restricted markets after all entries' accounts
+pub const BATCH_BET_ACCOUNTS: usize = 8;
+/// a transaction may lock at most this many accounts, program ids included
+pub const MAX_TX_ACCOUNT_LOCKS: usize = 64;
+/// the ten accounts of BatchBet (event authority and program included)
+/// plus the compute budget program the batch needs to raise its limit
+pub const BATCH_SHARED_ACCOUNTS: usize = 11;
+/// as many entries as fit next to the shared accounts, i.e. six,
+/// each entry costs roughly as much compute as a single buy_bet or sell_bet
+pub const MAX_BATCH_ENTRIES: usize = (MAX_TX_ACCOUNT_LOCKS - BATCH_SHARED_ACCOUNTS) / BATCH_BET_ACCOUNTS;
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug)]
+pub struct BatchEntry {
+    pub vault_state: Pubkey,
+    pub amount: u64,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct BatchBet<'info> {
+    #[account(mut)]
+    pub bettor: Signer<'info>,
+
+    #[account(
+        mut,
+        token::mint = collateral_mint,
+        token::authority = bettor,
+        token::token_program = collateral_token_program,
+    )]
+    pub collateral_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(mint::token_program = collateral_token_program)]
+    pub collateral_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        seeds = [
+            COLLATERAL_INFO_SEED.as_bytes(),
+            collateral_mint.key().as_ref(),
+        ],
+        bump = collateral_info.bump,
+    )]
+    pub collateral_info: Box<Account<'info, CollateralInfo>>,
+    pub collateral_token_program: Interface<'info, TokenInterface>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+/// One entry's market, validated the way BuyBet and SellBet constrain their accounts
+struct BatchMarket<'info> {
+    market_config: Box<Account<'info, MarketConfig>>,
+    vault_state: AccountLoader<'info, VaultState>,
+    authority: &'info AccountInfo<'info>,
+    vault: Box<InterfaceAccount<'info, TokenAccount>>,
+    ct1_mint: Box<InterfaceAccount<'info, Mint>>,
+    ct2_mint: Box<InterfaceAccount<'info, Mint>>,
+    ct1_account: Box<InterfaceAccount<'info, TokenAccount>>,
+    ct2_account: Box<InterfaceAccount<'info, TokenAccount>>,
+}
+
+impl<'info> BatchMarket<'info> {
+    fn load(
+        shared: &BatchBet<'info>,
+        entry: &BatchEntry,
+        accounts: &'info [AccountInfo<'info>],
+        create_ct_accounts: bool,
+    ) -> Result<Self> {
+        let [market_config, vault_state, authority, vault, ct1_mint, ct2_mint, ct1_account, ct2_account] = accounts else {
+            return Err(ErrorCode::InvalidBatchAccount.into());
+        };
+        require_keys_eq!(vault_state.key(), entry.vault_state, ErrorCode::InvalidBatchAccount);
+
+        let market_config = Box::new(Account::<MarketConfig>::try_from(market_config)?);
+        let vault_state = AccountLoader::<VaultState>::try_from(vault_state)?;
+        let auth_bump = {
+            let state = vault_state.load()?;
+            require_keys_eq!(market_config.vault_state, vault_state.key(), ErrorCode::InvalidBatchAccount);
+            require_keys_eq!(state.market_config, market_config.key(), ErrorCode::InvalidBatchAccount);
+            require_keys_eq!(state.vault, market_config.vault, ErrorCode::InvalidBatchAccount);
+            state.auth_bump
+        };
+        let expected_authority = Pubkey::create_program_address(
+            &[crate::AUTH_SEED.as_bytes(), vault_state.key().as_ref(), &[auth_bump]],
+            &crate::ID,
+        )
+        .map_err(|_| ErrorCode::InvalidBatchAccount)?;
+        require_keys_eq!(authority.key(), expected_authority, ErrorCode::InvalidBatchAccount);
+
+        // the vault holds this batch's collateral, which also ties the market to the vault state pda's collateral seed
+        require_keys_eq!(*vault.owner, shared.collateral_token_program.key(), ErrorCode::InvalidBatchAccount);
+        let vault = Box::new(InterfaceAccount::<TokenAccount>::try_from(vault)?);
+        require_keys_eq!(vault.key(), market_config.vault, ErrorCode::InvalidBatchAccount);
+        require_keys_eq!(vault.mint, shared.collateral_mint.key(), ErrorCode::InvalidBatchAccount);
+        require_keys_eq!(vault.owner, expected_authority, ErrorCode::InvalidBatchAccount);
+
+        let mut ct_mints = Vec::with_capacity(2);
+        let mut ct_accounts = Vec::with_capacity(2);
+        for (mint, expected_mint, account) in [
+            (ct1_mint, market_config.ct1_mint, ct1_account),
+            (ct2_mint, market_config.ct2_mint, ct2_account),
+        ] {
+            require_keys_eq!(mint.key(), expected_mint, ErrorCode::InvalidBatchAccount);
+            require_keys_eq!(*mint.owner, shared.token_program.key(), ErrorCode::InvalidBatchAccount);
+            require_keys_eq!(
+                account.key(),
+                get_associated_token_address_with_program_id(&shared.bettor.key(), &expected_mint, &shared.token_program.key()),
+                ErrorCode::InvalidBatchAccount
+            );
+            if create_ct_accounts && account.data_is_empty() {
+                associated_token::create_idempotent(CpiContext::new(
+                    shared.associated_token_program.to_account_info(),
+                    associated_token::Create {
+                        payer: shared.bettor.to_account_info(),
+                        associated_token: account.clone(),
+                        authority: shared.bettor.to_account_info(),
+                        mint: mint.clone(),
+                        system_program: shared.system_program.to_account_info(),
+                        token_program: shared.token_program.to_account_info(),
+                    },
+                ))?;
+            }
+            ct_mints.push(Box::new(InterfaceAccount::<Mint>::try_from(mint)?));
+            ct_accounts.push(Box::new(InterfaceAccount::<TokenAccount>::try_from(account)?));
+        }
+        let [ct1_mint, ct2_mint]: [_; 2] = ct_mints.try_into().map_err(|_| ErrorCode::InvalidBatchAccount)?;
+        let [ct1_account, ct2_account]: [_; 2] = ct_accounts.try_into().map_err(|_| ErrorCode::InvalidBatchAccount)?;
+
+        Ok(Self {
+            market_config,
+            vault_state,
+            authority,
+            vault,
+            ct1_mint,
+            ct2_mint,
+            ct1_account,
+            ct2_account,
+        })
+    }
+
+    /// Fails the batch unless the vault still backs every conditional token in circulation
+    fn check_solvency(&mut self, vault_state: &VaultState) -> Result<()> {
+        self.vault.reload()?;
+        self.ct1_mint.reload()?;
+        self.ct2_mint.reload()?;
+        vault_state.check_solvency(self.vault.amount, self.ct1_mint.supply, self.ct2_mint.supply)
+    }
+}
+
+fn check_batch(entries: &[BatchEntry], remaining_accounts: &[AccountInfo]) -> Result<()> {
+    if entries.len() > MAX_BATCH_ENTRIES {
+        return Err(ErrorCode::BatchTooLarge.into());
+    }
+    if entries.is_empty() || remaining_accounts.len() != entries.len() * BATCH_BET_ACCOUNTS {
+        return Err(ErrorCode::InvalidBatchAccount.into());
+    }
+    Ok(())
+}
+
+pub fn batch_buy_bet<'info>(
+    ctx: Context<'_, '_, 'info, 'info, BatchBet<'info>>,
+    entries: Vec<BatchEntry>,
+    delegate_claim: bool,
+) -> Result<()> {
+    let (market_accounts, allowlist_entries) = ctx
+        .remaining_accounts
+        .split_at(ctx.remaining_accounts.len().min(entries.len() * BATCH_BET_ACCOUNTS));
+    check_batch(&entries, market_accounts)?;
+    let now = Clock::get()?.unix_timestamp;
+
+    for (entry, accounts) in entries.iter().zip(market_accounts.chunks_exact(BATCH_BET_ACCOUNTS)) {
+        let mut market = BatchMarket::load(ctx.accounts, entry, accounts, true)?;
+        market.market_config.sync_status(now)?;
+        market.market_config.require_trading(now)?;
+        if market.market_config.restricted {
+            let address = allowlist_entry_address(&market.market_config.key(), &ctx.accounts.bettor.key());
+            require_allowlisted(&market.market_config, allowlist_entries.iter().find(|entry| entry.key() == address))?;
+        }
+        ctx.accounts
+            .collateral_info
+            .check_bet(entry.amount, market.vault_state.load()?.vault_collateral_balance)?;
+
+        let received = transfer_from_user_to_collateral_vault(
+            ctx.accounts.bettor.to_account_info(),
+            ctx.accounts.collateral_account.to_account_info(),
+            market.vault.to_account_info(),
+            ctx.accounts.collateral_mint.to_account_info(),
+            ctx.accounts.collateral_token_program.to_account_info(),
+            entry.amount,
+            ctx.accounts.collateral_mint.decimals,
+        )?;
+
+        let vault_state_key = market.vault_state.key();
+        let vault_state_loader = market.vault_state.clone();
+        let mut vault_state = vault_state_loader.load_mut()?;
+        let authority_seeds: &[&[u8]] = &[crate::AUTH_SEED.as_bytes(), vault_state_key.as_ref(), &[vault_state.auth_bump]];
+        for (mint, account) in [(&market.ct1_mint, &market.ct1_account), (&market.ct2_mint, &market.ct2_account)] {
+            token_mint_to(
+                market.authority.clone(),
+                ctx.accounts.token_program.to_account_info(),
+                mint.to_account_info(),
+                account.to_account_info(),
+                received,
+                &[authority_seeds],
+            )?;
+            // same opt-in claim delegation as buy_bet, replaces any existing delegate
+            if delegate_claim {
+                token_approve_unlimited(
+                    ctx.accounts.bettor.to_account_info(),
+                    ctx.accounts.token_program.to_account_info(),
+                    account,
+                    market.authority.clone(),
+                )?;
+            }
+        }
+
+        vault_state.update_collateral_supply(received, true)?;
+        market.check_solvency(&vault_state)?;
+        // sync_status may have opened trading
+        market.market_config.exit(&crate::ID)?;
+
+        emit_cpi!(BetBought {
+            market_config: vault_state.market_config,
+            vault_state: vault_state_key,
+            user: ctx.accounts.bettor.key(),
+            collateral_mint: ctx.accounts.collateral_mint.key(),
+            ct1_mint: market.ct1_mint.key(),
+            ct2_mint: market.ct2_mint.key(),
+            amount: received,
+            vault_balance: vault_state.vault_collateral_balance,
+        });
+    }
+    Ok(())
+}
+
+pub fn batch_sell_bet<'info>(
+    ctx: Context<'_, '_, 'info, 'info, BatchBet<'info>>,
+    entries: Vec<BatchEntry>,
+) -> Result<()> {
+    check_batch(&entries, ctx.remaining_accounts)?;
+    let now = Clock::get()?.unix_timestamp;
+
+    for (entry, accounts) in entries.iter().zip(ctx.remaining_accounts.chunks_exact(BATCH_BET_ACCOUNTS)) {
+        let mut market = BatchMarket::load(ctx.accounts, entry, accounts, false)?;
+        market.market_config.sync_status(now)?;
+        market.market_config.require_trading(now)?;
+
+        let vault_state_key = market.vault_state.key();
+        let vault_state_loader = market.vault_state.clone();
+        let mut vault_state = vault_state_loader.load_mut()?;
+
+        // the bettor owns the conditional tokens, no pda signature needed
+        for (mint, account) in [(&market.ct1_mint, &market.ct1_account), (&market.ct2_mint, &market.ct2_account)] {
+            token_burn(
+                ctx.accounts.bettor.to_account_info(),
+                ctx.accounts.token_program.to_account_info(),
+                mint.to_account_info(),
+                account.to_account_info(),
+                entry.amount,
+                &[],
+            )?;
+        }
+
+        let received = transfer_from_collateral_vault_to_user(
+            market.authority.clone(),
+            market.vault.to_account_info(),
+            ctx.accounts.collateral_account.to_account_info(),
+            ctx.accounts.collateral_mint.to_account_info(),
+            ctx.accounts.collateral_token_program.to_account_info(),
+            entry.amount,
+            ctx.accounts.collateral_mint.decimals,
+            &[&[crate::AUTH_SEED.as_bytes(), vault_state_key.as_ref(), &[vault_state.auth_bump]]],
+        )?;
+
+        vault_state.update_collateral_supply(entry.amount, false)?;
+        market.check_solvency(&vault_state)?;
+        market.market_config.exit(&crate::ID)?;
+
+        emit_cpi!(BetSold {
+            market_config: vault_state.market_config,
+            vault_state: vault_state_key,
+            user: ctx.accounts.bettor.key(),
+            collateral_mint: ctx.accounts.collateral_mint.key(),
+            ct1_mint: market.ct1_mint.key(),
+            ct2_mint: market.ct2_mint.key(),
+            amount: entry.amount,
+            collateral_received: received,
+            vault_balance: vault_state.vault_collateral_balance,
+        });
+    }
+    Ok(())
+}
@@ -3,10 +3,12 @@
 
 use crate::utils::*;
 use crate::states::*;
+use crate::events::*;
 use anchor_lang::prelude::*;
 use anchor_spl::associated_token::AssociatedToken;
 use anchor_spl::token_interface::{ Mint, TokenAccount, TokenInterface};
 // use anchor_spl::token::Mint;
+#[event_cpi]
 #[derive(Accounts)]
 pub struct BuyBet<'info> {
     #[account(mut)]
@@ -17,33 +19,55 @@ pub struct BuyBet<'info> {
         mut,
         seeds = [
             crate::AUTH_SEED.as_bytes(),
+            vault_state.key().as_ref(),
         ],
-        bump,
+        bump = vault_state.load()?.auth_bump,
     )]
     pub authority: UncheckedAccount<'info>,
 
     #[account(
         mut,
         token::mint = collateral_mint,
-        token::authority = bettor
+        token::authority = bettor,
+        token::token_program = collateral_token_program,
     )]
     pub collateral_account: Box<InterfaceAccount<'info, TokenAccount>>,
 
     // token mints of the conditional tokens
-    #[account(mut)]
+    #[account(mut, mint::token_program = token_program)]
     pub ct1_mint: InterfaceAccount<'info, Mint>,
 
-    #[account(mut)]
-    pub vault_state: AccountLoader<'info, VaultState>,
+    #[account(
+        mut,
+        has_one = vault_state,
+        has_one = vault,
+        has_one = ct1_mint,
+        has_one = ct2_mint,
+    )]
+    pub market_config: Box<Account<'info, MarketConfig>>,
 
+    #[account(
+        mut,
+        seeds = [
+            VAULT_STATE_SEED.as_bytes(),
+            market_config.key().as_ref(),
+            collateral_mint.key().as_ref(),
+        ],
+        bump,
+        has_one = market_config,
+        has_one = vault,
+    )]
+    pub vault_state: AccountLoader<'info, VaultState>,
 
     #[account(
         mut,
-        constraint = vault.key() == vault_state.load()?.vault
+        token::mint = collateral_mint,
+        token::authority = authority,
+        token::token_program = collateral_token_program,
     )]
     pub vault: Box<InterfaceAccount<'info, TokenAccount>>,
 
-    #[account(mut)]
+    #[account(mut, mint::token_program = token_program)]
     pub ct2_mint: InterfaceAccount<'info, Mint>,
 
     // user's token accounts for the conditional tokens
@@ -65,10 +89,26 @@ pub struct BuyBet<'info> {
     )]
     pub ct2_account: InterfaceAccount<'info, TokenAccount>,
 
-    #[account(mut)]
+    #[account(mut, mint::token_program = collateral_token_program)]
     pub collateral_mint: InterfaceAccount<'info, Mint>,
+    #[account(
+        seeds = [
+            COLLATERAL_INFO_SEED.as_bytes(),
+            collateral_mint.key().as_ref(),
+        ],
+        bump = collateral_info.bump,
+    )]
+    pub collateral_info: Box<Account<'info, CollateralInfo>>,
     pub collateral_token_program: Interface<'info, TokenInterface>,
 
+    /// CHECK: the bettor's allowlist entry on the transfer hook, only required for restricted markets
+    #[account(
+        seeds = [ALLOWLIST_SEED.as_bytes(), market_config.key().as_ref(), bettor.key().as_ref()],
+        bump,
+        seeds::program = CT_TRANSFER_HOOK_PROGRAM_ID,
+    )]
+    pub allowlist_entry: Option<UncheckedAccount<'info>>,
+
     // TODO : add the vault account of the collateral
     
     pub token_program: Interface<'info, TokenInterface>,
@@ -77,10 +117,19 @@ pub struct BuyBet<'info> {
 }
 
 
-pub fn buy_bet(ctx: Context<BuyBet>, amount: u64) -> Result<()> {
+pub fn buy_bet(ctx: Context<BuyBet>, amount: u64, delegate_claim: bool) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+    ctx.accounts.market_config.sync_status(now)?;
+    ctx.accounts.market_config.require_trading(now)?;
+    require_allowlisted(&ctx.accounts.market_config, ctx.accounts.allowlist_entry.as_deref())?;
+    ctx.accounts
+        .collateral_info
+        .check_bet(amount, ctx.accounts.vault_state.load()?.vault_collateral_balance)?;
+
     // logic to sign the transaction from user to send the collateral from
 
-    transfer_from_user_to_collateral_vault(
+    // with a transfer fee collateral the vault receives less than amount, only that is backed
+    let received = transfer_from_user_to_collateral_vault(
         ctx.accounts.bettor.to_account_info(),
         ctx.accounts.collateral_account.to_account_info(),
         ctx.accounts.vault.to_account_info(),
@@ -90,20 +139,25 @@ pub fn buy_bet(ctx: Context<BuyBet>, amount: u64) -> Result<()> {
         ctx.accounts.collateral_mint.decimals,
     )?;
 
+    let vault_state_key = ctx.accounts.vault_state.key();
     let mut vault_state = ctx.accounts.vault_state.load_mut()?;
 
-    msg!("DEBUG BuyBet - Stored auth_bump: {}", vault_state.auth_bump);
-    msg!("DEBUG BuyBet - Stored vault: {}", vault_state.vault);
-    msg!("DEBUG BuyBet - Authority address: {}", ctx.accounts.authority.key());
-    msg!("DEBUG BuyBet - Vault address passed: {}", ctx.accounts.vault.key());
+    #[cfg(feature = "enable-log")]
+    {
+        msg!("DEBUG BuyBet - Stored auth_bump: {}", vault_state.auth_bump);
+        msg!("DEBUG BuyBet - Stored vault: {}", vault_state.vault);
+        msg!("DEBUG BuyBet - Authority address: {}", ctx.accounts.authority.key());
+        msg!("DEBUG BuyBet - Vault address passed: {}", ctx.accounts.vault.key());
+    }
 
+    // conditional tokens share the collateral decimals, one raw unit of each per raw unit of collateral
     token_mint_to(
         ctx.accounts.authority.to_account_info(),
         ctx.accounts.token_program.to_account_info(),
         ctx.accounts.ct1_mint.to_account_info(),
         ctx.accounts.ct1_account.to_account_info(),
-        amount,
-        &[&[crate::AUTH_SEED.as_bytes(), &[vault_state.auth_bump]]],
+        received,
+        &[&[crate::AUTH_SEED.as_bytes(), vault_state_key.as_ref(), &[vault_state.auth_bump]]],
     )?;
 
     token_mint_to(
@@ -111,13 +165,46 @@ pub fn buy_bet(ctx: Context<BuyBet>, amount: u64) -> Result<()> {
         ctx.accounts.token_program.to_account_info(),
         ctx.accounts.ct2_mint.to_account_info(),
         ctx.accounts.ct2_account.to_account_info(),
-        amount,
-        &[&[crate::AUTH_SEED.as_bytes(), &[vault_state.auth_bump]]],
+        received,
+        &[&[crate::AUTH_SEED.as_bytes(), vault_state_key.as_ref(), &[vault_state.auth_bump]]],
     )?;
 
+    // opt-in, lets anyone redeem the winning side for the user after resolution, see claim_for.
+    // the approval replaces any delegate the bettor had set on these accounts
+    if delegate_claim {
+        for ct_account in [&ctx.accounts.ct1_account, &ctx.accounts.ct2_account] {
+            token_approve_unlimited(
+                ctx.accounts.bettor.to_account_info(),
+                ctx.accounts.token_program.to_account_info(),
+                ct_account,
+                ctx.accounts.authority.to_account_info(),
+            )?;
+        }
+    }
+
     // update the vault state with the new collateral amount.
-    vault_state.update_collateral_supply(amount, true)?;
+    vault_state.update_collateral_supply(received, true)?;
+
+    // the vault must still back every conditional token in circulation
+    ctx.accounts.vault.reload()?;
+    ctx.accounts.ct1_mint.reload()?;
+    ctx.accounts.ct2_mint.reload()?;
+    vault_state.check_solvency(
+        ctx.accounts.vault.amount,
+        ctx.accounts.ct1_mint.supply,
+        ctx.accounts.ct2_mint.supply,
+    )?;
 
+    emit_cpi!(BetBought {
+        market_config: vault_state.market_config,
+        vault_state: ctx.accounts.vault_state.key(),
+        user: ctx.accounts.bettor.key(),
+        collateral_mint: ctx.accounts.collateral_mint.key(),
+        ct1_mint: ctx.accounts.ct1_mint.key(),
+        ct2_mint: ctx.accounts.ct2_mint.key(),
+        amount: received,
+        vault_balance: vault_state.vault_collateral_balance,
+    });
     Ok(())
 
 }
@@ -0,0 +1,184 @@
+// permissionless crank that redeems winning conditional tokens for their holders,
+// the tokens are burned through the delegation buy_bet grants the market authority when
+// the bettor opts in, and the collateral always goes to the holder's own collateral ata
+
+use crate::utils::*;
+use crate::states::*;
+use crate::error::ErrorCode;
+use crate::events::*;
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::program_option::COption;
+use anchor_spl::associated_token::get_associated_token_address_with_program_id;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+/// remaining accounts per claim: the holder's ct account, then their collateral ata
+pub const CLAIM_FOR_ACCOUNTS: usize = 2;
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct ClaimFor<'info> {
+    /// CHECK: a authority pda account that is owned by this contract
+    #[account(
+        seeds = [
+            crate::AUTH_SEED.as_bytes(),
+            vault_state.key().as_ref(),
+        ],
+        bump = vault_state.load()?.auth_bump,
+    )]
+    pub authority: UncheckedAccount<'info>,
+
+    #[account(
+        has_one = vault_state,
+        has_one = vault,
+        has_one = ct1_mint,
+        has_one = ct2_mint,
+    )]
+    pub market_config: Box<Account<'info, MarketConfig>>,
+
+    #[account(
+        mut,
+        seeds = [
+            VAULT_STATE_SEED.as_bytes(),
+            market_config.key().as_ref(),
+            collateral_mint.key().as_ref(),
+        ],
+        bump,
+        has_one = market_config,
+        has_one = vault,
+    )]
+    pub vault_state: AccountLoader<'info, VaultState>,
+
+    #[account(
+        mut,
+        token::mint = collateral_mint,
+        token::authority = authority,
+        token::token_program = collateral_token_program,
+    )]
+    pub vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    // burning needs the mints writable for their supply
+    #[account(mut, mint::token_program = token_program)]
+    pub ct1_mint: Box<InterfaceAccount<'info, Mint>>,
+    #[account(mut, mint::token_program = token_program)]
+    pub ct2_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(mint::token_program = collateral_token_program)]
+    pub collateral_mint: Box<InterfaceAccount<'info, Mint>>,
+    pub collateral_token_program: Interface<'info, TokenInterface>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+// is_multiple_of is newer than the platform tools' rust
+#[allow(clippy::manual_is_multiple_of)]
+pub fn claim_for<'info>(ctx: Context<'_, '_, 'info, 'info, ClaimFor<'info>>) -> Result<()> {
+    if !ctx.accounts.market_config.status.is_redeemable() {
+        return Err(ErrorCode::MarketNotResolved.into());
+    }
+    if ctx.accounts.market_config.claims_closed(Clock::get()?.unix_timestamp) {
+        return Err(ErrorCode::ClaimDeadlinePassed.into());
+    }
+    let remaining_accounts = ctx.remaining_accounts;
+    if remaining_accounts.is_empty() || remaining_accounts.len() % CLAIM_FOR_ACCOUNTS != 0 {
+        return Err(ErrorCode::InvalidClaimAccount.into());
+    }
+
+    let vault_state_key = ctx.accounts.vault_state.key();
+    let mut vault_state = ctx.accounts.vault_state.load_mut()?;
+    let authority_key = ctx.accounts.authority.key();
+    let collateral_mint_key = ctx.accounts.collateral_mint.key();
+    let collateral_token_program_key = ctx.accounts.collateral_token_program.key();
+    let authority_seeds: &[&[u8]] = &[crate::AUTH_SEED.as_bytes(), vault_state_key.as_ref(), &[vault_state.auth_bump]];
+
+    for group in remaining_accounts.chunks_exact(CLAIM_FOR_ACCOUNTS) {
+        let (ct_account_info, collateral_account_info) = (&group[0], &group[1]);
+        if *ct_account_info.owner != ctx.accounts.token_program.key() {
+            return Err(ErrorCode::InvalidClaimAccount.into());
+        }
+        let ct_account = InterfaceAccount::<TokenAccount>::try_from(ct_account_info)?;
+        let ct_mint = if ct_account.mint == ctx.accounts.ct1_mint.key() {
+            &ctx.accounts.ct1_mint
+        } else if ct_account.mint == ctx.accounts.ct2_mint.key() {
+            &ctx.accounts.ct2_mint
+        } else {
+            return Err(ErrorCode::InvalidClaimAccount.into());
+        };
+        let user = ct_account.owner;
+        if collateral_account_info.key()
+            != get_associated_token_address_with_program_id(&user, &collateral_mint_key, &collateral_token_program_key)
+        {
+            return Err(ErrorCode::InvalidClaimAccount.into());
+        }
+
+        // holders who revoked the delegation, losing tokens, empty accounts and holders whose
+        // collateral ata is missing or frozen are left alone so one of them does not fail the batch
+        let collateral_account_ready = *collateral_account_info.owner == collateral_token_program_key
+            && InterfaceAccount::<TokenAccount>::try_from(collateral_account_info)
+                .is_ok_and(|collateral_account| !collateral_account.is_frozen());
+        if !collateral_account_ready {
+            continue;
+        }
+        let payout_bps = if ct_account.mint == vault_state.ctf1_mint {
+            vault_state.ct1_payout_bps
+        } else {
+            vault_state.ct2_payout_bps
+        };
+        let amount = if ct_account.delegate == COption::Some(authority_key) {
+            ct_account.amount.min(ct_account.delegated_amount)
+        } else {
+            0
+        };
+        if amount == 0 || payout_bps == 0 {
+            continue;
+        }
+        let payout = vault_state.payout_for(ct_account.mint, amount)?;
+        if payout == 0 {
+            continue;
+        }
+
+        token_burn(
+            ctx.accounts.authority.to_account_info(),
+            ctx.accounts.token_program.to_account_info(),
+            ct_mint.to_account_info(),
+            ct_account_info.clone(),
+            amount,
+            &[authority_seeds],
+        )?;
+
+        let received = transfer_from_collateral_vault_to_user(
+            ctx.accounts.authority.to_account_info(),
+            ctx.accounts.vault.to_account_info(),
+            collateral_account_info.clone(),
+            ctx.accounts.collateral_mint.to_account_info(),
+            ctx.accounts.collateral_token_program.to_account_info(),
+            payout,
+            ctx.accounts.collateral_mint.decimals,
+            &[authority_seeds],
+        )?;
+
+        vault_state.update_collateral_supply(payout, false)?;
+
+        emit_cpi!(RewardClaimed {
+            market_config: vault_state.market_config,
+            vault_state: vault_state_key,
+            user,
+            collateral_mint: collateral_mint_key,
+            ct_mint: ct_account.mint,
+            amount,
+            payout,
+            collateral_received: received,
+            vault_balance: vault_state.vault_collateral_balance,
+        });
+    }
+
+    // the vault must still cover what the remaining conditional tokens pay out
+    ctx.accounts.vault.reload()?;
+    ctx.accounts.ct1_mint.reload()?;
+    ctx.accounts.ct2_mint.reload()?;
+    vault_state.check_solvency(
+        ctx.accounts.vault.amount,
+        ctx.accounts.ct1_mint.supply,
+        ctx.accounts.ct2_mint.supply,
+    )?;
+    Ok(())
+}
@@ -0,0 +1,114 @@
+// after resolution a user burns whatever their conditional tokens no longer pay out
+// and closes their token accounts, the rent goes back to them
+
+use crate::utils::*;
+use crate::states::*;
+use crate::error::ErrorCode;
+use crate::events::*;
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{close_account, CloseAccount, Mint, TokenAccount, TokenInterface};
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct ClosePosition<'info> {
+    #[account(mut)]
+    pub bettor: Signer<'info>,
+
+    #[account(has_one = vault_state, has_one = ct1_mint, has_one = ct2_mint)]
+    pub market_config: Box<Account<'info, MarketConfig>>,
+
+    #[account(has_one = market_config)]
+    pub vault_state: AccountLoader<'info, VaultState>,
+
+    // burning needs the mints writable for their supply
+    #[account(mut, mint::token_program = token_program)]
+    pub ct1_mint: Box<InterfaceAccount<'info, Mint>>,
+    #[account(mut, mint::token_program = token_program)]
+    pub ct2_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    // either account may be left out, e.g. when the user only ever held one side
+    #[account(
+        mut,
+        token::mint = ct1_mint,
+        token::authority = bettor,
+        token::token_program = token_program,
+    )]
+    pub ct1_account: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+    #[account(
+        mut,
+        token::mint = ct2_mint,
+        token::authority = bettor,
+        token::token_program = token_program,
+    )]
+    pub ct2_account: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+impl<'info> ClosePosition<'info> {
+    /// Burns the balance of `account` if it pays nothing, then closes it. Returns the amount burned.
+    fn close(
+        &self,
+        mint: &InterfaceAccount<'info, Mint>,
+        account: &InterfaceAccount<'info, TokenAccount>,
+        payout_bps: u16,
+        claims_closed: bool,
+    ) -> Result<u64> {
+        let burned = account.amount;
+        if burned > 0 {
+            // winning tokens have to be redeemed with get_reward while they still pay out
+            if payout_bps > 0 && !claims_closed {
+                return Err(ErrorCode::PositionNotEmpty.into());
+            }
+            token_burn(
+                self.bettor.to_account_info(),
+                self.token_program.to_account_info(),
+                mint.to_account_info(),
+                account.to_account_info(),
+                burned,
+                &[],
+            )?;
+        }
+        close_account(CpiContext::new(
+            self.token_program.to_account_info(),
+            CloseAccount {
+                account: account.to_account_info(),
+                destination: self.bettor.to_account_info(),
+                authority: self.bettor.to_account_info(),
+            },
+        ))?;
+        Ok(burned)
+    }
+}
+
+pub fn close_position(ctx: Context<ClosePosition>) -> Result<()> {
+    if !ctx.accounts.market_config.status.is_redeemable() {
+        return Err(ErrorCode::MarketNotResolved.into());
+    }
+    let claims_closed = ctx.accounts.market_config.claims_closed(Clock::get()?.unix_timestamp);
+    let (ct1_payout_bps, ct2_payout_bps) = {
+        let vault_state = ctx.accounts.vault_state.load()?;
+        (vault_state.ct1_payout_bps, vault_state.ct2_payout_bps)
+    };
+
+    let accounts = &ctx.accounts;
+    let ct1_burned = match &accounts.ct1_account {
+        Some(account) => accounts.close(&accounts.ct1_mint, account, ct1_payout_bps, claims_closed)?,
+        None => 0,
+    };
+    let ct2_burned = match &accounts.ct2_account {
+        Some(account) => accounts.close(&accounts.ct2_mint, account, ct2_payout_bps, claims_closed)?,
+        None => 0,
+    };
+
+    emit_cpi!(PositionClosed {
+        market_config: ctx.accounts.market_config.key(),
+        vault_state: ctx.accounts.vault_state.key(),
+        user: ctx.accounts.bettor.key(),
+        ct1_burned,
+        ct2_burned,
+        ct1_account_closed: ctx.accounts.ct1_account.is_some(),
+        ct2_account_closed: ctx.accounts.ct2_account.is_some(),
+    });
+    Ok(())
+}
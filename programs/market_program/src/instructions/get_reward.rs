@@ -6,9 +6,11 @@
 use crate::utils::*;
 use crate::states::*;
 use crate::error::ErrorCode;
+use crate::events::*;
 use anchor_lang::prelude::*;
 use anchor_spl::associated_token::AssociatedToken;
 use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+#[event_cpi]
 #[derive(Accounts)]
 pub struct GetReward<'info> {
     #[account(mut)]
@@ -18,36 +20,69 @@ pub struct GetReward<'info> {
     #[account(
         seeds = [
             crate::AUTH_SEED.as_bytes(),
+            vault_state.key().as_ref(),
         ],
-        bump,
+        bump = vault_state.load()?.auth_bump,
     )]
     pub authority: UncheckedAccount<'info>,
 
     #[account(
         mut,
         token::mint = collateral_mint,
-        token::authority = bettor
+        token::authority = bettor,
+        token::token_program = collateral_token_program,
     )]
     pub collateral_account: Box<InterfaceAccount<'info, TokenAccount>>,
 
+    // burning needs the mint writable for its supply
+    #[account(
+        mut,
+        mint::token_program = token_program,
+        constraint = market_config.is_ct_mint(&ct_mint.key()) @ ErrorCode::WrongWinningToken,
+    )]
     pub ct_mint: InterfaceAccount<'info, Mint>,
 
+    // the market's other conditional token, only read for the solvency check
+    #[account(
+        mint::token_program = token_program,
+        constraint = market_config.is_ct_mint(&other_ct_mint.key()) @ ErrorCode::WrongWinningToken,
+        constraint = other_ct_mint.key() != ct_mint.key() @ ErrorCode::WrongWinningToken,
+    )]
+    pub other_ct_mint: InterfaceAccount<'info, Mint>,
+
     #[account(
         mut,
         associated_token::mint = ct_mint,
         associated_token::authority = bettor,
+        associated_token::token_program = token_program,
     )]
     pub ct_account: InterfaceAccount<'info, TokenAccount>,
 
-    #[account(mut)]
+    #[account(has_one = vault_state, has_one = vault)]
+    pub market_config: Box<Account<'info, MarketConfig>>,
+
+    #[account(
+        mut,
+        seeds = [
+            VAULT_STATE_SEED.as_bytes(),
+            market_config.key().as_ref(),
+            collateral_mint.key().as_ref(),
+        ],
+        bump,
+        has_one = market_config,
+        has_one = vault,
+    )]
     pub vault_state: AccountLoader<'info, VaultState>,
 
     #[account(
         mut,
-        constraint = vault.key() == vault_state.load()?.vault
+        token::mint = collateral_mint,
+        token::authority = authority,
+        token::token_program = collateral_token_program,
     )]
     pub vault: Box<InterfaceAccount<'info, TokenAccount>>,
 
+    #[account(mint::token_program = collateral_token_program)]
     pub collateral_mint: InterfaceAccount<'info, Mint>,
     pub collateral_token_program: Interface<'info, TokenInterface>,
 
@@ -58,14 +93,17 @@ pub struct GetReward<'info> {
 
 pub fn get_reward(ctx: Context<GetReward>, amount: u64) -> Result<()> {
     // logic to sign the transaction from user to send the collateral from
+    let vault_state_key = ctx.accounts.vault_state.key();
     let mut vault_state = ctx.accounts.vault_state.load_mut()?;
-    if vault_state.resolution == 0 && Clock::get()?.unix_timestamp < vault_state.vault_expiration {
+    // nothing can be redeemed until the oracle adapter has resolved or invalidated the market
+    if !ctx.accounts.market_config.status.is_redeemable() {
         return Err(ErrorCode::MarketNotResolved.into());
     }
-    // check to see if the users token account is of the same mint as that of the winning conditional token
-    if ctx.accounts.ct_mint.key() != vault_state.winning_ct_mint {
-        return Err(ErrorCode::WrongWinningToken.into());
+    if ctx.accounts.market_config.claims_closed(Clock::get()?.unix_timestamp) {
+        return Err(ErrorCode::ClaimDeadlinePassed.into());
     }
+    // the users token must pay out, i.e. be the winning token or either token of an invalidated market
+    let payout = vault_state.payout_for(ctx.accounts.ct_mint.key(), amount)?;
 
     token_burn(
         ctx.accounts.bettor.to_account_info(),
@@ -73,23 +111,44 @@ pub fn get_reward(ctx: Context<GetReward>, amount: u64) -> Result<()> {
         ctx.accounts.ct_mint.to_account_info(),
         ctx.accounts.ct_account.to_account_info(),
         amount,
-        &[&[crate::AUTH_SEED.as_bytes(), &[vault_state.auth_bump]]],
+        &[&[crate::AUTH_SEED.as_bytes(), vault_state_key.as_ref(), &[vault_state.auth_bump]]],
     )?;
 
-    transfer_from_collateral_vault_to_user(
+    let received = transfer_from_collateral_vault_to_user(
         ctx.accounts.authority.to_account_info(),
         ctx.accounts.vault.to_account_info(),
         ctx.accounts.collateral_account.to_account_info(),
         ctx.accounts.collateral_mint.to_account_info(),
         ctx.accounts.collateral_token_program.to_account_info(),
-        amount,
+        payout,
         ctx.accounts.collateral_mint.decimals,
-        &[&[crate::AUTH_SEED.as_bytes(), &[vault_state.auth_bump]]],
+        &[&[crate::AUTH_SEED.as_bytes(), vault_state_key.as_ref(), &[vault_state.auth_bump]]],
     )?;
 
     // update the vault state with the new collateral amount.
-    vault_state.update_collateral_supply(amount, false)?;
+    vault_state.update_collateral_supply(payout, false)?;
 
+    // the vault must still cover what the remaining conditional tokens pay out
+    ctx.accounts.vault.reload()?;
+    ctx.accounts.ct_mint.reload()?;
+    let (ct1_supply, ct2_supply) = if ctx.accounts.ct_mint.key() == vault_state.ctf1_mint {
+        (ctx.accounts.ct_mint.supply, ctx.accounts.other_ct_mint.supply)
+    } else {
+        (ctx.accounts.other_ct_mint.supply, ctx.accounts.ct_mint.supply)
+    };
+    vault_state.check_solvency(ctx.accounts.vault.amount, ct1_supply, ct2_supply)?;
+
+    emit_cpi!(RewardClaimed {
+        market_config: vault_state.market_config,
+        vault_state: ctx.accounts.vault_state.key(),
+        user: ctx.accounts.bettor.key(),
+        collateral_mint: ctx.accounts.collateral_mint.key(),
+        ct_mint: ctx.accounts.ct_mint.key(),
+        amount,
+        payout,
+        collateral_received: received,
+        vault_balance: vault_state.vault_collateral_balance,
+    });
     Ok(())
 
 }
@@ -9,57 +9,72 @@ use std::ops::DerefMut;
 // the ct1 mint is classified to be the positive asserting case for the questions outcome and vice versa.
 use crate::states::*;
 use crate::utils::*;
+use crate::events::*;
 use crate::{error::ErrorCode};
 
-use anchor_lang::prelude::*;
-use anchor_spl::token_interface::{Mint, TokenInterface};
+use anchor_lang::{prelude::*, system_program};
+use anchor_spl::metadata::{
+    create_metadata_accounts_v3,
+    mpl_token_metadata::{
+        types::DataV2, MAX_NAME_LENGTH, MAX_SYMBOL_LENGTH,
+    },
+    CreateMetadataAccountsV3, Metadata,
+};
+use anchor_spl::token_2022::{spl_token_2022, Token2022};
+use anchor_spl::token_interface::{
+    initialize_mint2, metadata_pointer_initialize, mint_close_authority_initialize,
+    spl_pod::optional_keys::OptionalNonZeroPubkey, spl_token_metadata_interface::state::TokenMetadata,
+    token_metadata_initialize, transfer_hook_initialize, InitializeMint2, Mint,
+    MetadataPointerInitialize, MintCloseAuthorityInitialize, TokenInterface, TokenMetadataInitialize,
+    TransferHookInitialize,
+};
+use spl_token_2022::extension::ExtensionType;
+use anchor_lang::solana_program::program_pack::Pack;
+// the mint and vault authority of a market is derived from [AUTH_SEED, vault_state]
 pub const AUTH_SEED: &str = "vault_and_lp_mint_auth_seed";
 
+#[event_cpi]
 #[derive(Accounts)]
 pub struct Initialize<'info> {
     // #[account(mut, address = pubkey!("Hoamid9gD8dEgLrirgt3gNnAWhmxYe5LSKrJJUGGd4DA"))]
     // TODO replace with the admin key
     #[account(mut)]
     pub creator: Signer<'info>,
-    #[account(mut)]
+    // only the owner sets up the market's accounts, close_market refunds their rent to the owner
+    #[account(mut, constraint = market_config.owner == creator.key() @ ErrorCode::InvalidOwner)]
     pub market_config: Account<'info, MarketConfig>,
 
     /// CHECK: a authority pda account that is owned by this contract
     #[account(
         seeds = [
             crate::AUTH_SEED.as_bytes(),
+            vault_state.key().as_ref(),
         ],
         bump,
     )]
     pub authority: UncheckedAccount<'info>,
 
+    /// CHECK: created in the handler so that it can carry token metadata
     #[account(
-        init,
+        mut,
         seeds = [
             b"conditional_token1",
             vault_state.key().as_ref(),
         ],
         bump,
-        mint::decimals = 9,
-        mint::authority = authority,
-        payer = creator,
-        mint::token_program = token_program,
     )]
-    pub ct1_mint: Box<InterfaceAccount<'info, Mint>>,
+    pub ct1_mint: UncheckedAccount<'info>,
 
+    /// CHECK: created in the handler so that it can carry token metadata
     #[account(
-        init,
+        mut,
         seeds = [
             b"conditional_token2",
             vault_state.key().as_ref(),
         ],
         bump,
-        mint::decimals = 9,
-        mint::authority = authority,
-        payer = creator,
-        mint::token_program = token_program,
     )]
-    pub ct2_mint: Box<InterfaceAccount<'info, Mint>>,
+    pub ct2_mint: UncheckedAccount<'info>,
 
     pub ct1_token_program: Interface<'info, TokenInterface>,
     pub ct2_token_program: Interface<'info, TokenInterface>,
@@ -80,14 +95,262 @@ pub struct Initialize<'info> {
     pub vault: UncheckedAccount<'info>,
 
     pub collateral_mint: InterfaceAccount<'info, Mint>,
+    /// CHECK: the listing of the collateral mint, unlisted mints are rejected with NotSupportMint
+    #[account(
+        seeds = [
+            COLLATERAL_INFO_SEED.as_bytes(),
+            collateral_mint.key().as_ref(),
+        ],
+        bump,
+    )]
+    pub collateral_info: UncheckedAccount<'info>,
     pub collateral_token_program: Interface<'info, TokenInterface>,
 
     //     // the vault to store the collateral
     pub token_program: Interface<'info, TokenInterface>,
     pub system_program: Program<'info, System>,
+
+    // only needed for conditional tokens on the legacy token program, Token-2022 mints hold their own metadata
+    /// CHECK: the metaplex metadata account of ct1, validated by the metadata program
+    #[account(mut)]
+    pub ct1_metadata: Option<UncheckedAccount<'info>>,
+    /// CHECK: the metaplex metadata account of ct2, validated by the metadata program
+    #[account(mut)]
+    pub ct2_metadata: Option<UncheckedAccount<'info>>,
+    pub metadata_program: Option<Program<'info, Metadata>>,
+
+    // only needed for conditional tokens on Token-2022, their transfer hook reads these lists on every transfer
+    /// CHECK: the transfer hook program
+    #[account(address = CT_TRANSFER_HOOK_PROGRAM_ID)]
+    pub transfer_hook_program: Option<UncheckedAccount<'info>>,
+    /// CHECK: the extra account meta list of ct1, created by the transfer hook
+    #[account(
+        mut,
+        seeds = [EXTRA_ACCOUNT_METAS_SEED.as_bytes(), ct1_mint.key().as_ref()],
+        bump,
+        seeds::program = CT_TRANSFER_HOOK_PROGRAM_ID,
+    )]
+    pub ct1_extra_account_meta_list: Option<UncheckedAccount<'info>>,
+    /// CHECK: the extra account meta list of ct2, created by the transfer hook
+    #[account(
+        mut,
+        seeds = [EXTRA_ACCOUNT_METAS_SEED.as_bytes(), ct2_mint.key().as_ref()],
+        bump,
+        seeds::program = CT_TRANSFER_HOOK_PROGRAM_ID,
+    )]
+    pub ct2_extra_account_meta_list: Option<UncheckedAccount<'info>>,
+}
+
+impl<'info> Initialize<'info> {
+    /// Creates a conditional token mint with the market's authority pda as mint and metadata update authority
+    fn create_ct_mint(
+        &self,
+        mint: &AccountInfo<'info>,
+        metadata: Option<&UncheckedAccount<'info>>,
+        mint_seeds: &[&[u8]],
+        auth_bump: u8,
+        ct_metadata: CtMetadata,
+    ) -> Result<()> {
+        let vault_state_key = self.vault_state.key();
+        let authority_seeds: &[&[u8]] = &[crate::AUTH_SEED.as_bytes(), vault_state_key.as_ref(), &[auth_bump]];
+        let is_token_2022 = self.token_program.key() == Token2022::id();
+
+        let space = if is_token_2022 {
+            ExtensionType::try_calculate_account_len::<spl_token_2022::state::Mint>(&[
+                ExtensionType::MetadataPointer,
+                ExtensionType::TransferHook,
+                ExtensionType::MintCloseAuthority,
+            ])?
+        } else {
+            spl_token_2022::state::Mint::LEN
+        };
+        create_or_allocate_account(
+            self.token_program.key,
+            self.creator.to_account_info(),
+            self.system_program.to_account_info(),
+            mint.clone(),
+            mint_seeds,
+            space,
+        )?;
+
+        if is_token_2022 {
+            // the metadata lives on the mint itself
+            metadata_pointer_initialize(
+                CpiContext::new(
+                    self.token_program.to_account_info(),
+                    MetadataPointerInitialize {
+                        token_program_id: self.token_program.to_account_info(),
+                        mint: mint.clone(),
+                    },
+                ),
+                Some(self.authority.key()),
+                Some(mint.key()),
+            )?;
+            // secondary transfers go through the market's transfer rules
+            transfer_hook_initialize(
+                CpiContext::new(
+                    self.token_program.to_account_info(),
+                    TransferHookInitialize {
+                        token_program_id: self.token_program.to_account_info(),
+                        mint: mint.clone(),
+                    },
+                ),
+                Some(self.authority.key()),
+                Some(CT_TRANSFER_HOOK_PROGRAM_ID),
+            )?;
+            // lets close_market reclaim the mint's rent once its supply is burned
+            mint_close_authority_initialize(
+                CpiContext::new(
+                    self.token_program.to_account_info(),
+                    MintCloseAuthorityInitialize {
+                        token_program_id: self.token_program.to_account_info(),
+                        mint: mint.clone(),
+                    },
+                ),
+                Some(&self.authority.key()),
+            )?;
+        }
+        initialize_mint2(
+            CpiContext::new(
+                self.token_program.to_account_info(),
+                InitializeMint2 { mint: mint.clone() },
+            ),
+            ct_metadata.decimals,
+            &self.authority.key(),
+            None,
+        )?;
+
+        if is_token_2022 {
+            // token metadata reallocs the mint, fund the extra rent up front
+            let token_metadata = TokenMetadata {
+                update_authority: OptionalNonZeroPubkey::try_from(Some(self.authority.key()))?,
+                mint: mint.key(),
+                name: ct_metadata.name.clone(),
+                symbol: ct_metadata.symbol.clone(),
+                uri: ct_metadata.uri.clone(),
+                additional_metadata: vec![],
+            };
+            let rent = Rent::get()?;
+            let required_lamports = rent
+                .minimum_balance(space + token_metadata.tlv_size_of()?)
+                .saturating_sub(mint.lamports());
+            if required_lamports > 0 {
+                system_program::transfer(
+                    CpiContext::new(
+                        self.system_program.to_account_info(),
+                        system_program::Transfer {
+                            from: self.creator.to_account_info(),
+                            to: mint.clone(),
+                        },
+                    ),
+                    required_lamports,
+                )?;
+            }
+            token_metadata_initialize(
+                CpiContext::new_with_signer(
+                    self.token_program.to_account_info(),
+                    TokenMetadataInitialize {
+                        program_id: self.token_program.to_account_info(),
+                        metadata: mint.clone(),
+                        update_authority: self.authority.to_account_info(),
+                        mint_authority: self.authority.to_account_info(),
+                        mint: mint.clone(),
+                    },
+                    &[authority_seeds],
+                ),
+                ct_metadata.name,
+                ct_metadata.symbol,
+                ct_metadata.uri,
+            )?;
+        } else if let (Some(metadata), Some(metadata_program)) = (metadata, &self.metadata_program) {
+            create_metadata_accounts_v3(
+                CpiContext::new_with_signer(
+                    metadata_program.to_account_info(),
+                    CreateMetadataAccountsV3 {
+                        metadata: metadata.to_account_info(),
+                        mint: mint.clone(),
+                        mint_authority: self.authority.to_account_info(),
+                        payer: self.creator.to_account_info(),
+                        update_authority: self.authority.to_account_info(),
+                        system_program: self.system_program.to_account_info(),
+                        // CreateMetadataAccountV3 no longer reads the rent sysvar
+                        rent: self.system_program.to_account_info(),
+                    },
+                    &[authority_seeds],
+                ),
+                DataV2 {
+                    name: truncate(ct_metadata.name, MAX_NAME_LENGTH),
+                    symbol: truncate(ct_metadata.symbol, MAX_SYMBOL_LENGTH),
+                    uri: ct_metadata.uri,
+                    seller_fee_basis_points: 0,
+                    creators: None,
+                    collection: None,
+                    uses: None,
+                },
+                true,
+                true,
+                None,
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// Wallet facing name, symbol and uri of a conditional token
+pub struct CtMetadata {
+    pub decimals: u8,
+    pub name: String,
+    pub symbol: String,
+    pub uri: String,
+}
+
+impl CtMetadata {
+    pub fn new(outcome: &str, market_config: &MarketConfig, decimals: u8) -> Self {
+        Self {
+            decimals,
+            name: format!("{} – {}", outcome, market_config.name),
+            symbol: format!("{}-{}", outcome, market_config.display_index()),
+            uri: market_config.metadata.metadata_uri.clone(),
+        }
+    }
+}
+
+fn truncate(mut value: String, max_len: usize) -> String {
+    if value.len() > max_len {
+        let mut end = max_len;
+        while !value.is_char_boundary(end) {
+            end -= 1;
+        }
+        value.truncate(end);
+    }
+    value
 }
 
 pub fn initialize(ctx: Context<Initialize>) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+    ctx.accounts.market_config.trading_window().validate(now)?;
+
+    if ctx.accounts.collateral_info.owner != &crate::id() {
+        return Err(ErrorCode::NotSupportMint.into());
+    }
+    CollateralInfo::try_deserialize(&mut &ctx.accounts.collateral_info.try_borrow_data()?[..])?
+        .check_mint(&ctx.accounts.collateral_mint.to_account_info())?;
+
+    let vault_state_key = ctx.accounts.vault_state.key();
+    for (outcome, mint, metadata, seed, bump) in [
+        ("YES", &ctx.accounts.ct1_mint, ctx.accounts.ct1_metadata.as_ref(), b"conditional_token1", ctx.bumps.ct1_mint),
+        ("NO", &ctx.accounts.ct2_mint, ctx.accounts.ct2_metadata.as_ref(), b"conditional_token2", ctx.bumps.ct2_mint),
+    ] {
+        ctx.accounts.create_ct_mint(
+            &mint.to_account_info(),
+            metadata,
+            &[seed, vault_state_key.as_ref(), &[bump]],
+            ctx.bumps.authority,
+            // one conditional token per unit of collateral, so both show the same ui amount
+            CtMetadata::new(outcome, &ctx.accounts.market_config, ctx.accounts.collateral_mint.decimals),
+        )?;
+    }
+
     //this makes the passed vault account a token account that has some given seeds 
     create_token_account(
         &ctx.accounts.authority.to_account_info(),
@@ -118,18 +381,21 @@ pub fn initialize(ctx: Context<Initialize>) -> Result<()> {
         ctx.accounts.vault.key(),
         ctx.accounts.creator.key(),
         ctx.accounts.market_config.created_at,
-        ctx.accounts.market_config.expiration,
+        ctx.accounts.market_config.resolves_at,
         ctx.accounts.ct1_mint.key(),
         ctx.accounts.ct2_mint.key(),
         ctx.accounts.ct1_token_program.key(),
         ctx.accounts.ct2_token_program.key(),
     )?;
 
-    msg!("DEBUG Initialize - Authority bump: {}", ctx.bumps.authority);
-    msg!("DEBUG Initialize - Authority address: {}", ctx.accounts.authority.key());
-    msg!("DEBUG Initialize - Vault address: {}", ctx.accounts.vault.key());
-    msg!("DEBUG Initialize - Creator passed: {}", ctx.accounts.creator.key());
-    msg!("DEBUG Initialize - VaultState creator stored: {}", vault_state.vault_creator);
+    #[cfg(feature = "enable-log")]
+    {
+        msg!("DEBUG Initialize - Authority bump: {}", ctx.bumps.authority);
+        msg!("DEBUG Initialize - Authority address: {}", ctx.accounts.authority.key());
+        msg!("DEBUG Initialize - Vault address: {}", ctx.accounts.vault.key());
+        msg!("DEBUG Initialize - Creator passed: {}", ctx.accounts.creator.key());
+        msg!("DEBUG Initialize - VaultState creator stored: {}", vault_state.vault_creator);
+    }
 
     // update the market config with the vault state, vault, conditional token mints, and market resolution
     let market_config = ctx.accounts.market_config.deref_mut();
@@ -137,7 +403,41 @@ pub fn initialize(ctx: Context<Initialize>) -> Result<()> {
     market_config.vault = ctx.accounts.vault.key();
     market_config.ct1_mint = ctx.accounts.ct1_mint.key();
     market_config.ct2_mint = ctx.accounts.ct2_mint.key();
-    market_config.market_resolution = false;
+    market_config.status.transition(MarketStatus::Initialized)?;
+    // trading opens right away if trading_opens_at has passed
+    market_config.sync_status(now)?;
+
+    if ctx.accounts.token_program.key() == Token2022::id() {
+        let (Some(hook_program), Some(ct1_meta_list), Some(ct2_meta_list)) = (
+            &ctx.accounts.transfer_hook_program,
+            &ctx.accounts.ct1_extra_account_meta_list,
+            &ctx.accounts.ct2_extra_account_meta_list,
+        ) else {
+            return Err(ErrorCode::TransferHookAccountsMissing.into());
+        };
+        // the hook checks the mints against the market config, write it before the cpi
+        ctx.accounts.market_config.exit(&crate::id())?;
+        for (mint, meta_list) in [(&ctx.accounts.ct1_mint, ct1_meta_list), (&ctx.accounts.ct2_mint, ct2_meta_list)] {
+            initialize_extra_account_meta_list(
+                hook_program.to_account_info(),
+                ctx.accounts.creator.to_account_info(),
+                ctx.accounts.market_config.to_account_info(),
+                mint.to_account_info(),
+                meta_list.to_account_info(),
+                ctx.accounts.system_program.to_account_info(),
+            )?;
+        }
+    }
+
+    emit_cpi!(MarketInitialized {
+        market_config: ctx.accounts.market_config.key(),
+        vault_state: ctx.accounts.vault_state.key(),
+        vault: ctx.accounts.vault.key(),
+        creator: ctx.accounts.creator.key(),
+        collateral_mint: ctx.accounts.collateral_mint.key(),
+        ct1_mint: ctx.accounts.ct1_mint.key(),
+        ct2_mint: ctx.accounts.ct2_mint.key(),
+    });
     Ok(())
 }
    
@@ -150,7 +450,7 @@ pub fn create_vault_state<'info>(
 ) -> Result<AccountLoad<'info, VaultState>> {
     let (expect_pda_address, bump) = Pubkey::find_program_address(
         &[
-            b"vault_state",
+            VAULT_STATE_SEED.as_bytes(),
             market_config.key().as_ref(),
             collateral_mint.key().as_ref(),
         ],
@@ -167,7 +467,7 @@ pub fn create_vault_state<'info>(
         system_program.to_account_info(),
         vault_state.clone(),
         &[
-            b"vault_state",
+            VAULT_STATE_SEED.as_bytes(),
             market_config.key().as_ref(),
             collateral_mint.key().as_ref(),
             &[bump]
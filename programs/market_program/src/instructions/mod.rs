@@ -7,6 +7,15 @@ pub use get_reward::*;
 pub mod sell_bet;
 pub use sell_bet::*;
 
+pub mod close_position;
+pub use close_position::*;
+
+pub mod claim_for;
+pub use claim_for::*;
+
+pub mod batch_bet;
+pub use batch_bet::*;
+
 pub mod admin;
 pub use admin::*;
 
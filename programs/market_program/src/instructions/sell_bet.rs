@@ -6,10 +6,12 @@
 
 use crate::states::*;
 use crate::utils::*;
+use crate::events::*;
 use anchor_lang::prelude::*;
 use anchor_spl::associated_token::AssociatedToken;
 use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
 
+#[event_cpi]
 #[derive(Accounts)]
 pub struct SellBet<'info> {
     #[account(mut)]
@@ -20,30 +22,52 @@ pub struct SellBet<'info> {
         mut,
         seeds = [
             crate::AUTH_SEED.as_bytes(),
+            vault_state.key().as_ref(),
         ],
-        bump,
+        bump = vault_state.load()?.auth_bump,
     )]
     pub authority: UncheckedAccount<'info>,
 
     #[account(
         mut,
         token::mint = collateral_mint,
-        token::authority = bettor
+        token::authority = bettor,
+        token::token_program = collateral_token_program,
     )]
     pub collateral_account: Box<InterfaceAccount<'info, TokenAccount>>,
-    #[account(mut)]
-    pub vault_state: AccountLoader<'info, VaultState>,
+    #[account(
+        mut,
+        has_one = vault_state,
+        has_one = vault,
+        has_one = ct1_mint,
+        has_one = ct2_mint,
+    )]
+    pub market_config: Box<Account<'info, MarketConfig>>,
 
+    #[account(
+        mut,
+        seeds = [
+            VAULT_STATE_SEED.as_bytes(),
+            market_config.key().as_ref(),
+            collateral_mint.key().as_ref(),
+        ],
+        bump,
+        has_one = market_config,
+        has_one = vault,
+    )]
+    pub vault_state: AccountLoader<'info, VaultState>,
 
     #[account(
         mut,
-        constraint = vault.key() == vault_state.load()?.vault
+        token::mint = collateral_mint,
+        token::authority = authority,
+        token::token_program = collateral_token_program,
     )]
     pub vault: Box<InterfaceAccount<'info, TokenAccount>>,
 
-    #[account(mut)]
+    #[account(mut, mint::token_program = token_program)]
     pub ct1_mint: InterfaceAccount<'info, Mint>,
-    #[account(mut)]
+    #[account(mut, mint::token_program = token_program)]
     pub ct2_mint: InterfaceAccount<'info, Mint>,
 
     // user's token accounts for the conditional tokens
@@ -63,7 +87,7 @@ pub struct SellBet<'info> {
     )]
     pub ct2_account: InterfaceAccount<'info, TokenAccount>,
 
-    #[account(mut)]
+    #[account(mut, mint::token_program = collateral_token_program)]
     pub collateral_mint: InterfaceAccount<'info, Mint>,
     pub collateral_token_program: Interface<'info, TokenInterface>,
 
@@ -74,7 +98,11 @@ pub struct SellBet<'info> {
 }
 
 pub fn sell_bet(ctx: Context<SellBet>, amount: u64) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+    ctx.accounts.market_config.sync_status(now)?;
+    ctx.accounts.market_config.require_trading(now)?;
 
+    let vault_state_key = ctx.accounts.vault_state.key();
     let mut vault_state = ctx.accounts.vault_state.load_mut()?;
 
     // burn the conditional tokens - bettor owns these, so use regular CPI (no PDA signing)
@@ -102,7 +130,7 @@ pub fn sell_bet(ctx: Context<SellBet>, amount: u64) -> Result<()> {
         amount,
     )?;
 
-    transfer_from_collateral_vault_to_user(
+    let received = transfer_from_collateral_vault_to_user(
         ctx.accounts.authority.to_account_info(),
         ctx.accounts.vault.to_account_info(),
         ctx.accounts.collateral_account.to_account_info(),
@@ -110,10 +138,31 @@ pub fn sell_bet(ctx: Context<SellBet>, amount: u64) -> Result<()> {
         ctx.accounts.collateral_token_program.to_account_info(),
         amount,
         ctx.accounts.collateral_mint.decimals,
-        &[&[crate::AUTH_SEED.as_bytes(), &[vault_state.auth_bump]]],
+        &[&[crate::AUTH_SEED.as_bytes(), vault_state_key.as_ref(), &[vault_state.auth_bump]]],
     )?;
 
     vault_state.update_collateral_supply(amount, false)?;
 
+    // the vault must still back every conditional token in circulation
+    ctx.accounts.vault.reload()?;
+    ctx.accounts.ct1_mint.reload()?;
+    ctx.accounts.ct2_mint.reload()?;
+    vault_state.check_solvency(
+        ctx.accounts.vault.amount,
+        ctx.accounts.ct1_mint.supply,
+        ctx.accounts.ct2_mint.supply,
+    )?;
+
+    emit_cpi!(BetSold {
+        market_config: vault_state.market_config,
+        vault_state: ctx.accounts.vault_state.key(),
+        user: ctx.accounts.bettor.key(),
+        collateral_mint: ctx.accounts.collateral_mint.key(),
+        ct1_mint: ctx.accounts.ct1_mint.key(),
+        ct2_mint: ctx.accounts.ct2_mint.key(),
+        amount,
+        collateral_received: received,
+        vault_balance: vault_state.vault_collateral_balance,
+    });
     Ok(())
 }
@@ -4,19 +4,61 @@ pub mod instructions;
 pub mod utils;
 pub mod states;
 pub mod error;
+pub mod events;
 
 pub use states::*;
 pub use instructions::*;
 pub use utils::*;
 pub use error::*;
+pub use events::*;
 
 declare_id!("9iCxo1nJnDCtZTyKqFKc5PCFmfiezNnXCnycCrYq1GVL");
 
 #[program]
 pub mod market_program {
     use super::*;
-    pub fn create_market_config(ctx: Context<CreateMarketConfig>, index: u16, name: String, description: String, expiration: i64) -> Result<()> {
-        instructions::create_market_config(ctx, index, name, description, expiration)
+    pub fn create_market_config(ctx: Context<CreateMarketConfig>, name: String, description: String, category: MarketCategory, metadata: MarketMetadata, window: TradingWindow) -> Result<()> {
+        instructions::create_market_config(ctx, name, description, category, metadata, window)
+    }
+
+    pub fn set_transfer_rules(ctx: Context<SetTransferRules>, transfers_frozen: bool, restricted: bool) -> Result<()> {
+        instructions::set_transfer_rules(ctx, transfers_frozen, restricted)
+    }
+
+    pub fn set_collateral_info(ctx: Context<SetCollateralInfo>, params: CollateralParams) -> Result<()> {
+        instructions::set_collateral_info(ctx, params)
+    }
+
+    pub fn sync_registry_entry(ctx: Context<SyncRegistryEntry>) -> Result<()> {
+        instructions::sync_registry_entry(ctx)
+    }
+
+    pub fn update_market_metadata(ctx: Context<UpdateMarketMetadata>, name: Option<String>, description: Option<String>, category: Option<MarketCategory>, metadata: Option<MarketMetadata>) -> Result<()> {
+        instructions::update_market_metadata(ctx, name, description, category, metadata)
+    }
+
+    pub fn migrate_market_authority(ctx: Context<MigrateMarketAuthority>) -> Result<()> {
+        instructions::migrate_market_authority(ctx)
+    }
+
+    pub fn close_market(ctx: Context<CloseMarket>) -> Result<()> {
+        instructions::close_market(ctx)
+    }
+
+    pub fn migrate_market_config(ctx: Context<MigrateMarketConfig>) -> Result<()> {
+        instructions::migrate_market_config(ctx)
+    }
+
+    pub fn migrate_vault_state(ctx: Context<MigrateVaultState>) -> Result<()> {
+        instructions::migrate_vault_state(ctx)
+    }
+
+    pub fn reconcile_vault(ctx: Context<ReconcileVault>, sweep: bool) -> Result<()> {
+        instructions::reconcile_vault(ctx, sweep)
+    }
+
+    pub fn sweep_unclaimed(ctx: Context<SweepUnclaimed>) -> Result<()> {
+        instructions::sweep_unclaimed(ctx)
     }
 
     pub fn create_permissioned_pda(ctx: Context<CreatePermissionPda>) -> Result<()> {
@@ -35,20 +77,58 @@ pub mod market_program {
         instructions::initialize(ctx)
     }
 
-    pub fn buy_bet(ctx: Context<BuyBet>, bet_amount: u64) -> Result<()> {
-        instructions::buy_bet(ctx, bet_amount)
+    // with delegate_claim the market authority becomes the delegate of both conditional token
+    // accounts, replacing any existing delegate, so claim_for can redeem them after resolution
+    pub fn buy_bet(ctx: Context<BuyBet>, bet_amount: u64, delegate_claim: bool) -> Result<()> {
+        instructions::buy_bet(ctx, bet_amount, delegate_claim)
     }
 
     pub fn sell_bet(ctx: Context<SellBet>, bet_amount: u64) -> Result<()> {
         instructions::sell_bet(ctx, bet_amount)
     }
 
+    pub fn batch_buy_bet<'info>(
+        ctx: Context<'_, '_, 'info, 'info, BatchBet<'info>>,
+        entries: Vec<BatchEntry>,
+        delegate_claim: bool,
+    ) -> Result<()> {
+        instructions::batch_buy_bet(ctx, entries, delegate_claim)
+    }
+
+    pub fn batch_sell_bet<'info>(ctx: Context<'_, '_, 'info, 'info, BatchBet<'info>>, entries: Vec<BatchEntry>) -> Result<()> {
+        instructions::batch_sell_bet(ctx, entries)
+    }
+
     pub fn get_reward(ctx: Context<GetReward>, reward_amount: u64) -> Result<()> {
         instructions::get_reward(ctx, reward_amount)
     }
 
-    pub fn resolve_market(ctx: Context<ResolveMarket>) -> Result<()> {
-        instructions::resolve_market(ctx)
+    pub fn claim_for<'info>(ctx: Context<'_, '_, 'info, 'info, ClaimFor<'info>>) -> Result<()> {
+        instructions::claim_for(ctx)
+    }
+
+    pub fn close_position(ctx: Context<ClosePosition>) -> Result<()> {
+        instructions::close_position(ctx)
+    }
+
+    pub fn resolve_market(ctx: Context<ResolveMarket>, report: ResolutionReport) -> Result<()> {
+        instructions::resolve_market(ctx, report)
+    }
+
+    pub fn invalidate_unconfigured_market(ctx: Context<InvalidateUnconfiguredMarket>) -> Result<()> {
+        instructions::invalidate_unconfigured_market(ctx)
+    }
+
+    pub fn set_start_price_pending(ctx: Context<SetStartPricePending>, pending: bool) -> Result<()> {
+        instructions::set_start_price_pending(ctx, pending)
+    }
+
+    pub fn finalize_market(ctx: Context<FinalizeMarket>) -> Result<()> {
+        instructions::finalize_market(ctx)
+    }
+
+    pub fn cancel_market(ctx: Context<CancelMarket>) -> Result<()> {
+        instructions::cancel_market(ctx)
     }
 
 }
@@ -0,0 +1,91 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_2022::{
+    spl_token_2022::{
+        extension::{BaseStateWithExtensions, StateWithExtensions},
+        state::Mint,
+    },
+    Token2022,
+};
+use crate::error::ErrorCode;
+
+pub const COLLATERAL_INFO_SEED: &str = "collateral_info";
+
+/// Admin managed listing of a mint that markets can use as collateral
+#[account]
+#[derive(Default, Debug)]
+#[derive(InitSpace)]
+pub struct CollateralInfo {
+    pub bump: u8,
+    pub mint: Pubkey,
+    /// new markets and bets are only accepted while enabled
+    pub enabled: bool,
+    /// cap on the collateral a single market can hold, 0 for no cap
+    pub max_open_interest: u64,
+    pub min_bet: u64,
+    /// bit `n` allows the Token-2022 extension with `ExtensionType` value `n`
+    pub allowed_extensions: u64,
+    /// whether the mint may have a freeze authority, e.g. USDC
+    pub allow_freeze_authority: bool,
+    /// token account that receives vault surpluses swept by reconcile_vault, default to disable sweeping
+    pub treasury: Pubkey,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default)]
+pub struct CollateralParams {
+    pub enabled: bool,
+    pub max_open_interest: u64,
+    pub min_bet: u64,
+    pub allowed_extensions: u64,
+    pub allow_freeze_authority: bool,
+    pub treasury: Pubkey,
+}
+
+impl CollateralInfo {
+    pub fn set(&mut self, params: CollateralParams) {
+        self.enabled = params.enabled;
+        self.max_open_interest = params.max_open_interest;
+        self.min_bet = params.min_bet;
+        self.allowed_extensions = params.allowed_extensions;
+        self.allow_freeze_authority = params.allow_freeze_authority;
+        self.treasury = params.treasury;
+    }
+
+    /// Fails unless the listing is enabled and the mint only uses what it allows
+    pub fn check_mint(&self, mint: &AccountInfo) -> Result<()> {
+        if !self.enabled || self.mint != mint.key() {
+            return Err(ErrorCode::NotSupportMint.into());
+        }
+        let mint_data = mint.try_borrow_data()?;
+        let mint_state = StateWithExtensions::<Mint>::unpack(&mint_data)?;
+        if mint_state.base.freeze_authority.is_some() && !self.allow_freeze_authority {
+            return Err(ErrorCode::NotSupportMint.into());
+        }
+        if *mint.owner == Token2022::id() {
+            for extension in mint_state.get_extension_types()? {
+                let bit = u16::from(extension) as u32;
+                if bit >= u64::BITS || self.allowed_extensions & (1 << bit) == 0 {
+                    msg!("Collateral extension {:?} not allowed", extension);
+                    return Err(ErrorCode::NotSupportMint.into());
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Fails unless a bet of `amount` is accepted on a vault holding `vault_balance`
+    pub fn check_bet(&self, amount: u64, vault_balance: u64) -> Result<()> {
+        if !self.enabled {
+            return Err(ErrorCode::NotSupportMint.into());
+        }
+        if amount < self.min_bet {
+            return Err(ErrorCode::BetTooSmall.into());
+        }
+        let open_interest = vault_balance
+            .checked_add(amount)
+            .ok_or(ErrorCode::MathOverflow)?;
+        if self.max_open_interest != 0 && open_interest > self.max_open_interest {
+            return Err(ErrorCode::OpenInterestExceeded.into());
+        }
+        Ok(())
+    }
+}
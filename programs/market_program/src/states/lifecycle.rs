@@ -0,0 +1,97 @@
+use anchor_lang::prelude::*;
+use crate::error::ErrorCode;
+
+/// The lifecycle of a market. All status changes go through `MarketStatus::transition`
+/// so a market can never skip or reverse a step.
+///
+/// Created -> Initialized -> Trading -> Closed -> Resolved -> Finalized
+///
+/// A market can be Cancelled before trading opens and is Invalid if it could not be resolved.
+/// A touched barrier closes trading early, the market still passes through Closed.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq, InitSpace)]
+pub enum MarketStatus {
+    /// the market config exists
+    #[default]
+    Created,
+    /// the vault and conditional token mints exist
+    Initialized,
+    /// conditional token sets can be bought and sold
+    Trading,
+    /// trading has ended, waiting for the oracle
+    Closed,
+    /// the outcome is known, winning tokens can be redeemed
+    Resolved,
+    /// the outcome can no longer change
+    Finalized,
+    /// the market was called off before trading opened
+    Cancelled,
+    /// the market could not be resolved, both tokens refund half a unit of collateral
+    Invalid,
+}
+
+impl MarketStatus {
+    pub fn can_transition_to(self, next: MarketStatus) -> bool {
+        use MarketStatus::*;
+        matches!(
+            (self, next),
+            (Created, Initialized)
+                | (Created, Cancelled)
+                | (Initialized, Trading)
+                | (Initialized, Cancelled)
+                | (Trading, Closed)
+                | (Closed, Resolved)
+                | (Closed, Invalid)
+                | (Resolved, Finalized)
+        )
+    }
+
+    pub fn transition(&mut self, next: MarketStatus) -> Result<()> {
+        if !self.can_transition_to(next) {
+            msg!("Illegal market status transition {:?} -> {:?}", self, next);
+            return Err(ErrorCode::InvalidStatusTransition.into());
+        }
+        *self = next;
+        Ok(())
+    }
+
+    /// Fails unless the market is in one of the `allowed` statuses
+    pub fn require_one_of(self, allowed: &[MarketStatus]) -> Result<()> {
+        if !allowed.contains(&self) {
+            msg!("Market status {:?} not in {:?}", self, allowed);
+            return Err(ErrorCode::InvalidMarketStatus.into());
+        }
+        Ok(())
+    }
+
+    /// Whether conditional tokens can be redeemed for collateral
+    pub fn is_redeemable(self) -> bool {
+        matches!(
+            self,
+            MarketStatus::Resolved | MarketStatus::Finalized | MarketStatus::Invalid
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn resolution_requires_closed_trading() {
+        let mut status = MarketStatus::Trading;
+        assert!(status.transition(MarketStatus::Resolved).is_err());
+        assert!(status.transition(MarketStatus::Invalid).is_err());
+        status.transition(MarketStatus::Closed).unwrap();
+        status.transition(MarketStatus::Resolved).unwrap();
+        status.transition(MarketStatus::Finalized).unwrap();
+        assert_eq!(status, MarketStatus::Finalized);
+    }
+
+    #[test]
+    fn cancel_only_before_trading() {
+        assert!(MarketStatus::Created.can_transition_to(MarketStatus::Cancelled));
+        assert!(MarketStatus::Initialized.can_transition_to(MarketStatus::Cancelled));
+        assert!(!MarketStatus::Trading.can_transition_to(MarketStatus::Cancelled));
+        assert!(!MarketStatus::Resolved.can_transition_to(MarketStatus::Trading));
+    }
+}
@@ -1,15 +1,33 @@
 use anchor_lang::prelude::*;
+use crate::error::ErrorCode;
+use crate::states::MarketStatus;
 
 pub const MARKET_CONFIG_SEED: &str = "market_config";
+/// seeds configs created through the market counter, apart from the caller indexed
+/// configs of the first version so a counter value can never land on one of them
+pub const INDEXED_MARKET_CONFIG_SEED: &str = "indexed_market_config";
+pub const MAX_NAME_LEN: usize = 100;
+pub const MAX_DESCRIPTION_LEN: usize = 100;
+pub const MAX_URI_LEN: usize = 200;
+pub const MAX_RESOLUTION_SOURCE_LEN: usize = 200;
+pub const MAX_TAGS: usize = 5;
+pub const MAX_TAG_LEN: usize = 32;
+pub const MARKET_CONFIG_VERSION: u8 = 1;
+/// the shortest claim window a market can set, holders get at least this long to redeem after resolution
+pub const MIN_CLAIM_WINDOW: i64 = 7 * 24 * 60 * 60;
 
 /// Holds the current owner of the factory
+///
+/// Fields up to `market_resolution` keep the layout of the first deployed version,
+/// new fixed size fields are carved out of `padding` and variable size ones are appended.
 #[account]
 #[derive(Default, Debug)]
 #[derive(InitSpace)]
 pub struct MarketConfig {
     /// Bump to identify PDA
     pub bump: u8,
-    /// Config index
+    /// Config index chosen by the creator of a first version config, 0 for configs created
+    /// through the market counter, see `market_index`
     pub index: u16,
     /// The market owner and creator
     pub owner: Pubkey,
@@ -21,10 +39,37 @@ pub struct MarketConfig {
     pub description: String,
     /// the market created at
     pub created_at: i64,
-    /// the market expiration
-    pub expiration: i64,
+    /// the oracle resolves the market from this time on, this was the market expiration
+    pub resolves_at: i64,
+    /// conditional token sets can be bought and sold from trading_opens_at until
+    /// halt_buffer seconds before trading_closes_at
+    pub trading_opens_at: i64,
+    pub trading_closes_at: i64,
+    pub halt_buffer: i64,
+    /// get_reward stops accepting claims this many seconds after resolution, 0 to accept them forever
+    pub claim_window: i64,
+    /// when the oracle resolved the market, 0 until then
+    pub resolved_at: i64,
+    // where the market is in its lifecycle
+    pub status: MarketStatus,
+    /// enforced by the transfer hook on Token-2022 conditional tokens:
+    /// no transfers while frozen, only to allowlisted wallets while restricted
+    pub transfers_frozen: bool,
+    pub restricted: bool,
+    /// the collateral left after the claim deadline went to the treasury
+    pub swept: bool,
+    /// MARKET_CONFIG_VERSION once the fields above are set, 0 for configs created before them
+    pub version: u8,
+    /// set by the oracle adapter until it recorded the start prices of a relative performance market,
+    /// the market cannot trade before that
+    pub start_price_pending: bool,
+    /// the market has an entry in the registry, markets created before the registry have none
+    pub registered: bool,
+    pub reserved: [u8; 1],
+    /// handed out by the market counter, also the market's position in the registry
+    pub market_index: u64,
     /// padding
-    pub padding: [u64; 15],
+    pub padding: [u64; 8],
     // the vault token account for the collateral
     pub vault_state: Pubkey,
 
@@ -37,5 +82,322 @@ pub struct MarketConfig {
     pub ct1_vault_token_account: Pubkey,
     pub ct2_vault_token_account: Pubkey,
 
+    // set once the market is resolved, kept for clients of the first version
     pub market_resolution: bool,
-}
\ No newline at end of file
+
+    pub category: MarketCategory,
+
+    pub metadata: MarketMetadata,
+}
+
+/// Off-chain details of a market, fixed once trading opens so that UIs and
+/// dispute resolvers see the exact rules the market traded under
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, Default, PartialEq, Eq, InitSpace)]
+pub struct MarketMetadata {
+    /// IPFS or Arweave URI of the full market document
+    #[max_len(MAX_URI_LEN)]
+    pub metadata_uri: String,
+    /// sha256 of the full resolution rules text
+    pub rules_hash: [u8; 32],
+    #[max_len(MAX_TAGS, MAX_TAG_LEN)]
+    pub tags: Vec<String>,
+    #[max_len(MAX_URI_LEN)]
+    pub image_uri: String,
+    /// where the outcome comes from, e.g. the price feed and its exchange
+    #[max_len(MAX_RESOLUTION_SOURCE_LEN)]
+    pub resolution_source: String,
+}
+
+impl MarketMetadata {
+    pub fn validate(&self) -> Result<()> {
+        if self.metadata_uri.len() > MAX_URI_LEN
+            || self.image_uri.len() > MAX_URI_LEN
+            || self.resolution_source.len() > MAX_RESOLUTION_SOURCE_LEN
+            || self.tags.len() > MAX_TAGS
+            || self.tags.iter().any(|tag| tag.len() > MAX_TAG_LEN)
+        {
+            return Err(ErrorCode::MetadataTooLong.into());
+        }
+        Ok(())
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq, InitSpace)]
+pub enum MarketCategory {
+    #[default]
+    Other,
+    Crypto,
+    Economics,
+    Politics,
+    Sports,
+    Weather,
+}
+
+/// The trading schedule of a market, set at creation
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default)]
+pub struct TradingWindow {
+    pub trading_opens_at: i64,
+    pub trading_closes_at: i64,
+    pub resolves_at: i64,
+    /// trading halts this many seconds before trading_closes_at, 0 for no halt
+    pub halt_buffer: i64,
+    /// winning tokens can be redeemed for this many seconds after resolution, at least MIN_CLAIM_WINDOW
+    pub claim_window: Option<i64>,
+}
+
+impl TradingWindow {
+    /// opens < closes <= resolves, a claim window of at least MIN_CLAIM_WINDOW, the halt buffer fits within the window and trading has not closed yet
+    pub fn validate(&self, now: i64) -> Result<()> {
+        let window = self
+            .trading_closes_at
+            .checked_sub(self.trading_opens_at)
+            .ok_or(ErrorCode::MathOverflow)?;
+        if window <= 0
+            || self.trading_closes_at > self.resolves_at
+            || self.halt_buffer < 0
+            || self.halt_buffer >= window
+            || self.trading_closes_at <= now
+            || self.claim_window.is_some_and(|claim_window| claim_window < MIN_CLAIM_WINDOW)
+        {
+            return Err(ErrorCode::InvalidTradingWindow.into());
+        }
+        Ok(())
+    }
+}
+
+pub fn validate_metadata(name: &str, description: &str) -> Result<()> {
+    if name.len() > MAX_NAME_LEN || description.len() > MAX_DESCRIPTION_LEN {
+        return Err(ErrorCode::MetadataTooLong.into());
+    }
+    Ok(())
+}
+
+impl MarketConfig {
+    pub fn trading_window(&self) -> TradingWindow {
+        TradingWindow {
+            trading_opens_at: self.trading_opens_at,
+            trading_closes_at: self.trading_closes_at,
+            resolves_at: self.resolves_at,
+            halt_buffer: self.halt_buffer,
+            claim_window: (self.claim_window != 0).then_some(self.claim_window),
+        }
+    }
+
+    /// Applies the status transitions that only depend on time:
+    /// trading opens at trading_opens_at and closes at trading_closes_at.
+    pub fn sync_status(&mut self, now: i64) -> Result<()> {
+        if self.status == MarketStatus::Initialized && now >= self.trading_opens_at {
+            self.status.transition(MarketStatus::Trading)?;
+        }
+        if self.status == MarketStatus::Trading && now >= self.trading_closes_at {
+            self.status.transition(MarketStatus::Closed)?;
+        }
+        Ok(())
+    }
+
+    /// Metadata can only change until trading opens
+    pub fn require_metadata_mutable(&self, now: i64) -> Result<()> {
+        self.status
+            .require_one_of(&[MarketStatus::Created, MarketStatus::Initialized])?;
+        if now >= self.trading_opens_at {
+            return Err(ErrorCode::MetadataFrozen.into());
+        }
+        Ok(())
+    }
+
+    /// Whether the claim window, if any, has run out at `now`. It starts when the market is
+    /// actually resolved, so a late resolution never cuts it short.
+    pub fn claims_closed(&self, now: i64) -> bool {
+        self.claim_window != 0
+            && self.resolved_at != 0
+            && now >= self.resolved_at.saturating_add(self.claim_window)
+    }
+
+    /// Fills the fields a config written by the first deployed program (version 0) lacks.
+    /// Trading ran from creation until the expiration, now `resolves_at`, and `market_resolution` was the only status.
+    pub fn upgrade(&mut self) -> Result<()> {
+        if self.version >= MARKET_CONFIG_VERSION {
+            return Err(ErrorCode::MarketConfigUpToDate.into());
+        }
+        self.trading_opens_at = self.created_at;
+        self.trading_closes_at = self.resolves_at;
+        self.halt_buffer = 0;
+        self.claim_window = 0;
+        // the resolution time of markets resolved before this field existed is unknown, they have no claim window
+        self.resolved_at = 0;
+        // a config cancelled before it was migrated stays cancelled
+        self.status = if self.status == MarketStatus::Cancelled {
+            MarketStatus::Cancelled
+        } else if self.market_resolution {
+            MarketStatus::Resolved
+        } else if self.vault_state != Pubkey::default() {
+            // sync_status moves it on to Trading and Closed
+            MarketStatus::Initialized
+        } else {
+            MarketStatus::Created
+        };
+        self.transfers_frozen = false;
+        self.restricted = false;
+        self.swept = false;
+        self.start_price_pending = false;
+        self.registered = false;
+        self.reserved = [0; 1];
+        self.market_index = 0;
+        self.version = MARKET_CONFIG_VERSION;
+        Ok(())
+    }
+
+    /// The number clients know the market by, its counter index or the index of a first version config
+    pub fn display_index(&self) -> u64 {
+        if self.registered {
+            self.market_index
+        } else {
+            self.index.into()
+        }
+    }
+
+    /// Whether `mint` is one of this market's conditional tokens
+    pub fn is_ct_mint(&self, mint: &Pubkey) -> bool {
+        *mint == self.ct1_mint || *mint == self.ct2_mint
+    }
+
+    /// Fails unless sets can be bought and sold at `now`, call after `sync_status`
+    pub fn require_trading(&self, now: i64) -> Result<()> {
+        if self.status == MarketStatus::Initialized {
+            return Err(ErrorCode::TradingNotOpen.into());
+        }
+        self.status.require_one_of(&[MarketStatus::Trading])?;
+        if self.start_price_pending {
+            return Err(ErrorCode::StartPricePending.into());
+        }
+        if now >= self.trading_closes_at.saturating_sub(self.halt_buffer) {
+            return Err(ErrorCode::TradingHalted.into());
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // a market config as the first deployed program wrote it, zero-extended to the current size
+    fn v0_account(market_resolution: bool, vault_state: Pubkey) -> Vec<u8> {
+        let mut data = Vec::new();
+        data.extend_from_slice(MarketConfig::DISCRIMINATOR);
+        data.push(255); // bump
+        data.extend_from_slice(&7u16.to_le_bytes()); // index
+        data.extend_from_slice(&[1; 32]); // owner
+        for text in ["BTC above 100k", "Resolves on the pyth BTC/USD price"] {
+            data.extend_from_slice(&(text.len() as u32).to_le_bytes());
+            data.extend_from_slice(text.as_bytes());
+        }
+        data.extend_from_slice(&1_700_000_000i64.to_le_bytes()); // created_at
+        data.extend_from_slice(&1_800_000_000i64.to_le_bytes()); // expiration
+        data.extend_from_slice(&[0; 8 * 15]); // padding
+        data.extend_from_slice(vault_state.as_ref());
+        for byte in 3..8 {
+            // vault, ct1_mint, ct2_mint, ct1_vault_token_account, ct2_vault_token_account
+            data.extend_from_slice(&[byte; 32]);
+        }
+        data.push(market_resolution as u8);
+        data.resize(8 + MarketConfig::INIT_SPACE, 0);
+        data
+    }
+
+    fn load(data: &[u8]) -> MarketConfig {
+        MarketConfig::try_deserialize(&mut &data[..]).unwrap()
+    }
+
+    #[test]
+    fn v0_fields_keep_their_positions() {
+        let market_config = load(&v0_account(false, Pubkey::new_from_array([2; 32])));
+        assert_eq!(market_config.bump, 255);
+        assert_eq!(market_config.index, 7);
+        assert_eq!(market_config.owner, Pubkey::new_from_array([1; 32]));
+        assert_eq!(market_config.name, "BTC above 100k");
+        assert_eq!(market_config.created_at, 1_700_000_000);
+        assert_eq!(market_config.resolves_at, 1_800_000_000);
+        assert_eq!(market_config.vault_state, Pubkey::new_from_array([2; 32]));
+        assert_eq!(market_config.vault, Pubkey::new_from_array([3; 32]));
+        assert_eq!(market_config.ct2_vault_token_account, Pubkey::new_from_array([7; 32]));
+        assert_eq!(market_config.version, 0);
+        assert_eq!(market_config.metadata, MarketMetadata::default());
+    }
+
+    #[test]
+    fn upgrade_translates_v0() {
+        let mut market_config = load(&v0_account(false, Pubkey::new_from_array([2; 32])));
+        market_config.upgrade().unwrap();
+        assert_eq!(market_config.trading_opens_at, 1_700_000_000);
+        assert_eq!(market_config.trading_closes_at, 1_800_000_000);
+        assert_eq!(market_config.status, MarketStatus::Initialized);
+        assert_eq!(market_config.version, MARKET_CONFIG_VERSION);
+        market_config.sync_status(1_800_000_000).unwrap();
+        assert_eq!(market_config.status, MarketStatus::Closed);
+        assert!(market_config.upgrade().is_err());
+
+        let mut market_config = load(&v0_account(true, Pubkey::new_from_array([2; 32])));
+        market_config.upgrade().unwrap();
+        assert_eq!(market_config.status, MarketStatus::Resolved);
+
+        let mut market_config = load(&v0_account(false, Pubkey::default()));
+        market_config.upgrade().unwrap();
+        assert_eq!(market_config.status, MarketStatus::Created);
+        // unregistered, so it keeps the index it was created with
+        assert_eq!(market_config.display_index(), 7);
+    }
+
+    #[test]
+    fn upgrade_keeps_a_cancellation_made_before_migration() {
+        // cancel_market on an unmigrated config, which reads as Created
+        let mut market_config = load(&v0_account(false, Pubkey::default()));
+        market_config.sync_status(1_750_000_000).unwrap();
+        market_config.status.transition(MarketStatus::Cancelled).unwrap();
+
+        market_config.upgrade().unwrap();
+        assert_eq!(market_config.status, MarketStatus::Cancelled);
+        market_config.sync_status(1_800_000_000).unwrap();
+        assert_eq!(market_config.status, MarketStatus::Cancelled);
+    }
+
+    #[test]
+    fn counter_created_configs_use_the_market_index() {
+        let market_config = MarketConfig {
+            market_index: u64::from(u16::MAX) + 1,
+            registered: true,
+            ..Default::default()
+        };
+        assert_eq!(market_config.display_index(), 65_536);
+    }
+
+    #[test]
+    fn claim_window_runs_from_resolution() {
+        let window = TradingWindow {
+            trading_opens_at: 100,
+            trading_closes_at: 200,
+            resolves_at: 200,
+            halt_buffer: 0,
+            claim_window: Some(MIN_CLAIM_WINDOW),
+        };
+        assert!(window.validate(150).is_ok());
+        assert!(TradingWindow { claim_window: Some(MIN_CLAIM_WINDOW - 1), ..window }.validate(150).is_err());
+        assert!(TradingWindow { claim_window: None, ..window }.validate(150).is_ok());
+
+        let mut market_config = MarketConfig {
+            resolves_at: 200,
+            claim_window: MIN_CLAIM_WINDOW,
+            ..Default::default()
+        };
+        // not resolved yet, however long after resolves_at
+        assert!(!market_config.claims_closed(200 + 2 * MIN_CLAIM_WINDOW));
+
+        // resolved late, the window still starts at the resolution
+        market_config.resolved_at = 200 + MIN_CLAIM_WINDOW;
+        assert!(!market_config.claims_closed(200 + 2 * MIN_CLAIM_WINDOW - 1));
+        assert!(market_config.claims_closed(200 + 2 * MIN_CLAIM_WINDOW));
+
+        market_config.claim_window = 0;
+        assert!(!market_config.claims_closed(i64::MAX));
+    }
+}
@@ -0,0 +1,22 @@
+use anchor_lang::prelude::*;
+use crate::error::ErrorCode;
+
+pub const MARKET_COUNTER_SEED: &str = "market_counter";
+
+/// Hands out market indexes, each market config is seeded by the next one
+#[account]
+#[derive(Default, Debug)]
+#[derive(InitSpace)]
+pub struct MarketCounter {
+    pub bump: u8,
+    /// number of markets created so far, also the index of the next market
+    pub count: u64,
+}
+
+impl MarketCounter {
+    pub fn next_index(&mut self) -> Result<u64> {
+        let index = self.count;
+        self.count = self.count.checked_add(1).ok_or(ErrorCode::MathOverflow)?;
+        Ok(index)
+    }
+}
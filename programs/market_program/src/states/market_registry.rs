@@ -0,0 +1,147 @@
+use anchor_lang::prelude::*;
+use crate::error::ErrorCode;
+use crate::states::{MarketCategory, MarketConfig, MarketStatus};
+
+pub const MARKET_REGISTRY_SEED: &str = "market_registry";
+/// markets per registry page, market `index` lives on page `index / REGISTRY_PAGE_CAPACITY`
+pub const REGISTRY_PAGE_CAPACITY: u64 = 64;
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, InitSpace)]
+pub struct RegistryEntry {
+    pub market_config: Pubkey,
+    pub category: MarketCategory,
+    pub status: MarketStatus,
+    pub trading_closes_at: i64,
+    pub resolves_at: i64,
+    /// the market's accounts were closed, the entry is kept so indices stay stable
+    pub closed: bool,
+}
+
+impl RegistryEntry {
+    pub fn from_config(market_config: Pubkey, config: &MarketConfig) -> Self {
+        Self {
+            market_config,
+            category: config.category,
+            status: config.status,
+            trading_closes_at: config.trading_closes_at,
+            resolves_at: config.resolves_at,
+            closed: false,
+        }
+    }
+}
+
+/// A page of the global market registry, markets are appended in creation order so
+/// clients can list every market with one getMultipleAccounts call over the pages
+#[account]
+#[derive(Default, Debug)]
+#[derive(InitSpace)]
+pub struct MarketRegistryPage {
+    pub bump: u8,
+    pub page: u64,
+    #[max_len(REGISTRY_PAGE_CAPACITY)]
+    pub entries: Vec<RegistryEntry>,
+}
+
+impl MarketRegistryPage {
+    pub fn page_of(index: u64) -> u64 {
+        index / REGISTRY_PAGE_CAPACITY
+    }
+
+    /// Appends the market with the given index, markets must be added in index order
+    pub fn push(&mut self, index: u64, entry: RegistryEntry) -> Result<()> {
+        let slot = self
+            .page
+            .checked_mul(REGISTRY_PAGE_CAPACITY)
+            .and_then(|first| index.checked_sub(first))
+            .ok_or(ErrorCode::InvalidRegistryPage)?;
+        if slot != self.entries.len() as u64 || slot >= REGISTRY_PAGE_CAPACITY {
+            return Err(ErrorCode::InvalidRegistryPage.into());
+        }
+        self.entries.push(entry);
+        Ok(())
+    }
+
+    /// Copies a registered market's current state into its entry. Instructions that change a market's status
+    /// call this so the registry never lags behind, markets created before the registry are skipped.
+    pub fn sync<'a>(
+        page: Option<&'a mut MarketRegistryPage>,
+        market_config: Pubkey,
+        config: &MarketConfig,
+    ) -> Result<Option<&'a mut RegistryEntry>> {
+        if !config.registered {
+            return Ok(None);
+        }
+        let page = page.ok_or(ErrorCode::RegistryPageMissing)?;
+        let entry = page.entry_mut(config.market_index)?;
+        require_keys_eq!(entry.market_config, market_config);
+        *entry = RegistryEntry::from_config(market_config, config);
+        Ok(Some(entry))
+    }
+
+    pub fn entry_mut(&mut self, index: u64) -> Result<&mut RegistryEntry> {
+        if Self::page_of(index) != self.page {
+            return Err(ErrorCode::InvalidRegistryPage.into());
+        }
+        self.entries
+            .get_mut((index % REGISTRY_PAGE_CAPACITY) as usize)
+            .ok_or(ErrorCode::InvalidRegistryPage.into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn registered_page(market_config: Pubkey, config: &MarketConfig) -> MarketRegistryPage {
+        let mut page = MarketRegistryPage {
+            page: MarketRegistryPage::page_of(config.market_index),
+            ..Default::default()
+        };
+        for index in 0..=config.market_index % REGISTRY_PAGE_CAPACITY {
+            let key = if index == config.market_index % REGISTRY_PAGE_CAPACITY {
+                market_config
+            } else {
+                Pubkey::new_unique()
+            };
+            page.push(page.page * REGISTRY_PAGE_CAPACITY + index, RegistryEntry::from_config(key, config))
+                .unwrap();
+        }
+        page
+    }
+
+    #[test]
+    fn sync_copies_the_status_of_registered_markets() {
+        let key = Pubkey::new_unique();
+        let mut config = MarketConfig {
+            market_index: 66,
+            registered: true,
+            status: MarketStatus::Closed,
+            ..Default::default()
+        };
+        let mut page = registered_page(key, &config);
+
+        config.status = MarketStatus::Resolved;
+        let entry = MarketRegistryPage::sync(Some(&mut page), key, &config).unwrap().unwrap();
+        assert_eq!(entry.status, MarketStatus::Resolved);
+        assert_eq!(page.entries[2].status, MarketStatus::Resolved);
+        assert_eq!(page.entries[1].status, MarketStatus::Closed);
+    }
+
+    #[test]
+    fn sync_requires_the_page_of_registered_markets() {
+        let key = Pubkey::new_unique();
+        let config = MarketConfig {
+            market_index: 3,
+            registered: true,
+            ..Default::default()
+        };
+        let mut page = registered_page(key, &config);
+
+        assert!(MarketRegistryPage::sync(None, key, &config).is_err());
+        // another market's entry at the same index
+        assert!(MarketRegistryPage::sync(Some(&mut page), Pubkey::new_unique(), &config).is_err());
+        // markets created before the registry have no entry to update
+        let legacy = MarketConfig { registered: false, ..config };
+        assert!(MarketRegistryPage::sync(None, key, &legacy).unwrap().is_none());
+    }
+}
@@ -12,4 +12,28 @@ pub use permission::*;
 
 pub mod oracle_authority;
 
-pub use oracle_authority::*;
\ No newline at end of file
+pub use oracle_authority::*;
+
+pub mod resolution_record;
+
+pub use resolution_record::*;
+
+pub mod lifecycle;
+
+pub use lifecycle::*;
+
+pub mod market_counter;
+
+pub use market_counter::*;
+
+pub mod market_registry;
+
+pub use market_registry::*;
+
+pub mod transfer_hook;
+
+pub use transfer_hook::*;
+
+pub mod collateral_info;
+
+pub use collateral_info::*;
\ No newline at end of file
@@ -1,9 +1,16 @@
 use anchor_lang::prelude::*;
 
-#[account]
-#[derive(Default, InitSpace)]
-pub struct OracleAuthority {
-  pub bump: u8,
-  pub resolution_price: i64,
-  pub target_price: i64,
-}
\ No newline at end of file
+pub const ORACLE_AUTHORITY_SEED: &str = "oracle-adapter-pda";
+
+/// The oracle adapter contract, the only program allowed to resolve markets.
+/// Its `[ORACLE_AUTHORITY_SEED]` pda signs the `resolve_market` cpi.
+pub const ORACLE_ADAPTER_PROGRAM_ID: Pubkey =
+    pubkey!("6QAq31696E4a8PKMgzVrTq1uBzvF87JNTeAQyHmGivEJ");
+
+/// Seeds of the adapter's accounts of a market, `close_market` requires them to be closed first
+pub const ADAPTER_RESOLUTION_CONFIG_SEED: &str = "resolution_config";
+pub const ADAPTER_BARRIER_TRACKER_SEED: &str = "barrier_tracker";
+
+/// How long after `resolves_at` a market without an adapter resolution config can be invalidated by anyone.
+/// Matches the adapter's longest grace period, so a configured market is never cut short by this.
+pub const UNCONFIGURED_MARKET_GRACE_PERIOD: i64 = 30 * 24 * 60 * 60;
@@ -0,0 +1,101 @@
+use anchor_lang::prelude::*;
+
+pub const RESOLUTION_RECORD_SEED: &str = "resolution_record";
+
+/// Payouts are expressed in basis points of one unit of collateral per conditional token.
+pub const PAYOUT_DENOMINATOR: u64 = 10_000;
+
+/// Where the resolution data came from
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq, InitSpace)]
+pub enum ResolutionSource {
+    /// a verified pyth pull-oracle price update
+    #[default]
+    PythPriceFeed,
+    /// no valid price landed within the grace period after expiry
+    GracePeriodExpired,
+    /// two verified pyth price updates, compared against their recorded start prices
+    PythRelativePerformance,
+    /// the price path of a barrier market, built from keeper submitted pyth price updates
+    PythBarrier,
+    /// the market never got an adapter resolution config and was invalidated after the fallback grace period
+    NoResolutionConfig,
+}
+
+/// The outcome the oracle adapter decided on.
+/// ct1 is the positive asserting case for the question and ct2 the negative one.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq, InitSpace)]
+pub enum MarketOutcome {
+    #[default]
+    Yes,
+    No,
+    /// the market could not be resolved, both conditional tokens refund half a unit of collateral
+    Invalid,
+}
+
+impl MarketOutcome {
+    /// Returns the (ct1, ct2) payout in basis points for this outcome
+    pub fn payout_bps(&self) -> (u16, u16) {
+        match self {
+            MarketOutcome::Yes => (PAYOUT_DENOMINATOR as u16, 0),
+            MarketOutcome::No => (0, PAYOUT_DENOMINATOR as u16),
+            MarketOutcome::Invalid => (
+                (PAYOUT_DENOMINATOR / 2) as u16,
+                (PAYOUT_DENOMINATOR / 2) as u16,
+            ),
+        }
+    }
+}
+
+/// The observation the oracle adapter resolved a market with, passed to `resolve_market`.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, Default)]
+pub struct ResolutionReport {
+    pub source: ResolutionSource,
+    pub feed_id: [u8; 32],
+    pub price: i64,
+    pub conf: u64,
+    pub exponent: i32,
+    pub publish_time: i64,
+    pub outcome: MarketOutcome,
+    /// the second feed of relative performance markets, zeroed otherwise
+    pub benchmark_feed_id: [u8; 32],
+    pub benchmark_price: i64,
+    pub benchmark_exponent: i32,
+    pub benchmark_publish_time: i64,
+}
+
+/// Immutable audit record of why a market resolved the way it did.
+/// One per market, created when the oracle adapter resolves the market.
+#[account]
+#[derive(Default, Debug, InitSpace)]
+pub struct ResolutionRecord {
+    /// Bump to identify PDA
+    pub bump: u8,
+    /// the resolved market
+    pub market_config: Pubkey,
+    pub vault_state: Pubkey,
+    /// where the price came from
+    pub source: ResolutionSource,
+    /// the pyth feed id the price was read from
+    pub feed_id: [u8; 32],
+    /// the price is (price ± conf) * 10^exponent
+    pub price: i64,
+    pub conf: u64,
+    pub exponent: i32,
+    /// the oracle publish time of the price
+    pub publish_time: i64,
+    /// the second feed of relative performance markets, zeroed otherwise
+    pub benchmark_feed_id: [u8; 32],
+    pub benchmark_price: i64,
+    pub benchmark_exponent: i32,
+    pub benchmark_publish_time: i64,
+    /// when the market was resolved
+    pub slot: u64,
+    pub resolved_at: i64,
+    /// who paid for and triggered the resolution
+    pub resolver: Pubkey,
+    /// the computed outcome and payout
+    pub outcome: MarketOutcome,
+    pub winning_ct_mint: Pubkey,
+    pub ct1_payout_bps: u16,
+    pub ct2_payout_bps: u16,
+}
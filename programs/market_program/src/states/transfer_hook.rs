@@ -0,0 +1,35 @@
+use anchor_lang::prelude::*;
+
+use super::MarketConfig;
+use crate::error::ErrorCode;
+
+/// The transfer hook of conditional token mints created on Token-2022
+pub const CT_TRANSFER_HOOK_PROGRAM_ID: Pubkey = pubkey!("HN7ixWKPXRgtcCkh3JWpvR8XxCVSpKoKggFnxmxEubKk");
+/// The seed of a mint's extra account meta list, derived under the transfer hook
+pub const EXTRA_ACCOUNT_METAS_SEED: &str = "extra-account-metas";
+/// The seed of a wallet's allowlist entry of a restricted market, derived under the transfer hook
+pub const ALLOWLIST_SEED: &str = "allowlist";
+/// The anchor discriminator of the hook's initialize_extra_account_meta_list, the hook
+/// depends on this crate so its cpi module cannot be used here
+pub const INITIALIZE_EXTRA_ACCOUNT_META_LIST_DISCRIMINATOR: [u8; 8] = [92, 197, 174, 197, 41, 124, 19, 3];
+
+/// The address of `wallet`'s allowlist entry of a market
+pub fn allowlist_entry_address(market_config: &Pubkey, wallet: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(
+        &[ALLOWLIST_SEED.as_bytes(), market_config.as_ref(), wallet.as_ref()],
+        &CT_TRANSFER_HOOK_PROGRAM_ID,
+    )
+    .0
+}
+
+/// Restricted markets only mint to wallets the hook would let receive their tokens.
+/// `allowlist_entry` must already be the wallet's entry address, it only counts while the hook owns it
+pub fn require_allowlisted(market_config: &MarketConfig, allowlist_entry: Option<&AccountInfo>) -> Result<()> {
+    if !market_config.restricted {
+        return Ok(());
+    }
+    match allowlist_entry {
+        Some(entry) if entry.owner == &CT_TRANSFER_HOOK_PROGRAM_ID && !entry.data_is_empty() => Ok(()),
+        _ => Err(ErrorCode::BettorNotAllowed.into()),
+    }
+}
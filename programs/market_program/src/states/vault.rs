@@ -1,7 +1,11 @@
 use anchor_lang::prelude::*;
-use crate::{error::ErrorCode};
+use crate::{error::ErrorCode, states::{MarketOutcome, PAYOUT_DENOMINATOR}};
 
 pub const COLLATERAL_VAULT_SEED: &str = "collateral_vault";
+pub const VAULT_STATE_SEED: &str = "vault_state";
+/// Layout version written by `initialize` and `migrate_vault_state`.
+/// Accounts created before the version field existed read as 0 once reallocated.
+pub const VAULT_STATE_VERSION: u8 = 2;
 
 /// Holds the current owner of the factory
 /// the contract will have to create and own a pda that will act as the vault for the collateral
@@ -39,14 +43,30 @@ pub struct VaultState {
     pub ctf1_token_program: Pubkey,
     pub ctf2_token_program: Pubkey,
 
-    // did the market resolve or not
+    // 1 once the market resolved, superseded by the payouts but kept so the fields after it keep their offsets
     pub resolution: u8,
 
     // winning conditional token mint
     pub winning_ct_mint: Pubkey,
+
+    // everything below was added in v2, new fields only ever go at the end and are carved out of reserved
+    pub version: u8,
+
+    // collateral paid out per conditional token, in basis points of PAYOUT_DENOMINATOR
+    pub ct1_payout_bps: u16,
+    pub ct2_payout_bps: u16,
+
+    pub reserved: [u64; 16],
 }
+
+// a wrong LEN once left the account too small, tie it to the actual layout
+const _: () = assert!(VaultState::LEN == 8 + std::mem::size_of::<VaultState>());
+const _: () = assert!(std::mem::offset_of!(VaultState, version) == VaultState::LEN_V1 - 8);
+
 impl VaultState {
-    pub const LEN: usize = 8 + 32 + 1 + 32 + 32 + 8 + 8 + 8 + 32 + 32 + 32 + 32 + 1 + 32;
+    /// size of the accounts created by the first deployed program, migrate_vault_state grows them to LEN
+    pub const LEN_V1: usize = 8 + 32 + 1 + 32 + 32 + 8 + 8 + 8 + 32 + 32 + 32 + 32 + 1 + 32;
+    pub const LEN: usize = Self::LEN_V1 + 1 + 2 + 2 + 8 * 16;
 
     pub fn initialize(
         &mut self,
@@ -71,13 +91,96 @@ impl VaultState {
         self.ctf2_mint = ctf2_mint;
         self.ctf1_token_program = ctf1_token_program;
         self.ctf2_token_program = ctf2_token_program;
-        self.resolution = 0;
+        self.version = VAULT_STATE_VERSION;
+        Ok(())
+    }
+
+    /// Brings an account written by an older program up to VAULT_STATE_VERSION
+    pub fn upgrade(&mut self) -> Result<()> {
+        if self.version >= VAULT_STATE_VERSION {
+            return Err(ErrorCode::VaultStateUpToDate.into());
+        }
+        // v1 only recorded the winning mint, a resolved v1 market pays its winner in full
+        if self.resolution == 1 {
+            let winning_ct_mint = self.winning_ct_mint;
+            let (ct1_payout_bps, ct2_payout_bps) = if winning_ct_mint == self.ctf1_mint {
+                MarketOutcome::Yes.payout_bps()
+            } else if winning_ct_mint == self.ctf2_mint {
+                MarketOutcome::No.payout_bps()
+            } else {
+                return Err(ErrorCode::WrongWinningToken.into());
+            };
+            self.ct1_payout_bps = ct1_payout_bps;
+            self.ct2_payout_bps = ct2_payout_bps;
+        } else {
+            self.ct1_payout_bps = 0;
+            self.ct2_payout_bps = 0;
+        }
+        self.reserved = [0; 16];
+        self.version = VAULT_STATE_VERSION;
         Ok(())
     }
 
-    pub fn resolve_market(&mut self, winning_ct_mint: Pubkey) -> Result<()> {
+    pub fn resolve_market(
+        &mut self,
+        winning_ct_mint: Pubkey,
+        ct1_payout_bps: u16,
+        ct2_payout_bps: u16,
+    ) -> Result<()> {
         self.resolution = 1;
         self.winning_ct_mint = winning_ct_mint;
+        self.ct1_payout_bps = ct1_payout_bps;
+        self.ct2_payout_bps = ct2_payout_bps;
+        Ok(())
+    }
+
+    /// Collateral owed for redeeming `amount` of the given conditional token mint
+    pub fn payout_for(&self, ct_mint: Pubkey, amount: u64) -> Result<u64> {
+        let payout_bps = if ct_mint == self.ctf1_mint {
+            self.ct1_payout_bps
+        } else if ct_mint == self.ctf2_mint {
+            self.ct2_payout_bps
+        } else {
+            return Err(ErrorCode::WrongWinningToken.into());
+        };
+        if payout_bps == 0 {
+            return Err(ErrorCode::WrongWinningToken.into());
+        }
+        let payout = (amount as u128)
+            .checked_mul(payout_bps as u128)
+            .ok_or(ErrorCode::MathOverflow)?
+            / PAYOUT_DENOMINATOR as u128;
+        u64::try_from(payout).map_err(|_| ErrorCode::MathOverflow.into())
+    }
+
+    /// Collateral the vault owes the holders of `ct1_supply` and `ct2_supply` conditional tokens.
+    /// Before resolution every complete set is worth one unit, afterwards the payouts apply.
+    pub fn liability(&self, ct1_supply: u64, ct2_supply: u64) -> Result<u64> {
+        if self.ct1_payout_bps == 0 && self.ct2_payout_bps == 0 {
+            if ct1_supply != ct2_supply {
+                msg!("Conditional token supplies diverged: {} != {}", ct1_supply, ct2_supply);
+                return Err(ErrorCode::VaultInsolvent.into());
+            }
+            return Ok(ct1_supply);
+        }
+        let owed = |supply: u64, payout_bps: u16| supply as u128 * payout_bps as u128 / PAYOUT_DENOMINATOR as u128;
+        let liability = owed(ct1_supply, self.ct1_payout_bps) + owed(ct2_supply, self.ct2_payout_bps);
+        u64::try_from(liability).map_err(|_| ErrorCode::MathOverflow.into())
+    }
+
+    /// Whether any conditional token that pays out is still in circulation
+    pub fn has_outstanding_claims(&self, ct1_supply: u64, ct2_supply: u64) -> bool {
+        (self.ct1_payout_bps > 0 && ct1_supply > 0) || (self.ct2_payout_bps > 0 && ct2_supply > 0)
+    }
+
+    /// Fails unless the vault's real balance covers both the tracked balance and the liability
+    pub fn check_solvency(&self, vault_amount: u64, ct1_supply: u64, ct2_supply: u64) -> Result<()> {
+        let liability = self.liability(ct1_supply, ct2_supply)?;
+        let tracked = self.vault_collateral_balance;
+        if vault_amount < liability || vault_amount < tracked {
+            msg!("Vault holds {}, tracked {}, owes {}", vault_amount, tracked, liability);
+            return Err(ErrorCode::VaultInsolvent.into());
+        }
         Ok(())
     }
 
@@ -101,3 +204,81 @@ impl VaultState {
     }
 
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CT1_MINT: Pubkey = Pubkey::new_from_array([3; 32]);
+    const CT2_MINT: Pubkey = Pubkey::new_from_array([4; 32]);
+
+    // a vault state as the first deployed program wrote it, field by field
+    fn v1_account(resolution: u8, winning_ct_mint: Pubkey) -> Vec<u8> {
+        let mut data = Vec::with_capacity(VaultState::LEN_V1);
+        data.extend_from_slice(VaultState::DISCRIMINATOR);
+        data.extend_from_slice(&[1; 32]); // market_config
+        data.push(254); // auth_bump
+        data.extend_from_slice(&[2; 32]); // vault_creator
+        data.extend_from_slice(&[5; 32]); // vault
+        data.extend_from_slice(&1_000u64.to_le_bytes()); // vault_collateral_balance
+        data.extend_from_slice(&1_700_000_000i64.to_le_bytes()); // vault_created_at
+        data.extend_from_slice(&1_800_000_000i64.to_le_bytes()); // vault_expiration
+        data.extend_from_slice(CT1_MINT.as_ref());
+        data.extend_from_slice(CT2_MINT.as_ref());
+        data.extend_from_slice(&[6; 32]); // ctf1_token_program
+        data.extend_from_slice(&[6; 32]); // ctf2_token_program
+        data.push(resolution);
+        data.extend_from_slice(winning_ct_mint.as_ref());
+        assert_eq!(data.len(), VaultState::LEN_V1);
+        // migrate_vault_state zero-extends the account to the current size
+        data.resize(VaultState::LEN, 0);
+        data
+    }
+
+    fn upgrade(data: &mut [u8]) -> Result<VaultState> {
+        let vault_state = bytemuck::from_bytes_mut::<VaultState>(&mut data[8..]);
+        vault_state.upgrade()?;
+        Ok(*vault_state)
+    }
+
+    #[test]
+    fn v1_fields_keep_their_offsets() {
+        let mut data = v1_account(0, Pubkey::default());
+        let vault_state = upgrade(&mut data).unwrap();
+        assert_eq!(vault_state.market_config, Pubkey::new_from_array([1; 32]));
+        assert_eq!(vault_state.auth_bump, 254);
+        assert_eq!(vault_state.vault_creator, Pubkey::new_from_array([2; 32]));
+        assert_eq!(vault_state.vault, Pubkey::new_from_array([5; 32]));
+        assert_eq!({ vault_state.vault_collateral_balance }, 1_000);
+        assert_eq!({ vault_state.vault_created_at }, 1_700_000_000);
+        assert_eq!({ vault_state.vault_expiration }, 1_800_000_000);
+        assert_eq!(vault_state.ctf1_mint, CT1_MINT);
+        assert_eq!(vault_state.ctf2_mint, CT2_MINT);
+        assert_eq!(vault_state.ctf2_token_program, Pubkey::new_from_array([6; 32]));
+        assert_eq!(vault_state.version, VAULT_STATE_VERSION);
+        assert_eq!(({ vault_state.ct1_payout_bps }, { vault_state.ct2_payout_bps }), (0, 0));
+    }
+
+    #[test]
+    fn upgrade_translates_a_v1_resolution() {
+        let mut data = v1_account(1, CT1_MINT);
+        let vault_state = upgrade(&mut data).unwrap();
+        assert_eq!(({ vault_state.ct1_payout_bps }, { vault_state.ct2_payout_bps }), MarketOutcome::Yes.payout_bps());
+        assert_eq!(vault_state.payout_for(CT1_MINT, 500).unwrap(), 500);
+        assert!(vault_state.payout_for(CT2_MINT, 500).is_err());
+
+        let mut data = v1_account(1, CT2_MINT);
+        let vault_state = upgrade(&mut data).unwrap();
+        assert_eq!(({ vault_state.ct1_payout_bps }, { vault_state.ct2_payout_bps }), MarketOutcome::No.payout_bps());
+    }
+
+    #[test]
+    fn upgrade_rejects_unknown_winner_and_current_version() {
+        let mut data = v1_account(1, Pubkey::new_from_array([9; 32]));
+        assert!(upgrade(&mut data).is_err());
+
+        let mut data = v1_account(0, Pubkey::default());
+        upgrade(&mut data).unwrap();
+        assert!(upgrade(&mut data).is_err());
+    }
+}
@@ -1,4 +1,12 @@
-use anchor_lang::{prelude::*, system_program};
+use anchor_lang::{
+    prelude::*,
+    solana_program::{
+        instruction::{AccountMeta, Instruction},
+        program::invoke,
+        program_option::COption,
+    },
+    system_program,
+};
 use anchor_spl::{
     token::{TokenAccount},
     token_2022::{self},
@@ -7,9 +15,92 @@ use anchor_spl::{
 use spl_token_2022::{
     self,
     extension::{
+        mint_close_authority::MintCloseAuthority,
+        transfer_fee::{instruction::harvest_withheld_tokens_to_mint, TransferFeeAmount, TransferFeeConfig},
         BaseStateWithExtensions, ExtensionType, StateWithExtensions,
     },
 };
+use crate::error::ErrorCode;
+use crate::states::{CT_TRANSFER_HOOK_PROGRAM_ID, INITIALIZE_EXTRA_ACCOUNT_META_LIST_DISCRIMINATOR};
+
+/// The fee Token-2022 withholds when `amount` of `mint` is transferred, 0 for mints without a transfer fee
+pub fn get_transfer_fee(mint: &AccountInfo, amount: u64) -> Result<u64> {
+    if *mint.owner != token_2022::Token2022::id() {
+        return Ok(0);
+    }
+    let mint_data = mint.try_borrow_data()?;
+    let mint_state = StateWithExtensions::<spl_token_2022::state::Mint>::unpack(&mint_data)?;
+    let fee = match mint_state.get_extension::<TransferFeeConfig>() {
+        Ok(transfer_fee_config) => transfer_fee_config
+            .calculate_epoch_fee(Clock::get()?.epoch, amount)
+            .ok_or(ErrorCode::TransferFeeCalculateNotMatch)?,
+        Err(_) => 0,
+    };
+    Ok(fee)
+}
+
+/// The close authority of a Token-2022 mint, None if the mint cannot be closed
+pub fn mint_close_authority(mint: &AccountInfo) -> Result<Option<Pubkey>> {
+    if *mint.owner != token_2022::Token2022::id() {
+        return Ok(None);
+    }
+    let mint_data = mint.try_borrow_data()?;
+    let mint_state = StateWithExtensions::<spl_token_2022::state::Mint>::unpack(&mint_data)?;
+    Ok(mint_state
+        .get_extension::<MintCloseAuthority>()
+        .ok()
+        .and_then(|extension| Option::<Pubkey>::from(extension.close_authority)))
+}
+
+/// The extensions a mint carries, none for mints of the legacy token program
+pub fn mint_extension_types(mint: &AccountInfo) -> Result<Vec<ExtensionType>> {
+    if *mint.owner != token_2022::Token2022::id() {
+        return Ok(Vec::new());
+    }
+    let mint_data = mint.try_borrow_data()?;
+    let mint_state = StateWithExtensions::<spl_token_2022::state::Mint>::unpack(&mint_data)?;
+    Ok(mint_state.get_extension_types()?)
+}
+
+/// Moves the transfer fees Token-2022 withheld in `account` to its mint, a token account
+/// cannot be closed while it still withholds fees. Anyone may harvest, no authority signs.
+pub fn harvest_withheld_fees<'a>(
+    token_program: AccountInfo<'a>,
+    mint: AccountInfo<'a>,
+    account: AccountInfo<'a>,
+) -> Result<()> {
+    if *account.owner != token_2022::Token2022::id() {
+        return Ok(());
+    }
+    let withheld = {
+        let data = account.try_borrow_data()?;
+        let state = StateWithExtensions::<spl_token_2022::state::Account>::unpack(&data)?;
+        state
+            .get_extension::<TransferFeeAmount>()
+            .map_or(0, |extension| u64::from(extension.withheld_amount))
+    };
+    if withheld == 0 {
+        return Ok(());
+    }
+    let ix = harvest_withheld_tokens_to_mint(token_program.key, mint.key, &[account.key])?;
+    invoke(&ix, &[mint, account, token_program])?;
+    Ok(())
+}
+
+pub fn token_account_amount(token_account: &AccountInfo) -> Result<u64> {
+    let data = token_account.try_borrow_data()?;
+    Ok(StateWithExtensions::<spl_token_2022::state::Account>::unpack(&data)?.base.amount)
+}
+
+// the destination must receive exactly amount minus the fee we computed
+fn check_received(received: u64, amount: u64, fee: u64) -> Result<u64> {
+    let expected = amount.checked_sub(fee).ok_or(ErrorCode::TransferFeeCalculateNotMatch)?;
+    if received != expected {
+        msg!("Transfer of {} with fee {} delivered {}", amount, fee, received);
+        return Err(ErrorCode::TransferFeeCalculateNotMatch.into());
+    }
+    Ok(received)
+}
 
 
 pub fn transfer_from_user_to_collateral_vault<'a>(
@@ -20,23 +111,30 @@ pub fn transfer_from_user_to_collateral_vault<'a>(
     token_program: AccountInfo<'a>,
     amount: u64,
     mint_decimals: u8,
-) -> Result<()> {
+) -> Result<u64> {
     if amount == 0 {
-        return Ok(());
+        return Ok(0);
     }
+    // returns what the vault actually received after the transfer fee
+    let fee = get_transfer_fee(&mint, amount)?;
+    let vault_before = token_account_amount(&to_vault)?;
     token_2022::transfer_checked(
         CpiContext::new(
             token_program.to_account_info(),
             token_2022::TransferChecked {
                 from,
-                to: to_vault,
+                to: to_vault.clone(),
                 authority,
                 mint,
             },
         ),
         amount,
         mint_decimals,
-    )
+    )?;
+    let received = token_account_amount(&to_vault)?
+        .checked_sub(vault_before)
+        .ok_or(ErrorCode::TransferFeeCalculateNotMatch)?;
+    check_received(received, amount, fee)
 }
 
 pub fn transfer_from_collateral_vault_to_user<'a>(
@@ -48,16 +146,19 @@ pub fn transfer_from_collateral_vault_to_user<'a>(
     amount: u64,
     mint_decimals: u8,
     signer_seeds: &[&[&[u8]]],
-) -> Result<()> {
+) -> Result<u64> {
     if amount == 0 {
-        return Ok(());
+        return Ok(0);
     }
+    // the vault pays `amount`, the user receives it minus the transfer fee
+    let fee = get_transfer_fee(&mint, amount)?;
+    let user_before = token_account_amount(&to)?;
     token_2022::transfer_checked(
         CpiContext::new_with_signer(
             token_program.to_account_info(),
             token_2022::TransferChecked {
                 from: from_vault,
-                to,
+                to: to.clone(),
                 authority,
                 mint,
             },
@@ -65,7 +166,11 @@ pub fn transfer_from_collateral_vault_to_user<'a>(
         ),
         amount,
         mint_decimals,
-    )
+    )?;
+    let received = token_account_amount(&to)?
+        .checked_sub(user_before)
+        .ok_or(ErrorCode::TransferFeeCalculateNotMatch)?;
+    check_received(received, amount, fee)
 }
 
 
@@ -114,6 +219,28 @@ pub fn token_burn<'a>(
     )
 }
 
+/// Lets `delegate` burn up to u64::MAX of `account`, unless it already may
+pub fn token_approve_unlimited<'a>(
+    owner: AccountInfo<'a>,
+    token_program: AccountInfo<'a>,
+    account: &InterfaceAccount<'a, anchor_spl::token_interface::TokenAccount>,
+    delegate: AccountInfo<'a>,
+) -> Result<()> {
+    if account.delegate == COption::Some(delegate.key()) && account.delegated_amount == u64::MAX {
+        return Ok(());
+    }
+    token_2022::approve(
+        CpiContext::new(
+            token_program,
+            token_2022::Approve {
+                to: account.to_account_info(),
+                delegate,
+                authority: owner,
+            },
+        ),
+        u64::MAX,
+    )
+}
 
 pub fn create_token_account<'a>(
     authority: &AccountInfo<'a>,
@@ -212,3 +339,31 @@ pub fn create_or_allocate_account<'a>(
     }
     Ok(())
 }
+
+/// Creates the transfer hook's extra account meta list of a conditional token mint,
+/// the market config must already hold the mint
+pub fn initialize_extra_account_meta_list<'a>(
+    hook_program: AccountInfo<'a>,
+    payer: AccountInfo<'a>,
+    market_config: AccountInfo<'a>,
+    mint: AccountInfo<'a>,
+    extra_account_meta_list: AccountInfo<'a>,
+    system_program: AccountInfo<'a>,
+) -> Result<()> {
+    let ix = Instruction {
+        program_id: CT_TRANSFER_HOOK_PROGRAM_ID,
+        accounts: vec![
+            AccountMeta::new(payer.key(), true),
+            AccountMeta::new_readonly(market_config.key(), false),
+            AccountMeta::new_readonly(mint.key(), false),
+            AccountMeta::new(extra_account_meta_list.key(), false),
+            AccountMeta::new_readonly(system_program.key(), false),
+        ],
+        data: INITIALIZE_EXTRA_ACCOUNT_META_LIST_DISCRIMINATOR.to_vec(),
+    };
+    invoke(
+        &ix,
+        &[hook_program, payer, market_config, mint, extra_account_meta_list, system_program],
+    )?;
+    Ok(())
+}
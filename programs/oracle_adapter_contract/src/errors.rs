@@ -11,4 +11,42 @@ pub enum ErrorCode {
     ResolutionConfigAlreadyInitialized,
     #[msg("Resolution config not expired yet")]
     ResolutionConfigNotExpiredYet,
+    #[msg("Invalid grace period")]
+    InvalidGracePeriod,
+    #[msg("Resolution grace period elapsed")]
+    GracePeriodElapsed,
+    #[msg("Resolution grace period not elapsed yet")]
+    GracePeriodNotElapsed,
+    #[msg("Invalid price rule")]
+    InvalidPriceRule,
+    #[msg("Price scaling overflow")]
+    PriceScalingOverflow,
+    #[msg("Price must be positive")]
+    NonPositivePrice,
+    #[msg("Instruction not supported for this market kind")]
+    InvalidMarketKind,
+    #[msg("Benchmark price update missing")]
+    MissingBenchmarkPriceUpdate,
+    #[msg("Start prices not recorded")]
+    StartPriceNotRecorded,
+    #[msg("Start prices already recorded")]
+    StartPriceAlreadyRecorded,
+    #[msg("Barrier tracker missing")]
+    MissingBarrierTracker,
+    #[msg("Price was not published during the market's life")]
+    PriceOutsideMarketLife,
+    #[msg("Market outcome can still change")]
+    MarketNotFinal,
+    #[msg("Signer is not the market owner")]
+    NotMarketOwner,
+    #[msg("Asset and benchmark feeds must differ")]
+    IdenticalFeeds,
+    #[msg("Trading already opened")]
+    TradingAlreadyOpen,
+    #[msg("Start prices can only be recorded around the opening of trading")]
+    OutsideStartPriceWindow,
+    #[msg("Price is not newer than the last submitted price")]
+    StalePrice,
+    #[msg("Resolution price was not published right after expiration")]
+    ResolutionPriceOutsideWindow,
 }
@@ -0,0 +1,53 @@
+// permissionless, once the market's outcome can no longer change (or it was cancelled) its resolution config
+// (and barrier tracker) are closed and the rent goes back to the market owner.
+// Has to run before the market program's close_market, which closes the market config.
+use anchor_lang::prelude::*;
+use market_program::{MarketConfig, MarketStatus};
+
+use crate::states::*;
+use crate::errors::ErrorCode;
+
+#[derive(Accounts)]
+pub struct CloseResolutionConfig<'info> {
+    /// CHECK: only receives the rent, must be the market owner
+    #[account(mut, address = market_config.owner)]
+    pub owner: UncheckedAccount<'info>,
+
+    pub market_config: Account<'info, MarketConfig>,
+
+    #[account(
+        mut,
+        close = owner,
+        seeds = [
+            b"resolution_config",
+            market_config.key().as_ref(),
+        ],
+        bump = resolution_config.resolution_config_bump,
+    )]
+    pub resolution_config: Account<'info, ResolutionConfig>,
+
+    // only barrier markets have one
+    #[account(
+        mut,
+        close = owner,
+        seeds = [
+            BARRIER_TRACKER_SEED.as_bytes(),
+            market_config.key().as_ref(),
+        ],
+        bump = barrier_tracker.bump,
+    )]
+    pub barrier_tracker: Option<Account<'info, BarrierTracker>>,
+}
+
+pub fn close_resolution_config(ctx: Context<CloseResolutionConfig>) -> Result<()> {
+    if !matches!(
+        ctx.accounts.market_config.status,
+        MarketStatus::Finalized | MarketStatus::Invalid | MarketStatus::Cancelled
+    ) {
+        return Err(ErrorCode::MarketNotFinal.into());
+    }
+    if ctx.accounts.resolution_config.kind == MarketKind::Barrier && ctx.accounts.barrier_tracker.is_none() {
+        return Err(ErrorCode::MissingBarrierTracker.into());
+    }
+    Ok(())
+}
@@ -0,0 +1,103 @@
+// permissionless fallback for markets whose oracle never delivered a valid price,
+// e.g. because the feed was deprecated. Once the grace period after expiration has
+// elapsed anyone can move the market to the refund resolution.
+use anchor_lang::prelude::*;
+use market_program::{
+    cpi::accounts::ResolveMarket, program::MarketProgram, MarketConfig, MarketOutcome,
+    ResolutionReport, ResolutionSource, VaultState, ORACLE_AUTHORITY_SEED, RESOLUTION_RECORD_SEED,
+};
+
+use crate::states::*;
+use crate::utils::*;
+use crate::errors::ErrorCode;
+
+#[derive(Accounts)]
+pub struct ForceInvalidate<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    // resolve_market updates the market's status
+    #[account(mut)]
+    pub market_config: Account<'info, MarketConfig>,
+
+    #[account(
+        seeds = [
+            b"resolution_config",
+            market_config.key().as_ref(),
+        ],
+        bump = resolution_config.resolution_config_bump,
+    )]
+    pub resolution_config: Account<'info, ResolutionConfig>,
+
+    /// CHECK: the pda that signs the resolve_market cpi on behalf of this program
+    #[account(
+        seeds = [ORACLE_AUTHORITY_SEED.as_bytes()],
+        bump,
+    )]
+    pub oracle_authority: UncheckedAccount<'info>,
+
+    /// CHECK: the market's resolution record, created and validated by the market program
+    #[account(
+        mut,
+        seeds = [
+            RESOLUTION_RECORD_SEED.as_bytes(),
+            market_config.key().as_ref(),
+        ],
+        bump,
+        seeds::program = market_program.key(),
+    )]
+    pub resolution_record: UncheckedAccount<'info>,
+
+    pub market_program: Program<'info, MarketProgram>,
+    /// CHECK: the market program's event authority, used by its emit_cpi
+    #[account(
+        seeds = [b"__event_authority"],
+        bump,
+        seeds::program = market_program.key(),
+    )]
+    pub market_event_authority: UncheckedAccount<'info>,
+    #[account(mut)]
+    pub vault_state: AccountLoader<'info, VaultState>,
+    /// CHECK: the market's registry page, validated by the market program which updates its entry
+    #[account(mut)]
+    pub market_registry_page: Option<UncheckedAccount<'info>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn force_invalidate(ctx: Context<ForceInvalidate>) -> Result<()> {
+    let resolution_config = &ctx.accounts.resolution_config;
+    let now = Clock::get()?.unix_timestamp;
+    if now < resolution_config.grace_period_end()? {
+        return Err(ErrorCode::GracePeriodNotElapsed.into());
+    }
+
+    msg!("No valid resolution within the grace period, invalidating market");
+
+    let report = ResolutionReport {
+        source: ResolutionSource::GracePeriodExpired,
+        feed_id: resolution_config.feed_id,
+        publish_time: now,
+        outcome: MarketOutcome::Invalid,
+        ..Default::default()
+    };
+    let cpi_accounts = ResolveMarket {
+        payer: ctx.accounts.payer.to_account_info(),
+        oracle_authority: ctx.accounts.oracle_authority.to_account_info(),
+        market_config: ctx.accounts.market_config.to_account_info(),
+        vault_state: ctx.accounts.vault_state.to_account_info(),
+        resolution_record: ctx.accounts.resolution_record.to_account_info(),
+        market_registry_page: ctx.accounts.market_registry_page.as_ref().map(|page| page.to_account_info()),
+        system_program: ctx.accounts.system_program.to_account_info(),
+        event_authority: ctx.accounts.market_event_authority.to_account_info(),
+        program: ctx.accounts.market_program.to_account_info(),
+    };
+    cpi_resolve_market(
+        ctx.accounts.market_program.to_account_info(),
+        cpi_accounts,
+        ctx.bumps.oracle_authority,
+        report,
+    )?;
+
+    Ok(())
+}
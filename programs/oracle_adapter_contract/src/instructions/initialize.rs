@@ -1,14 +1,22 @@
 use std::ops::DerefMut;
 use anchor_lang::prelude::*;
 use crate::states::*;
+use crate::errors::ErrorCode;
 
-use market_program::MarketConfig;
+use market_program::{
+    cpi::accounts::SetStartPricePending, program::MarketProgram, MarketConfig, ORACLE_AUTHORITY_SEED,
+};
+use crate::utils::*;
 
 #[derive(Accounts)]
 pub struct Initialize<'info> {
     #[account(mut)]
     pub payer: Signer<'info>,
 
+    // only the market's owner picks how it resolves
+    #[account(
+        constraint = market_config.owner == payer.key() @ ErrorCode::NotMarketOwner,
+    )]
     pub market_config: Account<'info, MarketConfig>,
 
     #[account(
@@ -26,13 +34,185 @@ pub struct Initialize<'info> {
     pub system_program: Program<'info, System>,
 }
 
-pub fn initialize(ctx: Context<Initialize>) -> Result<()> {
+pub fn initialize(
+    ctx: Context<Initialize>,
+    feed_id: [u8; 32],
+    rule: PriceRule,
+    grace_period: i64,
+) -> Result<()> {
+    rule.validate()?;
 
-    let resolution_config = ctx.accounts.resolution_config.deref_mut();
-    resolution_config.market_config = ctx.accounts.market_config.key();
-    resolution_config.resolution_config_bump = ctx.bumps.resolution_config;
-    resolution_config.resolution_config_created_at = Clock::get()?.unix_timestamp;
-    resolution_config.resolution_config_expiration = Clock::get()?.unix_timestamp + 1000000000;
+    let resolution_config = init_resolution_config(
+        &mut ctx.accounts.resolution_config,
+        &ctx.accounts.market_config,
+        ctx.bumps.resolution_config,
+        MarketKind::PriceRule,
+        feed_id,
+        grace_period,
+    )?;
+    resolution_config.rule = rule;
+
+    Ok(())
+}
+
+// relative performance markets are set up before trading opens, and cannot trade
+// until `record_start_price` recorded the start prices around the opening
+#[derive(Accounts)]
+pub struct InitializeRelative<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    #[account(
+        mut,
+        constraint = market_config.owner == payer.key() @ ErrorCode::NotMarketOwner,
+    )]
+    pub market_config: Account<'info, MarketConfig>,
+
+    #[account(
+        init,
+        payer = payer,
+        seeds = [
+            b"resolution_config",
+            market_config.key().as_ref(),
+        ],
+        bump,
+        space = ResolutionConfig::LEN,
+    )]
+    pub resolution_config: Account<'info, ResolutionConfig>,
+
+    /// CHECK: the pda that signs the set_start_price_pending cpi on behalf of this program
+    #[account(
+        seeds = [ORACLE_AUTHORITY_SEED.as_bytes()],
+        bump,
+    )]
+    pub oracle_authority: UncheckedAccount<'info>,
+
+    pub market_program: Program<'info, MarketProgram>,
+    pub system_program: Program<'info, System>,
+}
+
+pub fn initialize_relative(
+    ctx: Context<InitializeRelative>,
+    feed_id: [u8; 32],
+    benchmark_feed_id: [u8; 32],
+    grace_period: i64,
+) -> Result<()> {
+    // an asset can't outperform itself, every such market would resolve NO
+    if feed_id == benchmark_feed_id {
+        return Err(ErrorCode::IdenticalFeeds.into());
+    }
+    if Clock::get()?.unix_timestamp >= ctx.accounts.market_config.trading_opens_at {
+        return Err(ErrorCode::TradingAlreadyOpen.into());
+    }
+
+    let resolution_config = init_resolution_config(
+        &mut ctx.accounts.resolution_config,
+        &ctx.accounts.market_config,
+        ctx.bumps.resolution_config,
+        MarketKind::RelativePerformance,
+        feed_id,
+        grace_period,
+    )?;
+    resolution_config.benchmark_feed_id = benchmark_feed_id;
+
+    cpi_set_start_price_pending(
+        ctx.accounts.market_program.to_account_info(),
+        SetStartPricePending {
+            oracle_authority: ctx.accounts.oracle_authority.to_account_info(),
+            market_config: ctx.accounts.market_config.to_account_info(),
+        },
+        ctx.bumps.oracle_authority,
+        true,
+    )?;
+
+    Ok(())
+}
+
+// barrier markets also get the tracker keepers submit prices to
+#[derive(Accounts)]
+pub struct InitializeBarrier<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    // only the market's owner picks how it resolves
+    #[account(
+        constraint = market_config.owner == payer.key() @ ErrorCode::NotMarketOwner,
+    )]
+    pub market_config: Account<'info, MarketConfig>,
+
+    #[account(
+        init,
+        payer = payer,
+        seeds = [
+            b"resolution_config",
+            market_config.key().as_ref(),
+        ],
+        bump,
+        space = ResolutionConfig::LEN,
+    )]
+    pub resolution_config: Account<'info, ResolutionConfig>,
+
+    #[account(
+        init,
+        payer = payer,
+        seeds = [
+            BARRIER_TRACKER_SEED.as_bytes(),
+            market_config.key().as_ref(),
+        ],
+        bump,
+        space = BarrierTracker::LEN,
+    )]
+    pub barrier_tracker: Account<'info, BarrierTracker>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn initialize_barrier(
+    ctx: Context<InitializeBarrier>,
+    feed_id: [u8; 32],
+    rule: PriceRule,
+    grace_period: i64,
+) -> Result<()> {
+    rule.validate()?;
+    if !rule.is_single_sided() {
+        return Err(ErrorCode::InvalidPriceRule.into());
+    }
+
+    let resolution_config = init_resolution_config(
+        &mut ctx.accounts.resolution_config,
+        &ctx.accounts.market_config,
+        ctx.bumps.resolution_config,
+        MarketKind::Barrier,
+        feed_id,
+        grace_period,
+    )?;
+    resolution_config.rule = rule;
+
+    let barrier_tracker = ctx.accounts.barrier_tracker.deref_mut();
+    barrier_tracker.market_config = ctx.accounts.market_config.key();
+    barrier_tracker.bump = ctx.bumps.barrier_tracker;
 
     Ok(())
-}
\ No newline at end of file
+}
+
+fn init_resolution_config<'a>(
+    resolution_config: &'a mut Account<ResolutionConfig>,
+    market_config: &Account<MarketConfig>,
+    resolution_config_bump: u8,
+    kind: MarketKind,
+    feed_id: [u8; 32],
+    grace_period: i64,
+) -> Result<&'a mut ResolutionConfig> {
+    ResolutionConfig::validate_grace_period(grace_period)?;
+
+    let resolution_config = resolution_config.deref_mut();
+    resolution_config.market_config = market_config.key();
+    resolution_config.resolution_config_bump = resolution_config_bump;
+    resolution_config.resolution_config_created_at = Clock::get()?.unix_timestamp;
+    resolution_config.resolution_config_expiration = market_config.resolves_at;
+    resolution_config.feed_id = feed_id;
+    resolution_config.grace_period = grace_period;
+    resolution_config.kind = kind;
+
+    Ok(resolution_config)
+}
@@ -2,4 +2,15 @@ pub mod trigger;
 pub use trigger::*;
 
 pub mod initialize;
-pub use initialize::*;
\ No newline at end of file
+pub use initialize::*;
+
+pub mod force_invalidate;
+pub use force_invalidate::*;
+
+pub mod record_start_price;
+pub use record_start_price::*;
+
+pub mod submit_barrier_price;
+pub use submit_barrier_price::*;
+pub mod close_resolution_config;
+pub use close_resolution_config::*;
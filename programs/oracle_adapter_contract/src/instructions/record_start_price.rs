@@ -0,0 +1,102 @@
+// snapshots the starting prices of both feeds of a relative performance market and opens it to trading.
+// permissionless, can only be done once and within START_PRICE_WINDOW of trading opening.
+use std::ops::DerefMut;
+
+use anchor_lang::prelude::*;
+use pyth_solana_receiver_sdk::price_update::PriceUpdateV2;
+use market_program::{
+    cpi::accounts::SetStartPricePending, program::MarketProgram, MarketConfig, ORACLE_AUTHORITY_SEED,
+};
+
+use crate::states::*;
+use crate::utils::*;
+use crate::errors::ErrorCode;
+
+#[derive(Accounts)]
+pub struct RecordStartPrice<'info> {
+    pub payer: Signer<'info>,
+
+    #[account(mut)]
+    pub market_config: Account<'info, MarketConfig>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"resolution_config",
+            market_config.key().as_ref(),
+        ],
+        bump = resolution_config.resolution_config_bump,
+    )]
+    pub resolution_config: Account<'info, ResolutionConfig>,
+
+    pub price_update: Account<'info, PriceUpdateV2>,
+    pub benchmark_price_update: Account<'info, PriceUpdateV2>,
+
+    /// CHECK: the pda that signs the set_start_price_pending cpi on behalf of this program
+    #[account(
+        seeds = [ORACLE_AUTHORITY_SEED.as_bytes()],
+        bump,
+    )]
+    pub oracle_authority: UncheckedAccount<'info>,
+
+    pub market_program: Program<'info, MarketProgram>,
+}
+
+pub fn record_start_price(ctx: Context<RecordStartPrice>) -> Result<()> {
+    let resolution_config = ctx.accounts.resolution_config.deref_mut();
+    if resolution_config.kind != MarketKind::RelativePerformance {
+        return Err(ErrorCode::InvalidMarketKind.into());
+    }
+    if resolution_config.start_recorded_at != 0 {
+        return Err(ErrorCode::StartPriceAlreadyRecorded.into());
+    }
+    let clock = Clock::get()?;
+    // a price from long before or after the opening would not be the price the market traded from
+    let trading_opens_at = ctx.accounts.market_config.trading_opens_at;
+    if clock.unix_timestamp < trading_opens_at.saturating_sub(START_PRICE_WINDOW)
+        || clock.unix_timestamp > trading_opens_at.saturating_add(START_PRICE_WINDOW)
+    {
+        return Err(ErrorCode::OutsideStartPriceWindow.into());
+    }
+
+    let price = ctx.accounts.price_update.get_price_no_older_than(
+        &clock,
+        MAXIMUM_PRICE_AGE,
+        &resolution_config.feed_id,
+    )?;
+    let benchmark_price = ctx.accounts.benchmark_price_update.get_price_no_older_than(
+        &clock,
+        MAXIMUM_PRICE_AGE,
+        &resolution_config.benchmark_feed_id,
+    )?;
+
+    msg!(
+        "Start prices are {} * 10^{} and {} * 10^{}",
+        price.price,
+        price.exponent,
+        benchmark_price.price,
+        benchmark_price.exponent
+    );
+
+    resolution_config.start_prices = [
+        PriceSnapshot::new(price.price, price.exponent, price.publish_time)?,
+        PriceSnapshot::new(
+            benchmark_price.price,
+            benchmark_price.exponent,
+            benchmark_price.publish_time,
+        )?,
+    ];
+    resolution_config.start_recorded_at = clock.unix_timestamp;
+
+    cpi_set_start_price_pending(
+        ctx.accounts.market_program.to_account_info(),
+        SetStartPricePending {
+            oracle_authority: ctx.accounts.oracle_authority.to_account_info(),
+            market_config: ctx.accounts.market_config.to_account_info(),
+        },
+        ctx.bumps.oracle_authority,
+        false,
+    )?;
+
+    Ok(())
+}
@@ -0,0 +1,145 @@
+// permissionless keepers submit pyth price updates of a barrier market during its life.
+// the running max and min are tracked and the market resolves YES the moment the barrier is touched.
+use std::ops::DerefMut;
+
+use anchor_lang::prelude::*;
+use pyth_solana_receiver_sdk::price_update::PriceUpdateV2;
+use market_program::{
+    cpi::accounts::ResolveMarket, program::MarketProgram, MarketConfig, MarketOutcome,
+    ResolutionReport, ResolutionSource, VaultState, ORACLE_AUTHORITY_SEED, RESOLUTION_RECORD_SEED,
+};
+
+use crate::states::*;
+use crate::utils::*;
+use crate::errors::ErrorCode;
+
+#[derive(Accounts)]
+pub struct SubmitBarrierPrice<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    // resolve_market updates the market's status
+    #[account(mut)]
+    pub market_config: Account<'info, MarketConfig>,
+
+    #[account(
+        seeds = [
+            b"resolution_config",
+            market_config.key().as_ref(),
+        ],
+        bump = resolution_config.resolution_config_bump,
+    )]
+    pub resolution_config: Account<'info, ResolutionConfig>,
+
+    #[account(
+        mut,
+        seeds = [
+            BARRIER_TRACKER_SEED.as_bytes(),
+            market_config.key().as_ref(),
+        ],
+        bump = barrier_tracker.bump,
+    )]
+    pub barrier_tracker: Account<'info, BarrierTracker>,
+
+    /// CHECK: the pda that signs the resolve_market cpi on behalf of this program
+    #[account(
+        seeds = [ORACLE_AUTHORITY_SEED.as_bytes()],
+        bump,
+    )]
+    pub oracle_authority: UncheckedAccount<'info>,
+
+    /// CHECK: the market's resolution record, created and validated by the market program
+    #[account(
+        mut,
+        seeds = [
+            RESOLUTION_RECORD_SEED.as_bytes(),
+            market_config.key().as_ref(),
+        ],
+        bump,
+        seeds::program = market_program.key(),
+    )]
+    pub resolution_record: UncheckedAccount<'info>,
+
+    pub market_program: Program<'info, MarketProgram>,
+    /// CHECK: the market program's event authority, used by its emit_cpi
+    #[account(
+        seeds = [b"__event_authority"],
+        bump,
+        seeds::program = market_program.key(),
+    )]
+    pub market_event_authority: UncheckedAccount<'info>,
+    #[account(mut)]
+    pub vault_state: AccountLoader<'info, VaultState>,
+    /// CHECK: the market's registry page, validated by the market program which updates its entry
+    #[account(mut)]
+    pub market_registry_page: Option<UncheckedAccount<'info>>,
+
+    pub price_update: Account<'info, PriceUpdateV2>,
+    pub system_program: Program<'info, System>,
+}
+
+pub fn submit_barrier_price(ctx: Context<SubmitBarrierPrice>) -> Result<()> {
+    let resolution_config = &ctx.accounts.resolution_config;
+    if resolution_config.kind != MarketKind::Barrier {
+        return Err(ErrorCode::InvalidMarketKind.into());
+    }
+
+    let clock = Clock::get()?;
+    let price = ctx.accounts.price_update.get_price_no_older_than(
+        &clock,
+        MAXIMUM_PRICE_AGE,
+        &resolution_config.feed_id,
+    )?;
+    if !resolution_config.is_within_market_life(price.publish_time) {
+        return Err(ErrorCode::PriceOutsideMarketLife.into());
+    }
+
+    let barrier_tracker = ctx.accounts.barrier_tracker.deref_mut();
+    barrier_tracker.record(PriceSnapshot {
+        price: price.price,
+        exponent: price.exponent,
+        publish_time: price.publish_time,
+    })?;
+
+    if !resolution_config.rule.evaluate(price.price, price.exponent)? {
+        return Ok(());
+    }
+
+    msg!(
+        "Barrier touched at ({} ± {}) * 10^{}",
+        price.price,
+        price.conf,
+        price.exponent
+    );
+
+    // resolve early, the market program rejects this if the market is already resolved
+    let report = ResolutionReport {
+        source: ResolutionSource::PythBarrier,
+        feed_id: resolution_config.feed_id,
+        price: price.price,
+        conf: price.conf,
+        exponent: price.exponent,
+        publish_time: price.publish_time,
+        outcome: MarketOutcome::Yes,
+        ..Default::default()
+    };
+    let cpi_accounts = ResolveMarket {
+        payer: ctx.accounts.payer.to_account_info(),
+        oracle_authority: ctx.accounts.oracle_authority.to_account_info(),
+        market_config: ctx.accounts.market_config.to_account_info(),
+        vault_state: ctx.accounts.vault_state.to_account_info(),
+        resolution_record: ctx.accounts.resolution_record.to_account_info(),
+        market_registry_page: ctx.accounts.market_registry_page.as_ref().map(|page| page.to_account_info()),
+        system_program: ctx.accounts.system_program.to_account_info(),
+        event_authority: ctx.accounts.market_event_authority.to_account_info(),
+        program: ctx.accounts.market_program.to_account_info(),
+    };
+    cpi_resolve_market(
+        ctx.accounts.market_program.to_account_info(),
+        cpi_accounts,
+        ctx.bumps.oracle_authority,
+        report,
+    )?;
+
+    Ok(())
+}
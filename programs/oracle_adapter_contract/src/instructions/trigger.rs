@@ -1,10 +1,12 @@
-use std::ops::DerefMut;
-
 use anchor_lang::prelude::*;
-use pyth_solana_receiver_sdk::price_update::{get_feed_id_from_hex, PriceUpdateV2};
-use market_program::{self, cpi::accounts::ResolveMarket, program::MarketProgram, MarketConfig, VaultState, OracleAuthority};
- 
+use pyth_solana_receiver_sdk::price_update::PriceUpdateV2;
+use market_program::{
+    cpi::accounts::ResolveMarket, program::MarketProgram, MarketConfig, MarketOutcome,
+    ResolutionReport, ResolutionSource, VaultState, ORACLE_AUTHORITY_SEED, RESOLUTION_RECORD_SEED,
+};
+
 use crate::states::*;
+use crate::utils::*;
 use crate::errors::ErrorCode;
 
 #[derive(Accounts)]
@@ -12,6 +14,8 @@ pub struct Trigger<'info> {
     #[account(mut)]
     pub payer: Signer<'info>,
 
+    // resolve_market updates the market's status
+    #[account(mut)]
     pub market_config: Account<'info, MarketConfig>,
 
     #[account(
@@ -23,34 +27,73 @@ pub struct Trigger<'info> {
     )]
     pub resolution_config: Account<'info, ResolutionConfig>,
 
+    /// CHECK: the pda that signs the resolve_market cpi on behalf of this program
     #[account(
-        init,
-        payer = payer,
-        seeds = [b"oracle-adapter-pda"],
+        seeds = [ORACLE_AUTHORITY_SEED.as_bytes()],
         bump,
-        space = OracleAuthority::INIT_SPACE,
     )]
-    pub oracle_adapter_pda: Account<'info, OracleAuthority>,
+    pub oracle_authority: UncheckedAccount<'info>,
+
+    /// CHECK: the market's resolution record, created and validated by the market program
+    #[account(
+        mut,
+        seeds = [
+            RESOLUTION_RECORD_SEED.as_bytes(),
+            market_config.key().as_ref(),
+        ],
+        bump,
+        seeds::program = market_program.key(),
+    )]
+    pub resolution_record: UncheckedAccount<'info>,
 
     pub market_program: Program<'info, MarketProgram>,
+    /// CHECK: the market program's event authority, used by its emit_cpi
+    #[account(
+        seeds = [b"__event_authority"],
+        bump,
+        seeds::program = market_program.key(),
+    )]
+    pub market_event_authority: UncheckedAccount<'info>,
+    #[account(mut)]
     pub vault_state: AccountLoader<'info, VaultState>,
+    /// CHECK: the market's registry page, validated by the market program which updates its entry
+    #[account(mut)]
+    pub market_registry_page: Option<UncheckedAccount<'info>>,
 
     pub price_update: Account<'info, PriceUpdateV2>,
+    // only needed by relative performance markets
+    pub benchmark_price_update: Option<Account<'info, PriceUpdateV2>>,
     pub system_program: Program<'info, System>,
 }
 
 
 pub fn trigger(ctx: Context<Trigger>) -> Result<()> {
-    let price_update = &mut ctx.accounts.price_update;
+    let price_update = &ctx.accounts.price_update;
+    let resolution_config = &ctx.accounts.resolution_config;
 
-        if Clock::get()?.unix_timestamp < ctx.accounts.resolution_config.resolution_config_expiration {
+        let clock = Clock::get()?;
+        let now = clock.unix_timestamp;
+        if now < resolution_config.resolution_config_expiration {
             return Err(ErrorCode::ResolutionConfigNotExpiredYet.into());
         }
-        let maximum_age: u64 = 30;
-        let feed_id: [u8; 32] = get_feed_id_from_hex(
-            "0xe62df6c8b4a85fe1a67db44dc12de5db330f7ac66b72dc658afedf0f4a415b43",
+        // past the grace period the market can only be invalidated
+        if now >= resolution_config.grace_period_end()? {
+            return Err(ErrorCode::GracePeriodElapsed.into());
+        }
+        // prices are read as of the end of the tolerance window, not as of now, so pyth rejects
+        // anything published before expiration and the check below anything published after the window
+        let price_clock = Clock {
+            unix_timestamp: resolution_config
+                .resolution_config_expiration
+                .saturating_add(RESOLUTION_PRICE_TOLERANCE),
+            ..clock.clone()
+        };
+        let price = price_update.get_price_no_older_than(
+            &price_clock,
+            RESOLUTION_PRICE_TOLERANCE as u64,
+            &resolution_config.feed_id,
         )?;
-        let price = price_update.get_price_no_older_than(&Clock::get()?, maximum_age, &feed_id)?;
+        resolution_config.check_resolution_publish_time(price.publish_time)?;
 
         msg!(
             "The price is ({} ± {}) * 10^{}",
@@ -59,18 +102,88 @@ pub fn trigger(ctx: Context<Trigger>) -> Result<()> {
             price.exponent
         );
 
-        let oracle_authority = ctx.accounts.oracle_adapter_pda.deref_mut();
-        oracle_authority.resolution_price = price.price;
+        let mut report = ResolutionReport {
+            source: ResolutionSource::PythPriceFeed,
+            feed_id: resolution_config.feed_id,
+            price: price.price,
+            conf: price.conf,
+            exponent: price.exponent,
+            publish_time: price.publish_time,
+            ..Default::default()
+        };
+
+        let yes = match resolution_config.kind {
+            MarketKind::PriceRule => resolution_config.rule.evaluate(price.price, price.exponent)?,
+            // a touch is resolved by `submit_barrier_price` as soon as it happens, so at expiry
+            // only a final price published right at expiration can still touch the barrier
+            MarketKind::Barrier => {
+                report.source = ResolutionSource::PythBarrier;
+                resolution_config.is_within_market_life(price.publish_time)
+                    && resolution_config.rule.evaluate(price.price, price.exponent)?
+            }
+            MarketKind::RelativePerformance => {
+                if resolution_config.start_recorded_at == 0 {
+                    return Err(ErrorCode::StartPriceNotRecorded.into());
+                }
+                let benchmark_price = ctx
+                    .accounts
+                    .benchmark_price_update
+                    .as_ref()
+                    .ok_or(ErrorCode::MissingBenchmarkPriceUpdate)?
+                    .get_price_no_older_than(
+                        &price_clock,
+                        RESOLUTION_PRICE_TOLERANCE as u64,
+                        &resolution_config.benchmark_feed_id,
+                    )?;
+                resolution_config.check_resolution_publish_time(benchmark_price.publish_time)?;
+
+                msg!(
+                    "The benchmark price is ({} ± {}) * 10^{}",
+                    benchmark_price.price,
+                    benchmark_price.conf,
+                    benchmark_price.exponent
+                );
+
+                report.source = ResolutionSource::PythRelativePerformance;
+                report.benchmark_feed_id = resolution_config.benchmark_feed_id;
+                report.benchmark_price = benchmark_price.price;
+                report.benchmark_exponent = benchmark_price.exponent;
+                report.benchmark_publish_time = benchmark_price.publish_time;
+
+                let [asset_start, benchmark_start] = &resolution_config.start_prices;
+                outperforms(
+                    asset_start,
+                    &PriceSnapshot::new(price.price, price.exponent, price.publish_time)?,
+                    benchmark_start,
+                    &PriceSnapshot::new(
+                        benchmark_price.price,
+                        benchmark_price.exponent,
+                        benchmark_price.publish_time,
+                    )?,
+                )?
+            }
+        };
+        report.outcome = if yes { MarketOutcome::Yes } else { MarketOutcome::No };
 
         //send the price to the market program to resolve the market using cpi
-        let cpi_program = ctx.accounts.market_program.to_account_info();
+        //the market program stores it on the market's resolution record
         let cpi_accounts = ResolveMarket {
-            oracle_adapter_pda: ctx.accounts.oracle_adapter_pda.to_account_info(),
+            payer: ctx.accounts.payer.to_account_info(),
+            oracle_authority: ctx.accounts.oracle_authority.to_account_info(),
             market_config: ctx.accounts.market_config.to_account_info(),
             vault_state: ctx.accounts.vault_state.to_account_info(),
+            resolution_record: ctx.accounts.resolution_record.to_account_info(),
+            market_registry_page: ctx.accounts.market_registry_page.as_ref().map(|page| page.to_account_info()),
+            system_program: ctx.accounts.system_program.to_account_info(),
+            event_authority: ctx.accounts.market_event_authority.to_account_info(),
+            program: ctx.accounts.market_program.to_account_info(),
         };
-        let cpi_context = CpiContext::new(cpi_program, cpi_accounts);
-        market_program::cpi::resolve_market(cpi_context)?;
+        cpi_resolve_market(
+            ctx.accounts.market_program.to_account_info(),
+            cpi_accounts,
+            ctx.bumps.oracle_authority,
+            report,
+        )?;
 
         Ok(())
-}
\ No newline at end of file
+}
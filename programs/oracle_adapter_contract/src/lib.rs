@@ -5,20 +5,40 @@ use anchor_lang::prelude::*;
 pub mod errors;
 pub mod instructions;
 pub mod states;
+pub mod utils;
 
 pub use errors::*;
 pub use instructions::*;
 pub use states::*;
+pub use utils::*;
 
 
 declare_id!("6QAq31696E4a8PKMgzVrTq1uBzvF87JNTeAQyHmGivEJ");
 #[program]
 pub mod oracle_adapter_contract {
     use super::*;
-    pub fn initialize(ctx: Context<Initialize>) -> Result<()> {
-        instructions::initialize(ctx)
+    pub fn initialize(ctx: Context<Initialize>, feed_id: [u8; 32], rule: PriceRule, grace_period: i64) -> Result<()> {
+        instructions::initialize(ctx, feed_id, rule, grace_period)
+    }
+    pub fn initialize_relative(ctx: Context<InitializeRelative>, feed_id: [u8; 32], benchmark_feed_id: [u8; 32], grace_period: i64) -> Result<()> {
+        instructions::initialize_relative(ctx, feed_id, benchmark_feed_id, grace_period)
+    }
+    pub fn record_start_price(ctx: Context<RecordStartPrice>) -> Result<()> {
+        instructions::record_start_price(ctx)
+    }
+    pub fn initialize_barrier(ctx: Context<InitializeBarrier>, feed_id: [u8; 32], rule: PriceRule, grace_period: i64) -> Result<()> {
+        instructions::initialize_barrier(ctx, feed_id, rule, grace_period)
+    }
+    pub fn submit_barrier_price(ctx: Context<SubmitBarrierPrice>) -> Result<()> {
+        instructions::submit_barrier_price(ctx)
     }
     pub fn trigger(ctx: Context<Trigger>) -> Result<()> {
         instructions::trigger(ctx)
     }
+    pub fn force_invalidate(ctx: Context<ForceInvalidate>) -> Result<()> {
+        instructions::force_invalidate(ctx)
+    }
+    pub fn close_resolution_config(ctx: Context<CloseResolutionConfig>) -> Result<()> {
+        instructions::close_resolution_config(ctx)
+    }
 }
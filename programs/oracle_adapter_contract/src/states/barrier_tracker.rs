@@ -0,0 +1,128 @@
+use anchor_lang::prelude::*;
+use crate::errors::ErrorCode;
+use crate::states::{compare_scaled, PriceSnapshot};
+
+pub const BARRIER_TRACKER_SEED: &str = market_program::ADAPTER_BARRIER_TRACKER_SEED;
+
+/// The price path of a barrier market, built from the price updates keepers submit
+#[account]
+pub struct BarrierTracker {
+    pub market_config: Pubkey,
+    pub bump: u8,
+    // the running max and min of the submitted prices
+    pub max_price: PriceSnapshot,
+    pub min_price: PriceSnapshot,
+    // the number of submitted prices
+    pub observations: u64,
+    // the publish time of the latest submitted price, prices must be submitted in publish order
+    pub last_publish_time: i64,
+}
+
+impl BarrierTracker {
+    pub const LEN: usize = 8 + 32 + 1 + PriceSnapshot::LEN + PriceSnapshot::LEN + 8 + 8;
+
+    pub fn record(&mut self, price: PriceSnapshot) -> Result<()> {
+        // a replayed or older update adds nothing to the path
+        if self.observations > 0 && price.publish_time <= self.last_publish_time {
+            return Err(ErrorCode::StalePrice.into());
+        }
+        if self.observations == 0 {
+            self.max_price = price;
+            self.min_price = price;
+        } else {
+            if compare_scaled(
+                price.price as i128,
+                price.exponent,
+                self.max_price.price as i128,
+                self.max_price.exponent,
+            )?
+            .is_gt()
+            {
+                self.max_price = price;
+            }
+            if compare_scaled(
+                price.price as i128,
+                price.exponent,
+                self.min_price.price as i128,
+                self.min_price.exponent,
+            )?
+            .is_lt()
+            {
+                self.min_price = price;
+            }
+        }
+        self.observations = self.observations.saturating_add(1);
+        self.last_publish_time = price.publish_time;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tracker() -> BarrierTracker {
+        BarrierTracker {
+            market_config: Pubkey::default(),
+            bump: 0,
+            max_price: PriceSnapshot::default(),
+            min_price: PriceSnapshot::default(),
+            observations: 0,
+            last_publish_time: 0,
+        }
+    }
+
+    fn snapshot(price: i64, exponent: i32, publish_time: i64) -> PriceSnapshot {
+        PriceSnapshot { price, exponent, publish_time }
+    }
+
+    #[test]
+    fn first_price_sets_both_extremes() {
+        let mut tracker = tracker();
+        tracker.record(snapshot(100, 0, 10)).unwrap();
+        assert_eq!(tracker.max_price, snapshot(100, 0, 10));
+        assert_eq!(tracker.min_price, snapshot(100, 0, 10));
+        assert_eq!(tracker.observations, 1);
+    }
+
+    #[test]
+    fn tracks_running_max_and_min() {
+        let mut tracker = tracker();
+        for (price, publish_time) in [(100, 10), (120, 11), (90, 12), (110, 13)] {
+            tracker.record(snapshot(price, 0, publish_time)).unwrap();
+        }
+        assert_eq!(tracker.max_price, snapshot(120, 0, 11));
+        assert_eq!(tracker.min_price, snapshot(90, 0, 12));
+        assert_eq!(tracker.observations, 4);
+        assert_eq!(tracker.last_publish_time, 13);
+
+        // a tie with the running max keeps the earlier touch
+        tracker.record(snapshot(1_200, -1, 14)).unwrap();
+        assert_eq!(tracker.max_price, snapshot(120, 0, 11));
+    }
+
+    #[test]
+    fn compares_across_exponents() {
+        let mut tracker = tracker();
+        tracker.record(snapshot(100, 0, 10)).unwrap();
+        tracker.record(snapshot(10_001, -2, 11)).unwrap();
+        assert_eq!(tracker.max_price, snapshot(10_001, -2, 11));
+        tracker.record(snapshot(9_999_999, -5, 12)).unwrap();
+        assert_eq!(tracker.min_price, snapshot(9_999_999, -5, 12));
+    }
+
+    #[test]
+    fn rejects_stale_and_out_of_order_prices() {
+        let mut tracker = tracker();
+        tracker.record(snapshot(100, 0, 10)).unwrap();
+        // the same update again
+        assert!(tracker.record(snapshot(100, 0, 10)).is_err());
+        // an older update, even with a new extreme
+        assert!(tracker.record(snapshot(200, 0, 9)).is_err());
+        assert_eq!(tracker.max_price, snapshot(100, 0, 10));
+        assert_eq!(tracker.observations, 1);
+
+        tracker.record(snapshot(200, 0, 11)).unwrap();
+        assert_eq!(tracker.max_price, snapshot(200, 0, 11));
+    }
+}
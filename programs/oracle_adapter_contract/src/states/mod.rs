@@ -1,2 +1,11 @@
 pub mod resolution_config;
 pub use resolution_config::*;
+
+pub mod price_rule;
+pub use price_rule::*;
+
+pub mod relative_performance;
+pub use relative_performance::*;
+
+pub mod barrier_tracker;
+pub use barrier_tracker::*;
@@ -0,0 +1,208 @@
+use anchor_lang::prelude::*;
+use crate::errors::ErrorCode;
+
+/// How the resolution price is compared against the strike(s).
+/// A price equal to a strike only counts as YES where the rule says so.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Comparison {
+    /// YES if price > strike, a tie resolves NO
+    #[default]
+    Above,
+    /// YES if price >= strike, a tie resolves YES
+    AtOrAbove,
+    /// YES if price < strike, a tie resolves NO
+    Below,
+    /// YES if strike <= price <= upper_strike, ties on either bound resolve YES
+    Between,
+    /// YES if price < strike or price > upper_strike, ties on either bound resolve NO
+    Outside,
+    /// YES if price <= strike, a tie resolves YES
+    AtOrBelow,
+}
+
+/// The rule a binary price market resolves on.
+/// Strikes are scaled by `10^strike_exponent`, the same way pyth prices are scaled by their exponent,
+/// e.g. $90k is `strike = 90_000, strike_exponent = 0` or `strike = 9_000_000_000_000, strike_exponent = -8`.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct PriceRule {
+    pub comparison: Comparison,
+    // the strike, or the lower bound for the range comparisons
+    pub strike: i64,
+    // the upper bound for the range comparisons, unused otherwise
+    pub upper_strike: i64,
+    pub strike_exponent: i32,
+}
+
+impl PriceRule {
+    pub const LEN: usize = 1 + 8 + 8 + 4;
+    /// strikes are scaled like pyth prices, whose exponents stay within this range
+    pub const MAX_STRIKE_EXPONENT: i32 = 18;
+
+    pub fn validate(&self) -> Result<()> {
+        if !(-Self::MAX_STRIKE_EXPONENT..=Self::MAX_STRIKE_EXPONENT).contains(&self.strike_exponent) {
+            return Err(ErrorCode::InvalidPriceRule.into());
+        }
+        match self.comparison {
+            Comparison::Between | Comparison::Outside => {
+                if self.strike >= self.upper_strike {
+                    return Err(ErrorCode::InvalidPriceRule.into());
+                }
+            }
+            Comparison::Above | Comparison::AtOrAbove | Comparison::Below | Comparison::AtOrBelow => {
+                if self.upper_strike != 0 {
+                    return Err(ErrorCode::InvalidPriceRule.into());
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Whether the rule compares against a single strike
+    pub fn is_single_sided(&self) -> bool {
+        !matches!(self.comparison, Comparison::Between | Comparison::Outside)
+    }
+
+    /// Returns true if the price `price * 10^exponent` resolves the market YES
+    pub fn evaluate(&self, price: i64, exponent: i32) -> Result<bool> {
+        let cmp_strike = |strike: i64| {
+            compare_scaled(price as i128, exponent, strike as i128, self.strike_exponent)
+        };
+        let outcome = match self.comparison {
+            Comparison::Above => cmp_strike(self.strike)?.is_gt(),
+            Comparison::AtOrAbove => cmp_strike(self.strike)?.is_ge(),
+            Comparison::Below => cmp_strike(self.strike)?.is_lt(),
+            Comparison::AtOrBelow => cmp_strike(self.strike)?.is_le(),
+            Comparison::Between => {
+                cmp_strike(self.strike)?.is_ge() && cmp_strike(self.upper_strike)?.is_le()
+            }
+            Comparison::Outside => {
+                cmp_strike(self.strike)?.is_lt() || cmp_strike(self.upper_strike)?.is_gt()
+            }
+        };
+        Ok(outcome)
+    }
+}
+
+/// Compares `a * 10^a_exponent` with `b * 10^b_exponent` exactly by
+/// rescaling both sides to the smaller exponent.
+pub fn compare_scaled(a: i128, a_exponent: i32, b: i128, b_exponent: i32) -> Result<std::cmp::Ordering> {
+    let scale = |value: i128, exponent: i32| -> Result<i128> {
+        let shift = exponent
+            .checked_sub(a_exponent.min(b_exponent))
+            .and_then(|shift| u32::try_from(shift).ok())
+            .ok_or(ErrorCode::PriceScalingOverflow)?;
+        10i128
+            .checked_pow(shift)
+            .and_then(|factor| value.checked_mul(factor))
+            .ok_or(ErrorCode::PriceScalingOverflow.into())
+    };
+    Ok(scale(a, a_exponent)?.cmp(&scale(b, b_exponent)?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cmp::Ordering;
+
+    fn rule(comparison: Comparison, strike: i64, upper_strike: i64) -> PriceRule {
+        PriceRule {
+            comparison,
+            strike,
+            upper_strike,
+            strike_exponent: 0,
+        }
+    }
+
+    #[test]
+    fn single_sided_comparisons() {
+        let cases = [
+            (Comparison::Above, [false, false, true]),
+            (Comparison::AtOrAbove, [false, true, true]),
+            (Comparison::Below, [true, false, false]),
+            (Comparison::AtOrBelow, [true, true, false]),
+        ];
+        for (comparison, expected) in cases {
+            let rule = rule(comparison, 100, 0);
+            for (price, yes) in [99, 100, 101].into_iter().zip(expected) {
+                assert_eq!(rule.evaluate(price, 0).unwrap(), yes, "{comparison:?} at {price}");
+            }
+        }
+    }
+
+    #[test]
+    fn range_comparisons() {
+        let prices = [99, 100, 150, 200, 201];
+        let between = rule(Comparison::Between, 100, 200);
+        let outside = rule(Comparison::Outside, 100, 200);
+        for (price, yes) in prices.into_iter().zip([false, true, true, true, false]) {
+            assert_eq!(between.evaluate(price, 0).unwrap(), yes, "between at {price}");
+            assert_eq!(outside.evaluate(price, 0).unwrap(), !yes, "outside at {price}");
+        }
+    }
+
+    #[test]
+    fn validate_rejects_malformed_rules() {
+        assert!(rule(Comparison::Above, 100, 0).validate().is_ok());
+        assert!(rule(Comparison::Above, 100, 200).validate().is_err());
+        assert!(rule(Comparison::Between, 100, 200).validate().is_ok());
+        assert!(rule(Comparison::Between, 200, 200).validate().is_err());
+        assert!(rule(Comparison::Outside, 200, 100).validate().is_err());
+        assert!(!rule(Comparison::Between, 100, 200).is_single_sided());
+        assert!(rule(Comparison::AtOrBelow, 100, 0).is_single_sided());
+    }
+
+    #[test]
+    fn validate_bounds_the_strike_exponent() {
+        for strike_exponent in [-18, -8, 0, 18] {
+            let rule = PriceRule { strike_exponent, ..rule(Comparison::Above, 100, 0) };
+            assert!(rule.validate().is_ok(), "exponent {strike_exponent}");
+        }
+        for strike_exponent in [-19, 19, i32::MIN, i32::MAX] {
+            let rule = PriceRule { strike_exponent, ..rule(Comparison::Above, 100, 0) };
+            assert!(rule.validate().is_err(), "exponent {strike_exponent}");
+        }
+    }
+
+    #[test]
+    fn mismatched_exponents() {
+        // $90k strike against a pyth price with 8 decimals
+        let strike = PriceRule {
+            comparison: Comparison::AtOrAbove,
+            strike: 90_000,
+            upper_strike: 0,
+            strike_exponent: 0,
+        };
+        assert!(strike.evaluate(9_000_000_000_000, -8).unwrap());
+        assert!(!strike.evaluate(8_999_999_999_999, -8).unwrap());
+
+        // the same strike written with more decimals than the price
+        let strike = PriceRule {
+            strike: 90_000_000_000,
+            strike_exponent: -6,
+            ..strike
+        };
+        assert!(strike.evaluate(9_000_000, -2).unwrap());
+        assert!(!strike.evaluate(8_999_999, -2).unwrap());
+    }
+
+    #[test]
+    fn compare_scaled_boundaries() {
+        assert_eq!(compare_scaled(15, -1, 1, 0).unwrap(), Ordering::Greater);
+        assert_eq!(compare_scaled(10, -1, 1, 0).unwrap(), Ordering::Equal);
+        assert_eq!(compare_scaled(-5, 0, 5, 0).unwrap(), Ordering::Less);
+        assert_eq!(compare_scaled(1, 3, 999, 0).unwrap(), Ordering::Greater);
+        assert_eq!(compare_scaled(0, 10, 0, -10).unwrap(), Ordering::Equal);
+    }
+
+    #[test]
+    fn compare_scaled_extreme_exponents() {
+        // 10^38 still fits an i128, 10^39 does not
+        assert_eq!(compare_scaled(1, 38, 1, 0).unwrap(), Ordering::Greater);
+        assert!(compare_scaled(1, 39, 1, 0).is_err());
+        assert!(compare_scaled(1, i32::MAX, 1, i32::MIN).is_err());
+        assert!(compare_scaled(i64::MAX as i128, 20, 1, 0).is_err());
+        // equal exponents never rescale, however extreme
+        assert_eq!(compare_scaled(2, i32::MIN, 1, i32::MIN).unwrap(), Ordering::Greater);
+        assert!(rule(Comparison::Above, 100, 0).evaluate(1, 40).is_err());
+    }
+}
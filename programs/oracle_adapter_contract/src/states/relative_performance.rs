@@ -0,0 +1,97 @@
+use anchor_lang::prelude::*;
+use crate::errors::ErrorCode;
+use crate::states::compare_scaled;
+
+/// A pyth price observation, the price is `price * 10^exponent`
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct PriceSnapshot {
+    pub price: i64,
+    pub exponent: i32,
+    pub publish_time: i64,
+}
+
+impl PriceSnapshot {
+    pub const LEN: usize = 8 + 4 + 8;
+
+    pub fn new(price: i64, exponent: i32, publish_time: i64) -> Result<Self> {
+        // percentage changes are only meaningful for positive prices
+        if price <= 0 {
+            return Err(ErrorCode::NonPositivePrice.into());
+        }
+        Ok(Self {
+            price,
+            exponent,
+            publish_time,
+        })
+    }
+}
+
+/// Returns true if the asset's percentage change from `asset_start` to `asset_end`
+/// is strictly greater than the benchmark's, a tie resolves NO.
+///
+/// `asset_end / asset_start > benchmark_end / benchmark_start` is compared as
+/// `asset_end * benchmark_start > benchmark_end * asset_start` so no precision is lost.
+pub fn outperforms(
+    asset_start: &PriceSnapshot,
+    asset_end: &PriceSnapshot,
+    benchmark_start: &PriceSnapshot,
+    benchmark_end: &PriceSnapshot,
+) -> Result<bool> {
+    let lhs = (asset_end.price as i128) * (benchmark_start.price as i128);
+    let lhs_exponent = asset_end
+        .exponent
+        .checked_add(benchmark_start.exponent)
+        .ok_or(ErrorCode::PriceScalingOverflow)?;
+    let rhs = (benchmark_end.price as i128) * (asset_start.price as i128);
+    let rhs_exponent = benchmark_end
+        .exponent
+        .checked_add(asset_start.exponent)
+        .ok_or(ErrorCode::PriceScalingOverflow)?;
+    Ok(compare_scaled(lhs, lhs_exponent, rhs, rhs_exponent)?.is_gt())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn snapshot(price: i64, exponent: i32) -> PriceSnapshot {
+        PriceSnapshot::new(price, exponent, 0).unwrap()
+    }
+
+    #[test]
+    fn rejects_non_positive_prices() {
+        assert!(PriceSnapshot::new(0, -8, 0).is_err());
+        assert!(PriceSnapshot::new(-1, -8, 0).is_err());
+    }
+
+    #[test]
+    fn compares_percentage_changes() {
+        // asset +10%, benchmark +5%
+        assert!(outperforms(&snapshot(100, 0), &snapshot(110, 0), &snapshot(200, 0), &snapshot(210, 0)).unwrap());
+        // asset +5%, benchmark +10%
+        assert!(!outperforms(&snapshot(200, 0), &snapshot(210, 0), &snapshot(100, 0), &snapshot(110, 0)).unwrap());
+        // both -10%, a tie resolves NO
+        assert!(!outperforms(&snapshot(100, 0), &snapshot(90, 0), &snapshot(50, 0), &snapshot(45, 0)).unwrap());
+    }
+
+    #[test]
+    fn mismatched_exponents() {
+        // asset 1.00 -> 1.10 quoted with 2 decimals, benchmark 2 -> 2.1 quoted with 8 decimals
+        let asset = (snapshot(100, -2), snapshot(110, -2));
+        let benchmark = (snapshot(200_000_000, -8), snapshot(210_000_000, -8));
+        assert!(outperforms(&asset.0, &asset.1, &benchmark.0, &benchmark.1).unwrap());
+        assert!(!outperforms(&benchmark.0, &benchmark.1, &asset.0, &asset.1).unwrap());
+
+        // the exponent changing between the start and end snapshot of the same feed
+        assert!(!outperforms(&snapshot(100, 0), &snapshot(1_000, -1), &snapshot(5, 0), &snapshot(5, 0)).unwrap());
+        assert!(outperforms(&snapshot(100, 0), &snapshot(1_001, -1), &snapshot(5, 0), &snapshot(5, 0)).unwrap());
+    }
+
+    #[test]
+    fn extreme_values() {
+        // products of two i64 prices fit an i128
+        assert!(outperforms(&snapshot(1, 0), &snapshot(i64::MAX, 0), &snapshot(i64::MAX, 0), &snapshot(i64::MAX, 0)).unwrap());
+        assert!(outperforms(&snapshot(1, i32::MAX), &snapshot(1, 0), &snapshot(1, 0), &snapshot(1, 0)).is_err());
+        assert!(outperforms(&snapshot(1, -30), &snapshot(1, 30), &snapshot(1, 0), &snapshot(1, 0)).is_err());
+    }
+}
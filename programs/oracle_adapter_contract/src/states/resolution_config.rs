@@ -1,4 +1,29 @@
 use anchor_lang::prelude::*;
+use crate::errors::ErrorCode;
+use crate::states::{PriceRule, PriceSnapshot};
+
+// the oldest price update (in seconds) the adapter accepts
+pub const MAXIMUM_PRICE_AGE: u64 = 30;
+// trigger only resolves on prices published within this many seconds after expiration,
+// however late it runs, so the caller cannot pick the moment that gives the outcome they want
+pub const RESOLUTION_PRICE_TOLERANCE: i64 = 60;
+// the grace period has to leave trigger time to run, and grace_period_end() must not overflow
+pub const MIN_GRACE_PERIOD: i64 = 60 * 60;
+pub const MAX_GRACE_PERIOD: i64 = 30 * 24 * 60 * 60;
+// start prices of relative performance markets are recorded within this many seconds of trading opening
+pub const START_PRICE_WINDOW: i64 = 300;
+
+/// What the market resolves on
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum MarketKind {
+    /// the price of `feed_id` at expiry compared against the `rule`
+    #[default]
+    PriceRule,
+    /// YES if `feed_id` outperforms `benchmark_feed_id` between the recorded start prices and expiry
+    RelativePerformance,
+    /// YES as soon as a price of `feed_id` during the market's life satisfies the `rule`, NO if it never did
+    Barrier,
+}
 
 #[account]
 pub struct ResolutionConfig {
@@ -6,8 +31,95 @@ pub struct ResolutionConfig {
     pub resolution_config_bump: u8,
     pub resolution_config_created_at: i64,
     pub resolution_config_expiration: i64,
+    // the pyth feed the market resolves on
+    pub feed_id: [u8; 32],
+    // ct1 wins if the resolution price satisfies this rule, for barrier markets the barrier is touched if it does
+    pub rule: PriceRule,
+    // seconds after expiration a valid price may still land before the market can be invalidated
+    pub grace_period: i64,
+    pub kind: MarketKind,
+    // the feed `feed_id` is compared against in relative performance markets
+    pub benchmark_feed_id: [u8; 32],
+    // the starting prices of `feed_id` and `benchmark_feed_id`
+    pub start_prices: [PriceSnapshot; 2],
+    pub start_recorded_at: i64,
 }
 
 impl ResolutionConfig {
-    pub const LEN: usize = 8 + 32 + 1 + 8 + 8;
-}
\ No newline at end of file
+    pub const LEN: usize =
+        8 + 32 + 1 + 8 + 8 + 32 + PriceRule::LEN + 8 + 1 + 32 + 2 * PriceSnapshot::LEN + 8;
+
+    /// Whether a price published at `publish_time` was published during the market's life
+    pub fn is_within_market_life(&self, publish_time: i64) -> bool {
+        publish_time >= self.resolution_config_created_at
+            && publish_time <= self.resolution_config_expiration
+    }
+
+    /// Fails unless the grace period is between MIN_GRACE_PERIOD and MAX_GRACE_PERIOD
+    pub fn validate_grace_period(grace_period: i64) -> Result<()> {
+        if !(MIN_GRACE_PERIOD..=MAX_GRACE_PERIOD).contains(&grace_period) {
+            return Err(ErrorCode::InvalidGracePeriod.into());
+        }
+        Ok(())
+    }
+
+    /// Fails unless `publish_time` lies within RESOLUTION_PRICE_TOLERANCE seconds after expiration
+    pub fn check_resolution_publish_time(&self, publish_time: i64) -> Result<()> {
+        let latest = self
+            .resolution_config_expiration
+            .checked_add(RESOLUTION_PRICE_TOLERANCE)
+            .ok_or(ErrorCode::ResolutionPriceOutsideWindow)?;
+        if publish_time < self.resolution_config_expiration || publish_time > latest {
+            return Err(ErrorCode::ResolutionPriceOutsideWindow.into());
+        }
+        Ok(())
+    }
+
+    /// After this timestamp `trigger` is closed and anyone can `force_invalidate` the market
+    pub fn grace_period_end(&self) -> Result<i64> {
+        self.resolution_config_expiration
+            .checked_add(self.grace_period)
+            .ok_or(ErrorCode::InvalidGracePeriod.into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn resolution_config(expiration: i64) -> ResolutionConfig {
+        ResolutionConfig {
+            market_config: Pubkey::default(),
+            resolution_config_bump: 0,
+            resolution_config_created_at: 0,
+            resolution_config_expiration: expiration,
+            feed_id: [0; 32],
+            rule: PriceRule::default(),
+            grace_period: 3600,
+            kind: MarketKind::PriceRule,
+            benchmark_feed_id: [0; 32],
+            start_prices: [PriceSnapshot::default(); 2],
+            start_recorded_at: 0,
+        }
+    }
+
+    #[test]
+    fn resolution_price_must_follow_expiration_closely() {
+        let config = resolution_config(1_000);
+        assert!(config.check_resolution_publish_time(999).is_err());
+        assert!(config.check_resolution_publish_time(1_000).is_ok());
+        assert!(config.check_resolution_publish_time(1_000 + RESOLUTION_PRICE_TOLERANCE).is_ok());
+        assert!(config.check_resolution_publish_time(1_001 + RESOLUTION_PRICE_TOLERANCE).is_err());
+        assert!(resolution_config(i64::MAX).check_resolution_publish_time(i64::MAX).is_err());
+    }
+
+    #[test]
+    fn grace_period_bounds() {
+        for grace_period in [i64::MIN, -1, 0, MIN_GRACE_PERIOD - 1, MAX_GRACE_PERIOD + 1, i64::MAX] {
+            assert!(ResolutionConfig::validate_grace_period(grace_period).is_err(), "{grace_period}");
+        }
+        for grace_period in [MIN_GRACE_PERIOD, 86_400, MAX_GRACE_PERIOD] {
+            assert!(ResolutionConfig::validate_grace_period(grace_period).is_ok(), "{grace_period}");
+        }
+    }
+}
@@ -0,0 +1,37 @@
+use anchor_lang::prelude::*;
+use market_program::{
+    self,
+    cpi::accounts::{ResolveMarket, SetStartPricePending},
+    ResolutionReport, ORACLE_AUTHORITY_SEED,
+};
+
+/// Send the report to the market program to resolve the market using cpi,
+/// signed by the oracle authority pda of this program.
+pub fn cpi_resolve_market<'info>(
+    market_program: AccountInfo<'info>,
+    accounts: ResolveMarket<'info>,
+    oracle_authority_bump: u8,
+    report: ResolutionReport,
+) -> Result<()> {
+    let signer_seeds: &[&[&[u8]]] = &[&[
+        ORACLE_AUTHORITY_SEED.as_bytes(),
+        &[oracle_authority_bump],
+    ]];
+    let cpi_context = CpiContext::new_with_signer(market_program, accounts, signer_seeds);
+    market_program::cpi::resolve_market(cpi_context, report)
+}
+
+/// Opens or closes a relative performance market to trading while its start prices are missing
+pub fn cpi_set_start_price_pending<'info>(
+    market_program: AccountInfo<'info>,
+    accounts: SetStartPricePending<'info>,
+    oracle_authority_bump: u8,
+    pending: bool,
+) -> Result<()> {
+    let signer_seeds: &[&[&[u8]]] = &[&[
+        ORACLE_AUTHORITY_SEED.as_bytes(),
+        &[oracle_authority_bump],
+    ]];
+    let cpi_context = CpiContext::new_with_signer(market_program, accounts, signer_seeds);
+    market_program::cpi::set_start_price_pending(cpi_context, pending)
+}
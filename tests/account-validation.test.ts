@@ -0,0 +1,221 @@
+import * as anchor from "@coral-xyz/anchor";
+import { expect } from "chai";
+import {
+  createMint,
+  getAssociatedTokenAddressSync,
+  getOrCreateAssociatedTokenAccount,
+  mintTo,
+  TOKEN_2022_PROGRAM_ID,
+} from "@solana/spl-token";
+import { MarketProgram } from "../target/types/market_program";
+import {
+  setupBuyBetTest,
+  buyBet,
+  getAuthAddress,
+  getCollateralInfoAddress,
+  getVaultAddress,
+  getct1MintAddress,
+  getct2MintAddress,
+} from "./utils";
+
+const provider = anchor.AnchorProvider.env();
+anchor.setProvider(provider);
+const program = anchor.workspace.MarketProgram as anchor.Program<MarketProgram>;
+const connection = provider.connection;
+const walletKeypair = (provider.wallet as anchor.Wallet & { payer: anchor.web3.Keypair }).payer;
+
+type Market = {
+  configAddress: anchor.web3.PublicKey;
+  vaultStateAddress: anchor.web3.PublicKey;
+  vault: anchor.web3.PublicKey;
+  authority: anchor.web3.PublicKey;
+  ct1Mint: anchor.web3.PublicKey;
+  ct2Mint: anchor.web3.PublicKey;
+  collateralMint: anchor.web3.PublicKey;
+};
+
+const newCollateralMint = async () => {
+  const mint = await createMint(connection, walletKeypair, walletKeypair.publicKey, null, 6, undefined, undefined, TOKEN_2022_PROGRAM_ID);
+  const account = await getOrCreateAssociatedTokenAccount(
+    connection, walletKeypair, mint, walletKeypair.publicKey, false, "confirmed", undefined, TOKEN_2022_PROGRAM_ID
+  );
+  await mintTo(connection, walletKeypair, mint, account.address, walletKeypair, 1_000_000_000, [], undefined, TOKEN_2022_PROGRAM_ID);
+  return mint;
+};
+
+const newMarket = async (collateralMint: anchor.web3.PublicKey): Promise<Market> => {
+  const { configAddress, vaultStateAddress } = await setupBuyBetTest(
+    program, connection, walletKeypair, collateralMint, TOKEN_2022_PROGRAM_ID,
+    { name: "Validation", description: "Validation", expiration: new anchor.BN(Math.floor(Date.now() / 1000) + 86400) }
+  );
+  const market = {
+    configAddress,
+    vaultStateAddress,
+    vault: (await getVaultAddress(vaultStateAddress, program.programId))[0],
+    authority: (await getAuthAddress(vaultStateAddress, program.programId))[0],
+    ct1Mint: (await getct1MintAddress(vaultStateAddress, program.programId))[0],
+    ct2Mint: (await getct2MintAddress(vaultStateAddress, program.programId))[0],
+    collateralMint,
+  };
+  // leaves the wallet holding both conditional tokens of every market
+  await buyBet(
+    program, walletKeypair, new anchor.BN(1_000_000), configAddress, collateralMint, TOKEN_2022_PROGRAM_ID,
+    vaultStateAddress, market.vault, market.ct1Mint, market.ct2Mint
+  );
+  return market;
+};
+
+const ata = (mint: anchor.web3.PublicKey) =>
+  getAssociatedTokenAddressSync(mint, walletKeypair.publicKey, false, TOKEN_2022_PROGRAM_ID);
+
+// the accounts shared by buy_bet and sell_bet for `market`, with any of them swapped out
+const tradeAccounts = (market: Market, overrides: Partial<Market> = {}) => {
+  const m = { ...market, ...overrides };
+  return {
+    bettor: walletKeypair.publicKey,
+    authority: m.authority,
+    collateralAccount: ata(m.collateralMint),
+    marketConfig: m.configAddress,
+    vaultState: m.vaultStateAddress,
+    vault: m.vault,
+    ct1Mint: m.ct1Mint,
+    ct2Mint: m.ct2Mint,
+    ct1Account: ata(m.ct1Mint),
+    ct2Account: ata(m.ct2Mint),
+    collateralMint: m.collateralMint,
+    collateralTokenProgram: TOKEN_2022_PROGRAM_ID,
+    tokenProgram: TOKEN_2022_PROGRAM_ID,
+  };
+};
+
+const expectRejected = async (send: () => Promise<string>, error: string) => {
+  try {
+    await send();
+    expect.fail(`Should have failed with ${error}`);
+  } catch (err) {
+    expect(err.toString()).to.include(error);
+  }
+};
+
+describe("Account Validation Tests", () => {
+  let market: Market;
+  let other: Market;
+  let otherCollateral: Market;
+
+  before(async () => {
+    const sig = await connection.requestAirdrop(provider.wallet.publicKey, anchor.web3.LAMPORTS_PER_SOL * 5);
+    await connection.confirmTransaction(sig, "confirmed");
+
+    const collateralMint = await newCollateralMint();
+    market = await newMarket(collateralMint);
+    other = await newMarket(collateralMint);
+    otherCollateral = await newMarket(await newCollateralMint());
+  });
+
+  describe("buy_bet", () => {
+    const buy = (overrides: Partial<Market>) => async () => {
+      const collateralMint = overrides.collateralMint ?? market.collateralMint;
+      return program.methods
+        .buyBet(new anchor.BN(1_000), false)
+        .accountsPartial({
+          ...tradeAccounts(market, overrides),
+          collateralInfo: (await getCollateralInfoAddress(collateralMint, program.programId))[0],
+        })
+        .rpc();
+    };
+
+    it("should reject the conditional token mint of another market", async () => {
+      await expectRejected(buy({ ct1Mint: other.ct1Mint }), "ConstraintHasOne");
+      await expectRejected(buy({ ct2Mint: other.ct2Mint }), "ConstraintHasOne");
+    });
+
+    it("should reject the vault of another market", async () => {
+      await expectRejected(buy({ vault: other.vault }), "ConstraintHasOne");
+    });
+
+    it("should reject the vault state and authority of another market", async () => {
+      await expectRejected(
+        buy({ vaultStateAddress: other.vaultStateAddress, authority: other.authority }),
+        "ConstraintHasOne"
+      );
+    });
+
+    it("should reject a collateral mint the vault does not hold", async () => {
+      await expectRejected(buy({ collateralMint: otherCollateral.collateralMint }), "ConstraintSeeds");
+    });
+
+    it("should reject an authority that is not the market's", async () => {
+      await expectRejected(buy({ authority: other.authority }), "ConstraintSeeds");
+    });
+  });
+
+  describe("sell_bet", () => {
+    const sell = (overrides: Partial<Market>) => () =>
+      program.methods
+        .sellBet(new anchor.BN(1_000))
+        .accountsPartial(tradeAccounts(market, overrides))
+        .rpc();
+
+    it("should reject the conditional token mint of another market", async () => {
+      await expectRejected(sell({ ct1Mint: other.ct1Mint }), "ConstraintHasOne");
+      await expectRejected(sell({ ct2Mint: other.ct2Mint }), "ConstraintHasOne");
+    });
+
+    it("should reject the vault of another market", async () => {
+      await expectRejected(sell({ vault: other.vault }), "ConstraintHasOne");
+    });
+
+    it("should reject the vault state and authority of another market", async () => {
+      await expectRejected(
+        sell({ vaultStateAddress: other.vaultStateAddress, authority: other.authority }),
+        "ConstraintHasOne"
+      );
+    });
+
+    it("should reject a collateral mint the vault does not hold", async () => {
+      await expectRejected(sell({ collateralMint: otherCollateral.collateralMint }), "ConstraintSeeds");
+    });
+  });
+
+  describe("get_reward", () => {
+    const claim = (overrides: Partial<Market>, ctMint = market.ct1Mint) => () => {
+      const m = { ...market, ...overrides };
+      return program.methods
+        .getReward(new anchor.BN(1_000))
+        .accountsPartial({
+          bettor: walletKeypair.publicKey,
+          authority: m.authority,
+          collateralAccount: ata(m.collateralMint),
+          ctMint,
+          otherCtMint: m.ct2Mint,
+          ctAccount: ata(ctMint),
+          marketConfig: m.configAddress,
+          vaultState: m.vaultStateAddress,
+          vault: m.vault,
+          collateralMint: m.collateralMint,
+          collateralTokenProgram: TOKEN_2022_PROGRAM_ID,
+          tokenProgram: TOKEN_2022_PROGRAM_ID,
+        })
+        .rpc();
+    };
+
+    it("should reject the conditional token mint of another market", async () => {
+      await expectRejected(claim({}, other.ct1Mint), "WrongWinningToken");
+    });
+
+    it("should reject the vault state and authority of another market", async () => {
+      await expectRejected(
+        claim({ vaultStateAddress: other.vaultStateAddress, authority: other.authority }),
+        "ConstraintHasOne"
+      );
+    });
+
+    it("should reject the vault of another market", async () => {
+      await expectRejected(claim({ vault: other.vault }), "ConstraintHasOne");
+    });
+
+    it("should reject a collateral mint the vault does not hold", async () => {
+      await expectRejected(claim({ collateralMint: otherCollateral.collateralMint }), "ConstraintSeeds");
+    });
+  });
+});
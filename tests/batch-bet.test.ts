@@ -0,0 +1,183 @@
+import * as anchor from "@coral-xyz/anchor";
+import { expect } from "chai";
+import {
+  createMint,
+  getAccount,
+  getAssociatedTokenAddressSync,
+  getOrCreateAssociatedTokenAccount,
+  mintTo,
+  TOKEN_2022_PROGRAM_ID,
+} from "@solana/spl-token";
+import { MarketProgram } from "../target/types/market_program";
+import {
+  setupBuyBetTest,
+  batchBuyBet,
+  batchSellBet,
+  batchBetMarketAccounts,
+  createBatchBetLookupTable,
+} from "./utils";
+import {
+  MarketHarness,
+  startMarketHarness,
+  createCollateralMint,
+  createBettor,
+  createResolvableMarket,
+  setLookupTable,
+} from "./utils/bankrun";
+
+// MAX_BATCH_ENTRIES of the market program, the most entries whose accounts fit in one transaction
+const MAX_BATCH_ENTRIES = 6;
+
+const provider = anchor.AnchorProvider.env();
+anchor.setProvider(provider);
+const program = anchor.workspace.MarketProgram as anchor.Program<MarketProgram>;
+const connection = provider.connection;
+const walletKeypair = (provider.wallet as anchor.Wallet & { payer: anchor.web3.Keypair }).payer;
+
+describe("Batch Bet Tests", () => {
+  const marketCount = 3;
+  const amount = new anchor.BN(100_000);
+  let collateralMint: anchor.web3.PublicKey;
+  const configAddresses: anchor.web3.PublicKey[] = [];
+  let lookupTable: anchor.web3.AddressLookupTableAccount;
+
+  const ctBalances = async (configAddress: anchor.web3.PublicKey) => {
+    const marketConfig = await program.account.marketConfig.fetch(configAddress);
+    return Promise.all(
+      [marketConfig.ct1Mint, marketConfig.ct2Mint].map(async (mint) =>
+        Number((await getAccount(
+          connection,
+          getAssociatedTokenAddressSync(mint, walletKeypair.publicKey, false, TOKEN_2022_PROGRAM_ID),
+          "confirmed",
+          TOKEN_2022_PROGRAM_ID
+        )).amount)
+      )
+    );
+  };
+
+  before(async () => {
+    const sig = await connection.requestAirdrop(provider.wallet.publicKey, anchor.web3.LAMPORTS_PER_SOL * 10);
+    await connection.confirmTransaction(sig, "confirmed");
+
+    collateralMint = await createMint(connection, walletKeypair, walletKeypair.publicKey, null, 6, undefined, undefined, TOKEN_2022_PROGRAM_ID);
+    const collateralAccount = (await getOrCreateAssociatedTokenAccount(
+      connection, walletKeypair, collateralMint, walletKeypair.publicKey, false, "confirmed", undefined, TOKEN_2022_PROGRAM_ID
+    )).address;
+    await mintTo(connection, walletKeypair, collateralMint, collateralAccount, walletKeypair, 10_000_000, [], undefined, TOKEN_2022_PROGRAM_ID);
+
+    for (let i = 0; i < marketCount; i++) {
+      const { configAddress } = await setupBuyBetTest(
+        program, connection, walletKeypair, collateralMint, TOKEN_2022_PROGRAM_ID,
+        { name: `Batch ${i}`, description: `Batch ${i}`, expiration: new anchor.BN(Math.floor(Date.now() / 1000) + 86400) }
+      );
+      configAddresses.push(configAddress);
+    }
+    lookupTable = await createBatchBetLookupTable(
+      program, walletKeypair, walletKeypair.publicKey, configAddresses, collateralMint, TOKEN_2022_PROGRAM_ID
+    );
+  });
+
+  it("should split collateral into every market of the batch", async () => {
+    await batchBuyBet(
+      program, walletKeypair, configAddresses.map((configAddress) => ({ configAddress, amount })),
+      collateralMint, TOKEN_2022_PROGRAM_ID, lookupTable
+    );
+    for (const configAddress of configAddresses) {
+      expect(await ctBalances(configAddress)).to.deep.equal([amount.toNumber(), amount.toNumber()]);
+      const { vaultState } = await program.account.marketConfig.fetch(configAddress);
+      expect((await program.account.vaultState.fetch(vaultState)).vaultCollateralBalance.toNumber()).to.equal(amount.toNumber());
+    }
+  });
+
+  it("should merge sets back in every market of the batch", async () => {
+    const half = amount.divn(2);
+    await batchSellBet(
+      program, walletKeypair, configAddresses.map((configAddress) => ({ configAddress, amount: half })),
+      collateralMint, TOKEN_2022_PROGRAM_ID, lookupTable
+    );
+    for (const configAddress of configAddresses) {
+      expect(await ctBalances(configAddress)).to.deep.equal([half.toNumber(), half.toNumber()]);
+    }
+  });
+
+  it("should reject accounts that do not match their entry", async () => {
+    const marketConfig = await program.account.marketConfig.fetch(configAddresses[1]);
+    try {
+      await program.methods
+        .batchBuyBet([{ vaultState: marketConfig.vaultState, amount }], false)
+        .accountsPartial({
+          bettor: walletKeypair.publicKey,
+          collateralMint,
+          collateralTokenProgram: TOKEN_2022_PROGRAM_ID,
+          tokenProgram: TOKEN_2022_PROGRAM_ID,
+        })
+        .remainingAccounts(await batchBetMarketAccounts(program, walletKeypair.publicKey, configAddresses[0]))
+        .rpc();
+      expect.fail("Should have failed with another market's accounts");
+    } catch (err) {
+      expect(err.toString()).to.include("InvalidBatchAccount");
+    }
+  });
+
+  describe("compute", () => {
+    let h: MarketHarness;
+
+    before(async () => {
+      h = await startMarketHarness();
+    });
+
+    it("should fit a full batch in the account lock and compute limits", async () => {
+      const collateral = await createCollateralMint(h);
+      const markets = [];
+      for (let i = 0; i < MAX_BATCH_ENTRIES; i++) {
+        markets.push(await createResolvableMarket(h, collateral));
+      }
+      const bettor = await createBettor(h, collateral, amount.muln(MAX_BATCH_ENTRIES));
+      const ata = (mint: anchor.web3.PublicKey) =>
+        getAssociatedTokenAddressSync(mint, bettor.publicKey, false, TOKEN_2022_PROGRAM_ID);
+      const remainingAccounts = markets.flatMap((market) =>
+        [
+          market.configAddress,
+          market.vaultStateAddress,
+          market.authority,
+          market.vault,
+          market.ct1Mint,
+          market.ct2Mint,
+          ata(market.ct1Mint),
+          ata(market.ct2Mint),
+        ].map((pubkey, i) => ({ pubkey, isSigner: false, isWritable: i !== 2 }))
+      );
+      const ix = await h.program.methods
+        .batchBuyBet(markets.map((market) => ({ vaultState: market.vaultStateAddress, amount })), true)
+        .accountsPartial({
+          bettor: bettor.publicKey,
+          collateralAccount: ata(collateral),
+          collateralMint: collateral,
+          collateralInfo: markets[0].collateralInfo,
+          collateralTokenProgram: TOKEN_2022_PROGRAM_ID,
+          tokenProgram: TOKEN_2022_PROGRAM_ID,
+        })
+        .remainingAccounts(remainingAccounts)
+        .instruction();
+      const computeIx = anchor.web3.ComputeBudgetProgram.setComputeUnitLimit({ units: 1_400_000 });
+      // the signer and the invoked programs stay in the message itself
+      const table = await setLookupTable(h, ix.keys.filter((meta) => !meta.isSigner).map((meta) => meta.pubkey));
+
+      const message = new anchor.web3.TransactionMessage({
+        payerKey: bettor.publicKey,
+        recentBlockhash: h.context.lastBlockhash,
+        instructions: [computeIx, ix],
+      }).compileToV0Message([table]);
+      // the payer, the programs and every account of the instruction
+      expect(message.getAccountKeys({ addressLookupTableAccounts: [table] }).length).to.be.at.most(64);
+      const tx = new anchor.web3.VersionedTransaction(message);
+      tx.sign([bettor]);
+
+      const simulation = await h.context.banksClient.simulateTransaction(tx);
+      expect(simulation.result).to.be.null;
+      const units = Number(simulation.meta.computeUnitsConsumed);
+      console.log(`      batch_buy_bet of ${MAX_BATCH_ENTRIES} markets: ${units} compute units`);
+      expect(units).to.be.lessThan(1_400_000);
+    });
+  });
+});
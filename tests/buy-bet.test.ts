@@ -63,7 +63,6 @@ describe("Buy Bet Tests", () => {
       const expiration = new anchor.BN(Math.floor(Date.now() / 1000) + 86400);
 
       const config = {
-        index,
         name,
         description,
         expiration
@@ -153,7 +152,6 @@ describe("Buy Bet Tests", () => {
       const expiration = new anchor.BN(Math.floor(Date.now() / 1000) + 86400);
 
       const config = {
-        index,
         name,
         description,
         expiration
@@ -284,7 +282,6 @@ describe("Buy Bet Tests", () => {
       const expiration = new anchor.BN(Math.floor(Date.now() / 1000) + 86400);
 
       const config = {
-        index,
         name,
         description,
         expiration
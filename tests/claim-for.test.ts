@@ -0,0 +1,164 @@
+import * as anchor from "@coral-xyz/anchor";
+import { expect } from "chai";
+import {
+  createCloseAccountInstruction,
+  createMint,
+  createRevokeInstruction,
+  getAccount,
+  getAssociatedTokenAddressSync,
+  getOrCreateAssociatedTokenAccount,
+  mintTo,
+  TOKEN_2022_PROGRAM_ID,
+} from "@solana/spl-token";
+import { MarketProgram } from "../target/types/market_program";
+import { setupBuyBetTest, buyBet, claimFor, getAuthAddress, getVaultAddress } from "./utils";
+import {
+  MarketHarness,
+  startMarketHarness,
+  createCollateralMint,
+  createBettor,
+  createResolvableMarket,
+  harnessBuyBet,
+  harnessClaimFor,
+  resolveWithPrice,
+  sendInstructions,
+  tokenBalance,
+} from "./utils/bankrun";
+
+const provider = anchor.AnchorProvider.env();
+anchor.setProvider(provider);
+const program = anchor.workspace.MarketProgram as anchor.Program<MarketProgram>;
+const connection = provider.connection;
+const walletKeypair = (provider.wallet as anchor.Wallet & { payer: anchor.web3.Keypair }).payer;
+
+describe("Claim For Tests", () => {
+  let configAddress: anchor.web3.PublicKey;
+  let vaultStateAddress: anchor.web3.PublicKey;
+  let ct1Mint: anchor.web3.PublicKey;
+  let ct2Mint: anchor.web3.PublicKey;
+
+  before(async () => {
+    const sig = await connection.requestAirdrop(provider.wallet.publicKey, anchor.web3.LAMPORTS_PER_SOL * 5);
+    await connection.confirmTransaction(sig, "confirmed");
+
+    const collateralMint = await createMint(connection, walletKeypair, walletKeypair.publicKey, null, 6, undefined, undefined, TOKEN_2022_PROGRAM_ID);
+    const collateralAccount = (await getOrCreateAssociatedTokenAccount(
+      connection, walletKeypair, collateralMint, walletKeypair.publicKey, false, "confirmed", undefined, TOKEN_2022_PROGRAM_ID
+    )).address;
+    await mintTo(connection, walletKeypair, collateralMint, collateralAccount, walletKeypair, 1_000_000, [], undefined, TOKEN_2022_PROGRAM_ID);
+
+    ({ configAddress, vaultStateAddress } = await setupBuyBetTest(
+      program, connection, walletKeypair, collateralMint, TOKEN_2022_PROGRAM_ID,
+      { name: "Crank", description: "Crank", expiration: new anchor.BN(Math.floor(Date.now() / 1000) + 86400) }
+    ));
+    ({ ct1Mint, ct2Mint } = await program.account.marketConfig.fetch(configAddress));
+    const [vault] = await getVaultAddress(vaultStateAddress, program.programId);
+    await buyBet(
+      program, walletKeypair, new anchor.BN(500_000), configAddress, collateralMint, TOKEN_2022_PROGRAM_ID,
+      vaultStateAddress, vault, ct1Mint, ct2Mint, true
+    );
+  });
+
+  it("should delegate both conditional token accounts to the market authority when asked at buy time", async () => {
+    const [authority] = await getAuthAddress(vaultStateAddress, program.programId);
+    for (const mint of [ct1Mint, ct2Mint]) {
+      const account = await getAccount(
+        connection,
+        getAssociatedTokenAddressSync(mint, walletKeypair.publicKey, false, TOKEN_2022_PROGRAM_ID),
+        "confirmed",
+        TOKEN_2022_PROGRAM_ID
+      );
+      expect(account.delegate.toString()).to.equal(authority.toString());
+      expect(account.delegatedAmount).to.equal(BigInt("18446744073709551615"));
+    }
+  });
+
+  it("should fail to claim for holders before resolution", async () => {
+    try {
+      await claimFor(program, configAddress, ct1Mint, [walletKeypair.publicKey]);
+      expect.fail("Should have failed before resolution");
+    } catch (err) {
+      expect(err.toString()).to.include("MarketNotResolved");
+    }
+  });
+
+  describe("after resolution", () => {
+    let h: MarketHarness;
+    const ata = (mint: anchor.web3.PublicKey, owner: anchor.web3.PublicKey) =>
+      getAssociatedTokenAddressSync(mint, owner, false, TOKEN_2022_PROGRAM_ID);
+    // the delegate option tag follows the amount in every token account
+    const hasDelegate = async (account: anchor.web3.PublicKey) =>
+      Buffer.from((await h.context.banksClient.getAccount(account)).data).readUInt32LE(72) === 1;
+
+    before(async () => {
+      h = await startMarketHarness();
+    });
+
+    it("should not delegate unless the bettor opts in", async () => {
+      const market = await createResolvableMarket(h, await createCollateralMint(h));
+      const bettor = await createBettor(h, market.collateralMint, new anchor.BN(1_000_000));
+      await harnessBuyBet(h, market, bettor, new anchor.BN(1_000_000));
+      for (const mint of [market.ct1Mint, market.ct2Mint]) {
+        expect(await hasDelegate(ata(mint, bettor.publicKey))).to.be.false;
+      }
+    });
+
+    it("should redeem the winning side for delegated holders", async () => {
+      const market = await createResolvableMarket(h, await createCollateralMint(h));
+      const holders = [];
+      for (const amount of [1_000_000, 400_000]) {
+        const bettor = await createBettor(h, market.collateralMint, new anchor.BN(amount));
+        await harnessBuyBet(h, market, bettor, new anchor.BN(amount), true);
+        holders.push(bettor.publicKey);
+      }
+      await resolveWithPrice(h, market, new anchor.BN(101));
+
+      await harnessClaimFor(h, market, market.ct1Mint, holders);
+      expect(await tokenBalance(h, ata(market.collateralMint, holders[0]))).to.equal(1_000_000n);
+      expect(await tokenBalance(h, ata(market.collateralMint, holders[1]))).to.equal(400_000n);
+      for (const holder of holders) {
+        expect(await tokenBalance(h, ata(market.ct1Mint, holder))).to.equal(0n);
+      }
+      expect(await tokenBalance(h, market.vault)).to.equal(0n);
+    });
+
+    it("should skip holders who did not delegate, revoked, hold the losing side or have no collateral account", async () => {
+      const market = await createResolvableMarket(h, await createCollateralMint(h));
+      const undelegated = await createBettor(h, market.collateralMint, new anchor.BN(1_000_000));
+      await harnessBuyBet(h, market, undelegated, new anchor.BN(1_000_000));
+      const revoked = await createBettor(h, market.collateralMint, new anchor.BN(1_000_000));
+      await harnessBuyBet(h, market, revoked, new anchor.BN(1_000_000), true);
+      await sendInstructions(
+        h,
+        [createRevokeInstruction(ata(market.ct1Mint, revoked.publicKey), revoked.publicKey, [], TOKEN_2022_PROGRAM_ID)],
+        [revoked]
+      );
+      // the whole balance went into the bet, so the empty collateral ata can be closed
+      const noAta = await createBettor(h, market.collateralMint, new anchor.BN(1_000_000));
+      await harnessBuyBet(h, market, noAta, new anchor.BN(1_000_000), true);
+      await sendInstructions(
+        h,
+        [createCloseAccountInstruction(
+          ata(market.collateralMint, noAta.publicKey), noAta.publicKey, noAta.publicKey, [], TOKEN_2022_PROGRAM_ID
+        )],
+        [noAta]
+      );
+      const claimed = await createBettor(h, market.collateralMint, new anchor.BN(1_000_000));
+      await harnessBuyBet(h, market, claimed, new anchor.BN(1_000_000), true);
+      await resolveWithPrice(h, market, new anchor.BN(101));
+
+      const holders = [undelegated, revoked, noAta, claimed].map((bettor) => bettor.publicKey);
+      await harnessClaimFor(h, market, market.ct1Mint, holders);
+      for (const holder of holders.slice(0, 3)) {
+        expect(await tokenBalance(h, ata(market.ct1Mint, holder))).to.equal(1_000_000n);
+      }
+      expect(await h.context.banksClient.getAccount(ata(market.collateralMint, noAta.publicKey))).to.be.null;
+      expect(await tokenBalance(h, ata(market.collateralMint, claimed.publicKey))).to.equal(1_000_000n);
+
+      // the losing side pays nothing and stays untouched
+      await harnessClaimFor(h, market, market.ct2Mint, [claimed.publicKey]);
+      expect(await tokenBalance(h, ata(market.ct2Mint, claimed.publicKey))).to.equal(1_000_000n);
+      expect(await tokenBalance(h, market.vault)).to.equal(3_000_000n);
+    });
+  });
+});
@@ -0,0 +1,151 @@
+import * as anchor from "@coral-xyz/anchor";
+import { expect } from "chai";
+import {
+  createMint,
+  getMint,
+  getMintCloseAuthority,
+  getTransferFeeConfig,
+  unpackMint,
+  TOKEN_2022_PROGRAM_ID,
+} from "@solana/spl-token";
+import { MarketProgram } from "../target/types/market_program";
+import {
+  setupBuyBetTest,
+  getAuthAddress,
+  getCollateralInfoAddress,
+} from "./utils";
+import {
+  MarketHarness,
+  startMarketHarness,
+  createCollateralMint,
+  createBettor,
+  createResolvableMarket,
+  harnessBuyBet,
+  harnessGetReward,
+  resolveWithPrice,
+  finalizeMarket,
+  closeResolutionConfig,
+  harnessCloseMarket,
+  harnessCancelMarket,
+} from "./utils/bankrun";
+
+const provider = anchor.AnchorProvider.env();
+anchor.setProvider(provider);
+const program = anchor.workspace.MarketProgram as anchor.Program<MarketProgram>;
+const connection = provider.connection;
+const walletKeypair = (provider.wallet as anchor.Wallet & { payer: anchor.web3.Keypair }).payer;
+
+describe("Close Market Tests", () => {
+  let collateralMint: anchor.web3.PublicKey;
+  let configAddress: anchor.web3.PublicKey;
+  let vaultStateAddress: anchor.web3.PublicKey;
+
+  before(async () => {
+    const sig = await connection.requestAirdrop(provider.wallet.publicKey, anchor.web3.LAMPORTS_PER_SOL * 5);
+    await connection.confirmTransaction(sig, "confirmed");
+
+    collateralMint = await createMint(connection, walletKeypair, walletKeypair.publicKey, null, 6, undefined, undefined, TOKEN_2022_PROGRAM_ID);
+    ({ configAddress, vaultStateAddress } = await setupBuyBetTest(
+      program, connection, walletKeypair, collateralMint, TOKEN_2022_PROGRAM_ID,
+      { name: "Closable", description: "Closable", expiration: new anchor.BN(Math.floor(Date.now() / 1000) + 86400) }
+    ));
+  });
+
+  it("should give the market authority the close authority of both mints", async () => {
+    const [authority] = await getAuthAddress(vaultStateAddress, program.programId);
+    const marketConfig = await program.account.marketConfig.fetch(configAddress);
+    for (const mintAddress of [marketConfig.ct1Mint, marketConfig.ct2Mint]) {
+      const mint = await getMint(connection, mintAddress, "confirmed", TOKEN_2022_PROGRAM_ID);
+      expect(getMintCloseAuthority(mint).closeAuthority.toString()).to.equal(authority.toString());
+    }
+  });
+
+  it("should fail to close a market that is still trading", async () => {
+    const marketConfig = await program.account.marketConfig.fetch(configAddress);
+    try {
+      await program.methods
+        .closeMarket()
+        .accountsPartial({
+          owner: walletKeypair.publicKey,
+          marketConfig: configAddress,
+          vaultState: vaultStateAddress,
+          authority: (await getAuthAddress(vaultStateAddress, program.programId))[0],
+          vault: marketConfig.vault,
+          ct1Mint: marketConfig.ct1Mint,
+          ct2Mint: marketConfig.ct2Mint,
+          collateralMint,
+          collateralInfo: (await getCollateralInfoAddress(collateralMint, program.programId))[0],
+          treasury: null,
+          tokenProgram: TOKEN_2022_PROGRAM_ID,
+          collateralTokenProgram: TOKEN_2022_PROGRAM_ID,
+        })
+        .rpc();
+      expect.fail("Should have failed before finalization");
+    } catch (err) {
+      expect(err.toString()).to.include("InvalidMarketStatus");
+    }
+    expect(await connection.getAccountInfo(configAddress)).to.not.be.null;
+  });
+
+  describe("after finalization", () => {
+    let h: MarketHarness;
+
+    before(async () => {
+      h = await startMarketHarness();
+    });
+
+    it("should close a finalized market once its resolution config is closed", async () => {
+      const market = await createResolvableMarket(h, await createCollateralMint(h));
+      const bettor = await createBettor(h, market.collateralMint, new anchor.BN(1_000_000));
+      await harnessBuyBet(h, market, bettor, new anchor.BN(1_000_000));
+      await resolveWithPrice(h, market, new anchor.BN(101));
+      await harnessGetReward(h, market, bettor, market.ct1Mint, new anchor.BN(1_000_000));
+      await finalizeMarket(h, market);
+
+      try {
+        await harnessCloseMarket(h, market);
+        expect.fail("Should not orphan the resolution config");
+      } catch (err) {
+        expect(err.toString()).to.include("ResolutionConfigOpen");
+      }
+
+      await closeResolutionConfig(h, market);
+      await harnessCloseMarket(h, market);
+      for (const closed of [market.configAddress, market.vaultStateAddress, market.vault, market.resolutionConfig, market.ct1Mint]) {
+        expect(await h.context.banksClient.getAccount(closed)).to.be.null;
+      }
+      // nobody burned the losing tokens, so their mint stays open
+      expect(await h.context.banksClient.getAccount(market.ct2Mint)).to.not.be.null;
+      const page = await h.program.account.marketRegistryPage.fetch(market.registryPage);
+      expect(page.entries.find((entry) => entry.marketConfig.equals(market.configAddress)).closed).to.be.true;
+    });
+
+    it("should harvest the transfer fees withheld in the vault before closing it", async () => {
+      // 1% fee, the vault withholds the fee of the bet and the bettor's account that of the payout
+      const market = await createResolvableMarket(h, await createCollateralMint(h, 6, 100));
+      const bettor = await createBettor(h, market.collateralMint, new anchor.BN(1_000_000));
+      await harnessBuyBet(h, market, bettor, new anchor.BN(1_000_000));
+      await resolveWithPrice(h, market, new anchor.BN(101));
+      await harnessGetReward(h, market, bettor, market.ct1Mint, new anchor.BN(990_000));
+      await finalizeMarket(h, market);
+      await closeResolutionConfig(h, market);
+
+      await harnessCloseMarket(h, market);
+      expect(await h.context.banksClient.getAccount(market.vault)).to.be.null;
+      const info = await h.context.banksClient.getAccount(market.collateralMint);
+      const mint = unpackMint(market.collateralMint, { ...info, data: Buffer.from(info.data) }, TOKEN_2022_PROGRAM_ID);
+      expect(getTransferFeeConfig(mint).withheldAmount).to.equal(10_000n);
+    });
+
+    it("should close a cancelled market with both of its mints", async () => {
+      const market = await createResolvableMarket(h, await createCollateralMint(h), { opensIn: 3600 });
+      await harnessCancelMarket(h, market);
+      await closeResolutionConfig(h, market);
+
+      await harnessCloseMarket(h, market);
+      for (const closed of [market.configAddress, market.vaultStateAddress, market.vault, market.ct1Mint, market.ct2Mint]) {
+        expect(await h.context.banksClient.getAccount(closed)).to.be.null;
+      }
+    });
+  });
+});
@@ -0,0 +1,126 @@
+import * as anchor from "@coral-xyz/anchor";
+import { expect } from "chai";
+import {
+  createMint,
+  getAssociatedTokenAddressSync,
+  getOrCreateAssociatedTokenAccount,
+  mintTo,
+  TOKEN_2022_PROGRAM_ID,
+} from "@solana/spl-token";
+import { MarketProgram } from "../target/types/market_program";
+import { setupBuyBetTest, buyBet, closePosition, getVaultAddress } from "./utils";
+import {
+  MarketHarness,
+  startMarketHarness,
+  createCollateralMint,
+  createBettor,
+  createResolvableMarket,
+  harnessBuyBet,
+  harnessGetReward,
+  harnessClosePosition,
+  resolveWithPrice,
+  tokenBalance,
+} from "./utils/bankrun";
+
+const provider = anchor.AnchorProvider.env();
+anchor.setProvider(provider);
+const program = anchor.workspace.MarketProgram as anchor.Program<MarketProgram>;
+const connection = provider.connection;
+const walletKeypair = (provider.wallet as anchor.Wallet & { payer: anchor.web3.Keypair }).payer;
+
+describe("Close Position Tests", () => {
+  let configAddress: anchor.web3.PublicKey;
+  let ct1Account: anchor.web3.PublicKey;
+  let ct2Account: anchor.web3.PublicKey;
+
+  before(async () => {
+    const sig = await connection.requestAirdrop(provider.wallet.publicKey, anchor.web3.LAMPORTS_PER_SOL * 5);
+    await connection.confirmTransaction(sig, "confirmed");
+
+    const collateralMint = await createMint(connection, walletKeypair, walletKeypair.publicKey, null, 6, undefined, undefined, TOKEN_2022_PROGRAM_ID);
+    const collateralAccount = (await getOrCreateAssociatedTokenAccount(
+      connection, walletKeypair, collateralMint, walletKeypair.publicKey, false, "confirmed", undefined, TOKEN_2022_PROGRAM_ID
+    )).address;
+    await mintTo(connection, walletKeypair, collateralMint, collateralAccount, walletKeypair, 1_000_000, [], undefined, TOKEN_2022_PROGRAM_ID);
+
+    let vaultStateAddress: anchor.web3.PublicKey;
+    ({ configAddress, vaultStateAddress } = await setupBuyBetTest(
+      program, connection, walletKeypair, collateralMint, TOKEN_2022_PROGRAM_ID,
+      { name: "Positions", description: "Positions", expiration: new anchor.BN(Math.floor(Date.now() / 1000) + 86400) }
+    ));
+    const marketConfig = await program.account.marketConfig.fetch(configAddress);
+    const [vault] = await getVaultAddress(vaultStateAddress, program.programId);
+    await buyBet(
+      program, walletKeypair, new anchor.BN(500_000), configAddress, collateralMint, TOKEN_2022_PROGRAM_ID,
+      vaultStateAddress, vault, marketConfig.ct1Mint, marketConfig.ct2Mint
+    );
+    ct1Account = getAssociatedTokenAddressSync(marketConfig.ct1Mint, walletKeypair.publicKey, false, TOKEN_2022_PROGRAM_ID);
+    ct2Account = getAssociatedTokenAddressSync(marketConfig.ct2Mint, walletKeypair.publicKey, false, TOKEN_2022_PROGRAM_ID);
+  });
+
+  it("should fail to close a position before resolution", async () => {
+    try {
+      await closePosition(program, walletKeypair, configAddress, { ct1Account, ct2Account });
+      expect.fail("Should have failed before resolution");
+    } catch (err) {
+      expect(err.toString()).to.include("MarketNotResolved");
+    }
+    expect(await connection.getAccountInfo(ct1Account)).to.not.be.null;
+    expect(await connection.getAccountInfo(ct2Account)).to.not.be.null;
+  });
+
+  describe("after resolution", () => {
+    let h: MarketHarness;
+
+    before(async () => {
+      h = await startMarketHarness();
+    });
+
+    it("should burn the losing side and close both accounts once the winnings are redeemed", async () => {
+      const market = await createResolvableMarket(h, await createCollateralMint(h));
+      const bettor = await createBettor(h, market.collateralMint, new anchor.BN(1_000_000));
+      await harnessBuyBet(h, market, bettor, new anchor.BN(1_000_000));
+      await resolveWithPrice(h, market, new anchor.BN(101));
+      const [ct1Account, ct2Account] = [market.ct1Mint, market.ct2Mint].map((mint) =>
+        getAssociatedTokenAddressSync(mint, bettor.publicKey, false, TOKEN_2022_PROGRAM_ID)
+      );
+
+      try {
+        await harnessClosePosition(h, market, bettor);
+        expect.fail("Should not burn unredeemed winnings");
+      } catch (err) {
+        expect(err.toString()).to.include("PositionNotEmpty");
+      }
+
+      await harnessGetReward(h, market, bettor, market.ct1Mint, new anchor.BN(1_000_000));
+      const lamportsBefore = (await h.context.banksClient.getAccount(bettor.publicKey)).lamports;
+      await harnessClosePosition(h, market, bettor);
+
+      for (const account of [ct1Account, ct2Account]) {
+        expect(await h.context.banksClient.getAccount(account)).to.be.null;
+      }
+      // the losing side was burned, so both supplies are gone
+      for (const mint of [market.ct1Mint, market.ct2Mint]) {
+        const info = await h.context.banksClient.getAccount(mint);
+        expect(Buffer.from(info.data).readBigUInt64LE(36)).to.equal(0n);
+      }
+      expect((await h.context.banksClient.getAccount(bettor.publicKey)).lamports).to.be.greaterThan(lamportsBefore);
+      expect(await tokenBalance(h, getAssociatedTokenAddressSync(market.collateralMint, bettor.publicKey, false, TOKEN_2022_PROGRAM_ID))).to.equal(1_000_000n);
+    });
+
+    it("should close only the losing side while the winnings are unredeemed", async () => {
+      const market = await createResolvableMarket(h, await createCollateralMint(h));
+      const bettor = await createBettor(h, market.collateralMint, new anchor.BN(1_000_000));
+      await harnessBuyBet(h, market, bettor, new anchor.BN(1_000_000));
+      await resolveWithPrice(h, market, new anchor.BN(99));
+
+      await harnessClosePosition(h, market, bettor, { ct1: true, ct2: false });
+      expect(await h.context.banksClient.getAccount(
+        getAssociatedTokenAddressSync(market.ct1Mint, bettor.publicKey, false, TOKEN_2022_PROGRAM_ID)
+      )).to.be.null;
+      expect(await tokenBalance(
+        h, getAssociatedTokenAddressSync(market.ct2Mint, bettor.publicKey, false, TOKEN_2022_PROGRAM_ID)
+      )).to.equal(1_000_000n);
+    });
+  });
+});
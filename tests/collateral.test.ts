@@ -0,0 +1,210 @@
+import * as anchor from "@coral-xyz/anchor";
+import { expect } from "chai";
+import {
+  createMint,
+  TOKEN_2022_PROGRAM_ID,
+  getOrCreateAssociatedTokenAccount,
+  mintTo,
+} from "@solana/spl-token";
+import { MarketProgram } from "../target/types/market_program";
+import {
+  createMarketConfig,
+  initialize,
+  buyBet,
+  setCollateralInfo,
+  defaultCollateralParams,
+  getAuthAddress,
+  getVaultStateAddress,
+  getVaultAddress,
+  getct1MintAddress,
+  getct2MintAddress,
+  getCollateralInfoAddress,
+} from "./utils";
+
+const provider = anchor.AnchorProvider.env();
+anchor.setProvider(provider);
+const program = anchor.workspace.MarketProgram as anchor.Program<MarketProgram>;
+const connection = provider.connection;
+const walletKeypair = (provider.wallet as anchor.Wallet & { payer: anchor.web3.Keypair }).payer;
+
+const newCollateralMint = (freezeAuthority: anchor.web3.PublicKey | null = null) =>
+  createMint(
+    connection,
+    walletKeypair,
+    walletKeypair.publicKey,
+    freezeAuthority,
+    6,
+    undefined,
+    undefined,
+    TOKEN_2022_PROGRAM_ID
+  );
+
+const newMarket = () =>
+  createMarketConfig(
+    program,
+    connection,
+    walletKeypair,
+    "Collateral",
+    "Collateral",
+    new anchor.BN(Math.floor(Date.now() / 1000) + 86400)
+  );
+
+// initialize without listing the collateral first
+const initializeUnlisted = async (configAddress: anchor.web3.PublicKey, collateralMint: anchor.web3.PublicKey) => {
+  const [vaultState] = await getVaultStateAddress(configAddress, collateralMint, program.programId);
+  const [authority] = await getAuthAddress(vaultState, program.programId);
+  await program.methods
+    .initialize()
+    .accountsPartial({
+      creator: walletKeypair.publicKey,
+      marketConfig: configAddress,
+      authority,
+      ct1Mint: (await getct1MintAddress(vaultState, program.programId))[0],
+      ct2Mint: (await getct2MintAddress(vaultState, program.programId))[0],
+      ct1TokenProgram: TOKEN_2022_PROGRAM_ID,
+      ct2TokenProgram: TOKEN_2022_PROGRAM_ID,
+      vaultState,
+      vault: (await getVaultAddress(vaultState, program.programId))[0],
+      collateralMint,
+      collateralInfo: (await getCollateralInfoAddress(collateralMint, program.programId))[0],
+      collateralTokenProgram: TOKEN_2022_PROGRAM_ID,
+      tokenProgram: TOKEN_2022_PROGRAM_ID,
+      systemProgram: anchor.web3.SystemProgram.programId,
+    })
+    .rpc();
+};
+
+describe("Collateral Allowlist Tests", () => {
+  before(async () => {
+    const sig = await connection.requestAirdrop(
+      provider.wallet.publicKey,
+      anchor.web3.LAMPORTS_PER_SOL * 5
+    );
+    await connection.confirmTransaction(sig, "confirmed");
+  });
+
+  it("should reject an unlisted collateral mint", async () => {
+    const collateralMint = await newCollateralMint();
+    try {
+      await initializeUnlisted(await newMarket(), collateralMint);
+      expect.fail("Should have failed with an unlisted collateral mint");
+    } catch (err) {
+      expect(err.toString()).to.include("NotSupportMint");
+    }
+  });
+
+  it("should reject a disabled collateral mint", async () => {
+    const collateralMint = await newCollateralMint();
+    await setCollateralInfo(program, walletKeypair, collateralMint, {
+      ...defaultCollateralParams(),
+      enabled: false,
+    });
+    try {
+      await initializeUnlisted(await newMarket(), collateralMint);
+      expect.fail("Should have failed with a disabled collateral mint");
+    } catch (err) {
+      expect(err.toString()).to.include("NotSupportMint");
+    }
+  });
+
+  it("should reject a collateral mint with a freeze authority unless allowed", async () => {
+    const collateralMint = await newCollateralMint(walletKeypair.publicKey);
+    await setCollateralInfo(program, walletKeypair, collateralMint);
+    try {
+      await initializeUnlisted(await newMarket(), collateralMint);
+      expect.fail("Should have failed with a freeze authority");
+    } catch (err) {
+      expect(err.toString()).to.include("NotSupportMint");
+    }
+
+    await setCollateralInfo(program, walletKeypair, collateralMint, {
+      ...defaultCollateralParams(),
+      allowFreezeAuthority: true,
+    });
+    await initializeUnlisted(await newMarket(), collateralMint);
+  });
+
+  it("should only let the upgrade authority list collateral", async () => {
+    const stranger = anchor.web3.Keypair.generate();
+    const sig = await connection.requestAirdrop(stranger.publicKey, anchor.web3.LAMPORTS_PER_SOL);
+    await connection.confirmTransaction(sig, "confirmed");
+    const collateralMint = await newCollateralMint();
+    try {
+      await setCollateralInfo(program, stranger, collateralMint);
+      expect.fail("Should have failed with a non-admin signer");
+    } catch (err) {
+      expect(err).to.exist;
+    }
+  });
+
+  it("should enforce the minimum bet and the open interest cap", async () => {
+    const collateralMint = await newCollateralMint();
+    await setCollateralInfo(program, walletKeypair, collateralMint, {
+      ...defaultCollateralParams(),
+      minBet: new anchor.BN(1_000_000),
+      maxOpenInterest: new anchor.BN(5_000_000),
+    });
+    const configAddress = await newMarket();
+    const { vaultStateAddress } = await initialize(
+      program,
+      walletKeypair,
+      configAddress,
+      collateralMint,
+      TOKEN_2022_PROGRAM_ID
+    );
+    const [vaultAddress] = await getVaultAddress(vaultStateAddress, program.programId);
+    const [ct1Mint] = await getct1MintAddress(vaultStateAddress, program.programId);
+    const [ct2Mint] = await getct2MintAddress(vaultStateAddress, program.programId);
+
+    const userCollateralAccount = await getOrCreateAssociatedTokenAccount(
+      connection,
+      walletKeypair,
+      collateralMint,
+      walletKeypair.publicKey,
+      false,
+      "confirmed",
+      undefined,
+      TOKEN_2022_PROGRAM_ID
+    );
+    await mintTo(
+      connection,
+      walletKeypair,
+      collateralMint,
+      userCollateralAccount.address,
+      walletKeypair,
+      BigInt(10_000_000),
+      [],
+      undefined,
+      TOKEN_2022_PROGRAM_ID
+    );
+    const bet = (amount: number) =>
+      buyBet(
+        program,
+        walletKeypair,
+        new anchor.BN(amount),
+        configAddress,
+        collateralMint,
+        TOKEN_2022_PROGRAM_ID,
+        vaultStateAddress,
+        vaultAddress,
+        ct1Mint,
+        ct2Mint
+      );
+
+    try {
+      await bet(999_999);
+      expect.fail("Should have failed below the minimum bet");
+    } catch (err) {
+      expect(err.toString()).to.include("BetTooSmall");
+    }
+
+    await bet(4_000_000);
+    try {
+      await bet(1_000_001);
+      expect.fail("Should have failed above the open interest cap");
+    } catch (err) {
+      expect(err.toString()).to.include("OpenInterestExceeded");
+    }
+    await bet(1_000_000);
+  });
+});
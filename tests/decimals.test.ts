@@ -0,0 +1,144 @@
+import * as anchor from "@coral-xyz/anchor";
+import { expect } from "chai";
+import {
+  createMint,
+  TOKEN_2022_PROGRAM_ID,
+  getOrCreateAssociatedTokenAccount,
+  getMint,
+  mintTo,
+  getAccount,
+} from "@solana/spl-token";
+import { MarketProgram } from "../target/types/market_program";
+import {
+  setupBuyBetTest,
+  buyBet,
+  sellBet,
+  getVaultAddress,
+  getct1MintAddress,
+  getct2MintAddress,
+  getUserCtAccountInfo,
+  isEqual
+} from "./utils";
+
+const provider = anchor.AnchorProvider.env();
+anchor.setProvider(provider);
+const program = anchor.workspace.MarketProgram as anchor.Program<MarketProgram>;
+const connection = provider.connection;
+const walletKeypair = (provider.wallet as anchor.Wallet & { payer: anchor.web3.Keypair }).payer;
+
+describe("Conditional Token Decimals Tests", () => {
+  before(async () => {
+    const sig = await connection.requestAirdrop(
+      provider.wallet.publicKey,
+      anchor.web3.LAMPORTS_PER_SOL * 5
+    );
+    await connection.confirmTransaction(sig, "confirmed");
+  });
+
+  for (const decimals of [0, 6, 9]) {
+    it(`should match conditional tokens to a collateral mint with ${decimals} decimals`, async () => {
+      const collateralMint = await createMint(
+        connection,
+        walletKeypair,
+        walletKeypair.publicKey,
+        null,
+        decimals,
+        undefined,
+        undefined,
+        TOKEN_2022_PROGRAM_ID
+      );
+      const expiration = new anchor.BN(Math.floor(Date.now() / 1000) + 86400);
+      const { configAddress, vaultStateAddress } = await setupBuyBetTest(
+        program,
+        connection,
+        walletKeypair,
+        collateralMint,
+        TOKEN_2022_PROGRAM_ID,
+        {
+          name: `Decimals ${decimals}`,
+          description: `Collateral with ${decimals} decimals`,
+          expiration
+        }
+      );
+
+      const [ct1MintAddress] = await getct1MintAddress(vaultStateAddress, program.programId);
+      const [ct2MintAddress] = await getct2MintAddress(vaultStateAddress, program.programId);
+      const [vaultAddress] = await getVaultAddress(vaultStateAddress, program.programId);
+
+      const ct1Mint = await getMint(connection, ct1MintAddress, "confirmed", TOKEN_2022_PROGRAM_ID);
+      const ct2Mint = await getMint(connection, ct2MintAddress, "confirmed", TOKEN_2022_PROGRAM_ID);
+      expect(ct1Mint.decimals).to.equal(decimals);
+      expect(ct2Mint.decimals).to.equal(decimals);
+
+      const userCollateralAccount = await getOrCreateAssociatedTokenAccount(
+        connection,
+        walletKeypair,
+        collateralMint,
+        walletKeypair.publicKey,
+        false,
+        "confirmed",
+        { skipPreflight: true },
+        TOKEN_2022_PROGRAM_ID
+      );
+      // 10 whole units of collateral
+      const oneUnit = BigInt(10) ** BigInt(decimals);
+      const deposit = BigInt(10) * oneUnit;
+      await mintTo(
+        connection,
+        walletKeypair,
+        collateralMint,
+        userCollateralAccount.address,
+        walletKeypair,
+        deposit,
+        [],
+        { skipPreflight: true },
+        TOKEN_2022_PROGRAM_ID
+      );
+
+      await buyBet(
+        program,
+        walletKeypair,
+        new anchor.BN(deposit.toString()),
+        configAddress,
+        collateralMint,
+        TOKEN_2022_PROGRAM_ID,
+        vaultStateAddress,
+        vaultAddress,
+        ct1MintAddress,
+        ct2MintAddress
+      );
+
+      // 10 collateral shows up as 10 YES and 10 NO
+      const { ct1Amount, ct2Amount } = await getUserCtAccountInfo(
+        connection,
+        walletKeypair.publicKey,
+        ct1MintAddress,
+        ct2MintAddress
+      );
+      expect(isEqual(ct1Amount, deposit)).to.be.true;
+      expect(isEqual(ct2Amount, deposit)).to.be.true;
+
+      await sellBet(
+        program,
+        walletKeypair,
+        new anchor.BN(oneUnit.toString()),
+        configAddress,
+        collateralMint,
+        TOKEN_2022_PROGRAM_ID,
+        vaultStateAddress,
+        vaultAddress,
+        ct1MintAddress,
+        ct2MintAddress
+      );
+
+      // selling 1 YES + 1 NO returns exactly 1 collateral
+      const collateralAccount = await getAccount(
+        connection,
+        userCollateralAccount.address,
+        "confirmed",
+        TOKEN_2022_PROGRAM_ID
+      );
+      expect(isEqual(collateralAccount.amount, oneUnit)).to.be.true;
+    });
+  }
+});
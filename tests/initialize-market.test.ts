@@ -1,13 +1,19 @@
 import * as anchor from "@coral-xyz/anchor";
 import { expect } from "chai";
-import { createMint, TOKEN_2022_PROGRAM_ID } from "@solana/spl-token";
+import { createMint, getAccount, getMint, getTokenMetadata, TOKEN_2022_PROGRAM_ID } from "@solana/spl-token";
 import { MarketProgram } from "../target/types/market_program";
 import { 
   setupInitializeMarketTest,
   createMarketConfig,
   initialize,
-  getMarketConfigAddress,
+  getIndexedMarketConfigAddress,
+  getMarketCounterAddress,
+  listMarkets,
+  emptyMetadata,
+  getMarketRegistryPageAddress,
+  REGISTRY_PAGE_CAPACITY,
   getAuthAddress,
+  getLegacyAuthAddress,
   getVaultStateAddress,
   getVaultAddress,
   getct1MintAddress,
@@ -58,26 +64,31 @@ describe("Initialize Market Tests", () => {
       const description = `Test Description ${index}`;
       const expiration = new anchor.BN(Math.floor(Date.now() / 1000) + 86400);
 
-      const [marketConfigAddress] = await getMarketConfigAddress(index, program.programId);
+      const [counterAddress] = await getMarketCounterAddress(program.programId);
+      const counter = await program.account.marketCounter.fetchNullable(counterAddress);
+      const expectedIndex = counter ? counter.count.toNumber() : 0;
+      const [marketConfigAddress] = await getIndexedMarketConfigAddress(expectedIndex, program.programId);
 
-      await createMarketConfig(
+      const configAddress = await createMarketConfig(
         program,
         connection,
         walletKeypair,
-        index,
         name,
         description,
         expiration
       );
 
+      expect(configAddress.toString()).to.equal(marketConfigAddress.toString());
       const marketConfig = await program.account.marketConfig.fetch(marketConfigAddress);
       
-      expect(marketConfig.index).to.equal(index);
+      expect(marketConfig.marketIndex.toNumber()).to.equal(expectedIndex);
       expect(marketConfig.owner.toString()).to.equal(walletKeypair.publicKey.toString());
       expect(marketConfig.name).to.equal(name);
       expect(marketConfig.description).to.equal(description);
-      expect(marketConfig.expiration.toNumber()).to.equal(expiration.toNumber());
-      expect(marketConfig.marketResolution).to.equal(false);
+      expect(marketConfig.tradingClosesAt.toNumber()).to.equal(expiration.toNumber());
+      expect(marketConfig.resolvesAt.toNumber()).to.equal(expiration.toNumber());
+      expect(marketConfig.haltBuffer.toNumber()).to.equal(0);
+      expect(marketConfig.status).to.deep.equal({ created: {} });
       expect(marketConfig.vaultState.toString()).to.equal(anchor.web3.PublicKey.default.toString());
       expect(marketConfig.vault.toString()).to.equal(anchor.web3.PublicKey.default.toString());
     });
@@ -89,7 +100,6 @@ describe("Initialize Market Tests", () => {
       const expiration = new anchor.BN(Math.floor(Date.now() / 1000) + 86400);
 
       const config = {
-        index,
         name,
         description,
         expiration
@@ -136,7 +146,7 @@ describe("Initialize Market Tests", () => {
       expect(marketConfig.vault.toString()).to.equal(expectedVaultAddress.toString());
       expect(marketConfig.ct1Mint.toString()).to.equal(expectedCt1MintAddress.toString());
       expect(marketConfig.ct2Mint.toString()).to.equal(expectedCt2MintAddress.toString());
-      expect(marketConfig.marketResolution).to.equal(false);
+      expect(marketConfig.status).to.deep.equal({ trading: {} });
 
       const vaultStateAccount = await program.account.vaultState.fetch(vaultStateAddress);
       expect(vaultStateAccount.marketConfig.toString()).to.equal(configAddress.toString());
@@ -145,8 +155,18 @@ describe("Initialize Market Tests", () => {
       expect(vaultStateAccount.ctf2Mint.toString()).to.equal(expectedCt2MintAddress.toString());
       expect(vaultStateAccount.ctf1TokenProgram.toString()).to.equal(TOKEN_2022_PROGRAM_ID.toString());
       expect(vaultStateAccount.ctf2TokenProgram.toString()).to.equal(TOKEN_2022_PROGRAM_ID.toString());
-      expect(vaultStateAccount.resolution).to.equal(0);
+      expect(vaultStateAccount.ct1PayoutBps).to.equal(0);
+      expect(vaultStateAccount.ct2PayoutBps).to.equal(0);
       expect(vaultStateAccount.vaultCollateralBalance.toNumber()).to.equal(0);
+
+      const [authority] = await getAuthAddress(vaultStateAddress, program.programId);
+      const ct1Metadata = await getTokenMetadata(connection, expectedCt1MintAddress, "confirmed", TOKEN_2022_PROGRAM_ID);
+      const ct2Metadata = await getTokenMetadata(connection, expectedCt2MintAddress, "confirmed", TOKEN_2022_PROGRAM_ID);
+      expect(ct1Metadata.name).to.equal(`YES – ${name}`);
+      expect(ct1Metadata.symbol).to.equal(`YES-${marketConfig.marketIndex.toString()}`);
+      expect(ct2Metadata.name).to.equal(`NO – ${name}`);
+      expect(ct2Metadata.symbol).to.equal(`NO-${marketConfig.marketIndex.toString()}`);
+      expect(ct1Metadata.updateAuthority.toString()).to.equal(authority.toString());
     });
   });
 
@@ -169,18 +189,17 @@ describe("Initialize Market Tests", () => {
         program,
         connection,
         walletKeypair,
-        index,
         name,
         description,
         expiration
       );
 
-      const [authority] = await getAuthAddress(program.programId);
       const [vaultStateAddress] = await getVaultStateAddress(
         configAddress,
         collateralMint,
         program.programId
       );
+      const [authority] = await getAuthAddress(vaultStateAddress, program.programId);
       const [vaultAddress] = await getVaultAddress(
         vaultStateAddress,
         program.programId
@@ -231,14 +250,13 @@ describe("Initialize Market Tests", () => {
         program,
         connection,
         walletKeypair,
-        index,
         name,
         description,
         expiration
       );
 
-      const [authority] = await getAuthAddress(program.programId);
       const wrongVaultState = anchor.web3.Keypair.generate().publicKey;
+      const [authority] = await getAuthAddress(wrongVaultState, program.programId);
       const [vaultAddress] = await getVaultAddress(
         wrongVaultState,
         program.programId
@@ -282,4 +300,252 @@ describe("Initialize Market Tests", () => {
       }
     });
   });
+
+  describe("Test 1c: Market creation cannot overwrite an existing market", () => {
+    it("should give every new market the next counter index", async () => {
+      const expiration = new anchor.BN(Math.floor(Date.now() / 1000) + 86400);
+      const first = await createMarketConfig(program, connection, walletKeypair, "First", "First", expiration);
+      const second = await createMarketConfig(program, connection, walletKeypair, "Second", "Second", expiration);
+
+      expect(first.toString()).to.not.equal(second.toString());
+      const firstConfig = await program.account.marketConfig.fetch(first);
+      const secondConfig = await program.account.marketConfig.fetch(second);
+      expect(firstConfig.name).to.equal("First");
+      expect(secondConfig.marketIndex.toNumber()).to.equal(firstConfig.marketIndex.toNumber() + 1);
+      expect(secondConfig.index).to.equal(0);
+    });
+
+    it("should fail when creating a market at an already used index", async () => {
+      const expiration = new anchor.BN(Math.floor(Date.now() / 1000) + 86400);
+      const existing = await createMarketConfig(program, connection, walletKeypair, "Existing", "Existing", expiration);
+      const existingIndex = (await program.account.marketConfig.fetch(existing)).marketIndex.toNumber();
+      const [counterAddress] = await getMarketCounterAddress(program.programId);
+      const [registryPage] = await getMarketRegistryPageAddress(
+        Math.floor(existingIndex / REGISTRY_PAGE_CAPACITY),
+        program.programId
+      );
+
+      try {
+        await program.methods
+          .createMarketConfig("Overwrite", "Overwrite", { other: {} }, emptyMetadata(), {
+            tradingOpensAt: new anchor.BN(0),
+            tradingClosesAt: expiration,
+            resolvesAt: expiration,
+            haltBuffer: new anchor.BN(0),
+            claimWindow: null,
+          })
+          .accountsPartial({
+            signer: walletKeypair.publicKey,
+            marketCounter: counterAddress,
+            marketConfig: existing,
+            marketRegistryPage: registryPage,
+            systemProgram: anchor.web3.SystemProgram.programId,
+          })
+          .rpc();
+        expect.fail("Should have failed with an existing market config");
+      } catch (err) {
+        expect(err).to.exist;
+      }
+      const marketConfig = await program.account.marketConfig.fetch(existing);
+      expect(marketConfig.name).to.equal("Existing");
+    });
+
+    it("should only let the owner update the market metadata", async () => {
+      const expiration = new anchor.BN(Math.floor(Date.now() / 1000) + 86400);
+      const configAddress = await createMarketConfig(
+        program, connection, walletKeypair, "Old name", "Old description", expiration, undefined,
+        { opensAt: new anchor.BN(Math.floor(Date.now() / 1000) + 3600) }
+      );
+      const stranger = anchor.web3.Keypair.generate();
+
+      try {
+        await program.methods
+          .updateMarketMetadata("Hijacked", null, null, null)
+          .accountsPartial({ owner: stranger.publicKey, marketConfig: configAddress })
+          .signers([stranger])
+          .rpc();
+        expect.fail("Should have failed with a non-owner signer");
+      } catch (err) {
+        expect(err).to.exist;
+      }
+
+      await program.methods
+        .updateMarketMetadata("New name", null, null, null)
+        .accountsPartial({ owner: walletKeypair.publicKey, marketConfig: configAddress })
+        .rpc();
+      const marketConfig = await program.account.marketConfig.fetch(configAddress);
+      expect(marketConfig.name).to.equal("New name");
+      expect(marketConfig.description).to.equal("Old description");
+    });
+  });
+
+  describe("Test 1d: Markets are listed in the registry", () => {
+    it("should append every new market to the registry", async () => {
+      const expiration = new anchor.BN(Math.floor(Date.now() / 1000) + 86400);
+      const configAddress = await createMarketConfig(program, connection, walletKeypair, "Listed", "Listed", expiration);
+      const marketConfig = await program.account.marketConfig.fetch(configAddress);
+
+      const entries = await listMarkets(program);
+      const entry = entries[marketConfig.marketIndex.toNumber()];
+      expect(entry.marketConfig.toString()).to.equal(configAddress.toString());
+      expect(entry.status).to.deep.equal({ created: {} });
+      expect(entry.category).to.deep.equal({ other: {} });
+      expect(entry.resolvesAt.toNumber()).to.equal(expiration.toNumber());
+    });
+
+    it("should sync the registry entry with the market status", async () => {
+      const expiration = new anchor.BN(Math.floor(Date.now() / 1000) + 86400);
+      const configAddress = await createMarketConfig(program, connection, walletKeypair, "Synced", "Synced", expiration);
+      await initialize(program, walletKeypair, configAddress, collateralMint, TOKEN_2022_PROGRAM_ID);
+
+      await program.methods
+        .syncRegistryEntry()
+        .accountsPartial({ marketConfig: configAddress })
+        .rpc();
+
+      const marketConfig = await program.account.marketConfig.fetch(configAddress);
+      const entries = await listMarkets(program);
+      expect(entries[marketConfig.marketIndex.toNumber()].status).to.deep.equal({ trading: {} });
+    });
+  });
+
+  describe("Test 1e: Market metadata", () => {
+    const metadata = {
+      metadataUri: "ipfs://bafybeigdyrzt5sfp7udm7hu76uh7y26nf3efuylqabf3oclgtqy55fbzdi",
+      rulesHash: Array.from(Buffer.alloc(32, 7)),
+      tags: ["btc", "price"],
+      imageUri: "ar://market-image",
+      resolutionSource: "Pyth BTC/USD",
+    };
+
+    it("should store the metadata set at creation", async () => {
+      const expiration = new anchor.BN(Math.floor(Date.now() / 1000) + 86400);
+      const configAddress = await createMarketConfig(
+        program, connection, walletKeypair, "With metadata", "With metadata", expiration, undefined, { metadata }
+      );
+      const marketConfig = await program.account.marketConfig.fetch(configAddress);
+      expect(marketConfig.metadata.metadataUri).to.equal(metadata.metadataUri);
+      expect(marketConfig.metadata.rulesHash).to.deep.equal(metadata.rulesHash);
+      expect(marketConfig.metadata.tags).to.deep.equal(metadata.tags);
+      expect(marketConfig.metadata.imageUri).to.equal(metadata.imageUri);
+      expect(marketConfig.metadata.resolutionSource).to.equal(metadata.resolutionSource);
+    });
+
+    it("should allow metadata updates before trading opens", async () => {
+      const expiration = new anchor.BN(Math.floor(Date.now() / 1000) + 86400);
+      const configAddress = await createMarketConfig(
+        program, connection, walletKeypair, "Upcoming", "Upcoming", expiration, undefined,
+        { opensAt: new anchor.BN(Math.floor(Date.now() / 1000) + 3600) }
+      );
+
+      await program.methods
+        .updateMarketMetadata(null, null, { crypto: {} }, metadata)
+        .accountsPartial({ owner: walletKeypair.publicKey, marketConfig: configAddress })
+        .rpc();
+      const marketConfig = await program.account.marketConfig.fetch(configAddress);
+      expect(marketConfig.category).to.deep.equal({ crypto: {} });
+      expect(marketConfig.metadata.rulesHash).to.deep.equal(metadata.rulesHash);
+    });
+
+    it("should fail to update metadata once trading opened", async () => {
+      const expiration = new anchor.BN(Math.floor(Date.now() / 1000) + 86400);
+      const configAddress = await createMarketConfig(program, connection, walletKeypair, "Open", "Open", expiration);
+
+      try {
+        await program.methods
+          .updateMarketMetadata(null, null, null, metadata)
+          .accountsPartial({ owner: walletKeypair.publicKey, marketConfig: configAddress })
+          .rpc();
+        expect.fail("Should have failed once trading opened");
+      } catch (err) {
+        expect(err.toString()).to.include("MetadataFrozen");
+      }
+    });
+  });
+
+  describe("Test 1f: Every market has its own authority", () => {
+    const initializeMarket = async () => {
+      const expiration = new anchor.BN(Math.floor(Date.now() / 1000) + 86400);
+      const configAddress = await createMarketConfig(program, connection, walletKeypair, "Own authority", "Own authority", expiration);
+      const { vaultStateAddress } = await initialize(program, walletKeypair, configAddress, collateralMint, TOKEN_2022_PROGRAM_ID);
+      return { configAddress, vaultStateAddress };
+    };
+
+    it("should give the mints and vault of each market a distinct authority", async () => {
+      const first = await initializeMarket();
+      const second = await initializeMarket();
+      const [firstAuthority, firstBump] = await getAuthAddress(first.vaultStateAddress, program.programId);
+      const [secondAuthority] = await getAuthAddress(second.vaultStateAddress, program.programId);
+      expect(firstAuthority.toString()).to.not.equal(secondAuthority.toString());
+
+      const marketConfig = await program.account.marketConfig.fetch(first.configAddress);
+      const ct1Mint = await getMint(connection, marketConfig.ct1Mint, "confirmed", TOKEN_2022_PROGRAM_ID);
+      const ct2Mint = await getMint(connection, marketConfig.ct2Mint, "confirmed", TOKEN_2022_PROGRAM_ID);
+      const vault = await getAccount(connection, marketConfig.vault, "confirmed", TOKEN_2022_PROGRAM_ID);
+      expect(ct1Mint.mintAuthority.toString()).to.equal(firstAuthority.toString());
+      expect(ct2Mint.mintAuthority.toString()).to.equal(firstAuthority.toString());
+      expect(vault.owner.toString()).to.equal(firstAuthority.toString());
+
+      const vaultState = await program.account.vaultState.fetch(first.vaultStateAddress);
+      expect(vaultState.authBump).to.equal(firstBump);
+    });
+
+    it("should refuse to migrate a market that already has its own authority", async () => {
+      const { configAddress, vaultStateAddress } = await initializeMarket();
+      const marketConfig = await program.account.marketConfig.fetch(configAddress);
+
+      try {
+        await program.methods
+          .migrateMarketAuthority()
+          .accountsPartial({
+            marketConfig: configAddress,
+            vaultState: vaultStateAddress,
+            legacyAuthority: (await getLegacyAuthAddress(program.programId))[0],
+            authority: (await getAuthAddress(vaultStateAddress, program.programId))[0],
+            ct1Mint: marketConfig.ct1Mint,
+            ct2Mint: marketConfig.ct2Mint,
+            vault: marketConfig.vault,
+            tokenProgram: TOKEN_2022_PROGRAM_ID,
+            collateralTokenProgram: TOKEN_2022_PROGRAM_ID,
+            ct1Metadata: null,
+            ct2Metadata: null,
+            metadataProgram: null,
+          })
+          .rpc();
+        expect.fail("Should have failed for a market that was never on the legacy authority");
+      } catch (err) {
+        expect(err.toString()).to.include("AuthorityAlreadyMigrated");
+      }
+    });
+  });
+
+  describe("Test 1g: Vault state versioning", () => {
+    // 8 byte discriminator + v1 fields + version + reserved
+    const VAULT_STATE_LEN = 8 + 285 + 1 + 128;
+
+    it("should create vault states at the current version and size", async () => {
+      const expiration = new anchor.BN(Math.floor(Date.now() / 1000) + 86400);
+      const configAddress = await createMarketConfig(program, connection, walletKeypair, "Versioned", "Versioned", expiration);
+      const { vaultStateAddress } = await initialize(program, walletKeypair, configAddress, collateralMint, TOKEN_2022_PROGRAM_ID);
+
+      const vaultState = await program.account.vaultState.fetch(vaultStateAddress);
+      expect(vaultState.version).to.equal(2);
+      const accountInfo = await connection.getAccountInfo(vaultStateAddress);
+      expect(accountInfo.data.length).to.equal(VAULT_STATE_LEN);
+
+      try {
+        await program.methods
+          .migrateVaultState()
+          .accountsPartial({
+            payer: walletKeypair.publicKey,
+            marketConfig: configAddress,
+            vaultState: vaultStateAddress,
+          })
+          .rpc();
+        expect.fail("Should have failed for an up to date vault state");
+      } catch (err) {
+        expect(err.toString()).to.include("VaultStateUpToDate");
+      }
+    });
+  });
 });
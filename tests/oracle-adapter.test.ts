@@ -0,0 +1,91 @@
+import * as anchor from "@coral-xyz/anchor";
+import { expect } from "chai";
+import { MarketProgram } from "../target/types/market_program";
+import { OracleAdapterContract } from "../target/types/oracle_adapter_contract";
+import { setupInitializeMarketTest, getResolutionConfigAddress } from "./utils";
+
+const provider = anchor.AnchorProvider.env();
+anchor.setProvider(provider);
+const program = anchor.workspace.MarketProgram as anchor.Program<MarketProgram>;
+const adapter = anchor.workspace.OracleAdapterContract as anchor.Program<OracleAdapterContract>;
+const connection = provider.connection;
+const walletKeypair = (provider.wallet as anchor.Wallet & { payer: anchor.web3.Keypair }).payer;
+
+const FEED_ID = Array(32).fill(7);
+const RULE = { comparison: { above: {} }, strike: new anchor.BN(100), upperStrike: new anchor.BN(0), strikeExponent: 0 };
+
+describe("Oracle Adapter Tests", () => {
+  let configAddress: anchor.web3.PublicKey;
+  let relativeConfigAddress: anchor.web3.PublicKey;
+  const stranger = anchor.web3.Keypair.generate();
+
+  before(async () => {
+    for (const key of [provider.wallet.publicKey, stranger.publicKey]) {
+      const sig = await connection.requestAirdrop(key, anchor.web3.LAMPORTS_PER_SOL * 5);
+      await connection.confirmTransaction(sig, "confirmed");
+    }
+    ({ configAddress } = await setupInitializeMarketTest(program, connection, walletKeypair, {
+      name: "Adapter",
+      description: "Adapter",
+      expiration: new anchor.BN(Math.floor(Date.now() / 1000) + 86400),
+    }));
+    ({ configAddress: relativeConfigAddress } = await setupInitializeMarketTest(program, connection, walletKeypair, {
+      name: "Relative",
+      description: "Relative",
+      expiration: new anchor.BN(Math.floor(Date.now() / 1000) + 86400),
+    }));
+  });
+
+  it("should not let anyone but the market owner create the resolution config", async () => {
+    const [resolutionConfig] = await getResolutionConfigAddress(configAddress, adapter.programId);
+    try {
+      await adapter.methods
+        .initialize(FEED_ID, RULE, new anchor.BN(3600))
+        .accountsPartial({ payer: stranger.publicKey, marketConfig: configAddress, resolutionConfig })
+        .signers([stranger])
+        .rpc();
+      expect.fail("Should have rejected a non-owner");
+    } catch (err) {
+      expect(err.toString()).to.include("NotMarketOwner");
+    }
+    expect(await connection.getAccountInfo(resolutionConfig)).to.be.null;
+  });
+
+  it("should let the market owner create the resolution config", async () => {
+    const [resolutionConfig] = await getResolutionConfigAddress(configAddress, adapter.programId);
+    await adapter.methods
+      .initialize(FEED_ID, RULE, new anchor.BN(3600))
+      .accountsPartial({ payer: walletKeypair.publicKey, marketConfig: configAddress, resolutionConfig })
+      .rpc();
+    const config = await adapter.account.resolutionConfig.fetch(resolutionConfig);
+    expect(config.marketConfig.toString()).to.equal(configAddress.toString());
+  });
+
+  it("should not let anyone but the market owner create a relative resolution config", async () => {
+    const [resolutionConfig] = await getResolutionConfigAddress(relativeConfigAddress, adapter.programId);
+    try {
+      await adapter.methods
+        .initializeRelative(FEED_ID, Array(32).fill(8), new anchor.BN(3600))
+        .accountsPartial({ payer: stranger.publicKey, marketConfig: relativeConfigAddress, resolutionConfig })
+        .signers([stranger])
+        .rpc();
+      expect.fail("Should have rejected a non-owner");
+    } catch (err) {
+      expect(err.toString()).to.include("NotMarketOwner");
+    }
+  });
+
+  it("should reject a relative market benchmarked against its own feed", async () => {
+    const [resolutionConfig] = await getResolutionConfigAddress(relativeConfigAddress, adapter.programId);
+    try {
+      await adapter.methods
+        .initializeRelative(FEED_ID, FEED_ID, new anchor.BN(3600))
+        .accountsPartial({ payer: walletKeypair.publicKey, marketConfig: relativeConfigAddress, resolutionConfig })
+        .rpc();
+      expect.fail("Should have rejected identical feeds");
+    } catch (err) {
+      expect(err.toString()).to.include("IdenticalFeeds");
+    }
+    expect(await connection.getAccountInfo(resolutionConfig)).to.be.null;
+  });
+});
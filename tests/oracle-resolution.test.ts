@@ -0,0 +1,262 @@
+import { BN } from "@coral-xyz/anchor";
+import { expect } from "chai";
+import {
+  MarketHarness,
+  HarnessMarket,
+  startMarketHarness,
+  createCollateralMint,
+  createBettor,
+  createResolvableMarket,
+  harnessBuyBet,
+  resolveWithPrice,
+  forceInvalidate,
+  invalidateUnconfiguredMarket,
+  finalizeMarket,
+  now,
+  warpTo,
+  setPriceUpdate,
+  DEFAULT_FEED_ID,
+  adapterResolveAccounts,
+  recordStartPrices,
+  submitBarrierPrice,
+} from "./utils/bankrun";
+import { Keypair, PublicKey } from "@solana/web3.js";
+import { REGISTRY_PAGE_CAPACITY } from "./utils";
+
+describe("Oracle Resolution Tests", () => {
+  let h: MarketHarness;
+  let collateralMint: PublicKey;
+
+  before(async () => {
+    h = await startMarketHarness();
+    collateralMint = await createCollateralMint(h);
+  });
+
+  async function tradedMarket(): Promise<HarnessMarket> {
+    const market = await createResolvableMarket(h, collateralMint);
+    const bettor = await createBettor(h, collateralMint, new BN(1_000_000));
+    await harnessBuyBet(h, market, bettor, new BN(1_000_000));
+    return market;
+  }
+
+  it("should resolve YES when the price ends above the strike", async () => {
+    const market = await tradedMarket();
+    await resolveWithPrice(h, market, new BN(101));
+
+    const config = await h.program.account.marketConfig.fetch(market.configAddress);
+    expect(config.status.resolved).to.not.be.undefined;
+    const vaultState = await h.program.account.vaultState.fetch(market.vaultStateAddress);
+    expect(vaultState.winningCtMint.toString()).to.equal(market.ct1Mint.toString());
+    const record = await h.program.account.resolutionRecord.fetch(market.resolutionRecord);
+    expect(record.price.toNumber()).to.equal(101);
+  });
+
+  it("should keep the registry entry in step with the market status", async () => {
+    const market = await tradedMarket();
+    const config = await h.program.account.marketConfig.fetch(market.configAddress);
+    const entryOf = async () =>
+      (await h.program.account.marketRegistryPage.fetch(market.registryPage)).entries[config.marketIndex.toNumber() % REGISTRY_PAGE_CAPACITY];
+
+    await resolveWithPrice(h, market, new BN(101));
+    expect((await entryOf()).status.resolved).to.not.be.undefined;
+    await finalizeMarket(h, market);
+    expect((await entryOf()).status.finalized).to.not.be.undefined;
+  });
+
+  it("should resolve NO on a tie with an above rule", async () => {
+    const market = await tradedMarket();
+    await resolveWithPrice(h, market, new BN(100));
+
+    const vaultState = await h.program.account.vaultState.fetch(market.vaultStateAddress);
+    expect(vaultState.winningCtMint.toString()).to.equal(market.ct2Mint.toString());
+  });
+
+  it("should not trigger before the market resolves", async () => {
+    const market = await tradedMarket();
+    const priceUpdate = setPriceUpdate(h, Keypair.generate().publicKey, DEFAULT_FEED_ID, new BN(101), 0, await now(h));
+    try {
+      await h.adapter.methods
+        .trigger()
+        .accountsPartial({ ...adapterResolveAccounts(h, market), priceUpdate, benchmarkPriceUpdate: null })
+        .rpc();
+      expect.fail("Should not trigger before resolves_at");
+    } catch (err) {
+      expect(err.toString()).to.include("ResolutionConfigNotExpiredYet");
+    }
+  });
+
+  it("should reject a stale price update", async () => {
+    const market = await tradedMarket();
+    const resolveTime = market.resolvesAt + 1;
+    await warpTo(h, resolveTime);
+    const priceUpdate = setPriceUpdate(h, Keypair.generate().publicKey, DEFAULT_FEED_ID, new BN(101), 0, resolveTime - 120);
+    try {
+      await h.adapter.methods
+        .trigger()
+        .accountsPartial({ ...adapterResolveAccounts(h, market), priceUpdate, benchmarkPriceUpdate: null })
+        .rpc();
+      expect.fail("Should reject a stale price");
+    } catch (err) {
+      expect(err.toString()).to.include("PriceTooOld");
+    }
+  });
+
+  it("should reject a price published after the tolerance window, however late the trigger", async () => {
+    const market = await tradedMarket();
+    // RESOLUTION_PRICE_TOLERANCE of the oracle adapter
+    const publishTime = market.resolvesAt + 61;
+    await warpTo(h, publishTime);
+    const priceUpdate = setPriceUpdate(h, Keypair.generate().publicKey, DEFAULT_FEED_ID, new BN(101), 0, publishTime);
+    try {
+      await h.adapter.methods
+        .trigger()
+        .accountsPartial({ ...adapterResolveAccounts(h, market), priceUpdate, benchmarkPriceUpdate: null })
+        .rpc();
+      expect.fail("Should only resolve on the price at expiration");
+    } catch (err) {
+      expect(err.toString()).to.include("ResolutionPriceOutsideWindow");
+    }
+
+    // the price at expiration still resolves it
+    await resolveWithPrice(h, market, new BN(101));
+    const config = await h.program.account.marketConfig.fetch(market.configAddress);
+    expect(config.status.resolved).to.not.be.undefined;
+  });
+
+  it("should invalidate a market nobody resolved within the grace period", async () => {
+    const market = await tradedMarket();
+    await forceInvalidate(h, market);
+
+    const config = await h.program.account.marketConfig.fetch(market.configAddress);
+    expect(config.status.invalid).to.not.be.undefined;
+  });
+
+  it("should invalidate a market without a resolution config after the fallback grace period", async () => {
+    const market = await createResolvableMarket(h, collateralMint, { unconfigured: true });
+    const bettor = await createBettor(h, collateralMint, new BN(1_000_000));
+    await harnessBuyBet(h, market, bettor, new BN(1_000_000));
+
+    // UNCONFIGURED_MARKET_GRACE_PERIOD of the market program
+    const gracePeriod = 30 * 24 * 3600;
+    await warpTo(h, market.resolvesAt + gracePeriod - 1);
+    try {
+      await invalidateUnconfiguredMarket(h, market);
+      expect.fail("Should wait for the fallback grace period");
+    } catch (err) {
+      expect(err.toString()).to.include("FallbackGracePeriodActive");
+    }
+
+    await warpTo(h, market.resolvesAt + gracePeriod);
+    await invalidateUnconfiguredMarket(h, market);
+    const config = await h.program.account.marketConfig.fetch(market.configAddress);
+    expect(config.status.invalid).to.not.be.undefined;
+    const record = await h.program.account.resolutionRecord.fetch(market.resolutionRecord);
+    expect(record.source.noResolutionConfig).to.not.be.undefined;
+  });
+
+  it("should not invalidate a market through the fallback while it has a resolution config", async () => {
+    const market = await tradedMarket();
+    await warpTo(h, market.resolvesAt + 30 * 24 * 3600);
+    try {
+      await invalidateUnconfiguredMarket(h, market);
+      expect.fail("Should leave configured markets to the oracle adapter");
+    } catch (err) {
+      expect(err.toString()).to.include("ResolutionConfigExists");
+    }
+  });
+
+  it("should let the owner finalize a resolved market", async () => {
+    const market = await tradedMarket();
+    await resolveWithPrice(h, market, new BN(101));
+    await finalizeMarket(h, market);
+
+    const config = await h.program.account.marketConfig.fetch(market.configAddress);
+    expect(config.status.finalized).to.not.be.undefined;
+  });
+
+  describe("barrier markets", () => {
+    let market: HarnessMarket;
+
+    before(async () => {
+      market = await createResolvableMarket(h, collateralMint, { barrier: true });
+      const bettor = await createBettor(h, collateralMint, new BN(1_000_000));
+      await harnessBuyBet(h, market, bettor, new BN(1_000_000));
+    });
+
+    it("should track prices that do not touch the barrier", async () => {
+      await warpTo(h, market.opensAt + 10);
+      await submitBarrierPrice(h, market, new BN(90));
+
+      const tracker = await h.adapter.account.barrierTracker.fetch(market.barrierTracker);
+      expect(tracker.observations.toNumber()).to.equal(1);
+      const config = await h.program.account.marketConfig.fetch(market.configAddress);
+      expect(config.status.trading).to.not.be.undefined;
+    });
+
+    it("should reject a price no newer than the last one", async () => {
+      try {
+        await submitBarrierPrice(h, market, new BN(150), market.opensAt + 5);
+        expect.fail("Should reject an out of order price");
+      } catch (err) {
+        expect(err.toString()).to.include("StalePrice");
+      }
+    });
+
+    it("should resolve YES before resolves_at once the barrier is touched", async () => {
+      await warpTo(h, market.opensAt + 20);
+      await submitBarrierPrice(h, market, new BN(101));
+
+      const config = await h.program.account.marketConfig.fetch(market.configAddress);
+      expect(config.status.resolved).to.not.be.undefined;
+      expect(await now(h)).to.be.lessThan(market.resolvesAt);
+      const vaultState = await h.program.account.vaultState.fetch(market.vaultStateAddress);
+      expect(vaultState.winningCtMint.toString()).to.equal(market.ct1Mint.toString());
+      const tracker = await h.adapter.account.barrierTracker.fetch(market.barrierTracker);
+      expect(tracker.maxPrice.price.toNumber()).to.equal(101);
+      expect(tracker.minPrice.price.toNumber()).to.equal(90);
+    });
+  });
+
+  describe("relative performance markets", () => {
+    let market: HarnessMarket;
+    let bettor: Keypair;
+
+    before(async () => {
+      market = await createResolvableMarket(h, collateralMint, { relative: true, opensIn: 3600 });
+      bettor = await createBettor(h, collateralMint, new BN(1_000_000));
+    });
+
+    it("should not record start prices long before trading opens", async () => {
+      try {
+        await recordStartPrices(h, market, new BN(100), new BN(200));
+        expect.fail("Should reject start prices outside the window");
+      } catch (err) {
+        expect(err.toString()).to.include("OutsideStartPriceWindow");
+      }
+    });
+
+    it("should not trade until the start prices are recorded", async () => {
+      await warpTo(h, market.opensAt + 1);
+      try {
+        await harnessBuyBet(h, market, bettor, new BN(1_000_000));
+        expect.fail("Should not trade without start prices");
+      } catch (err) {
+        expect(err.toString()).to.include("StartPricePending");
+      }
+    });
+
+    it("should trade once the start prices are recorded", async () => {
+      await recordStartPrices(h, market, new BN(100), new BN(200));
+      await harnessBuyBet(h, market, bettor, new BN(1_000_000));
+
+      const config = await h.program.account.marketConfig.fetch(market.configAddress);
+      expect(config.startPricePending).to.be.false;
+      try {
+        await recordStartPrices(h, market, new BN(100), new BN(200));
+        expect.fail("Should only record once");
+      } catch (err) {
+        expect(err.toString()).to.include("StartPriceAlreadyRecorded");
+      }
+    });
+  });
+});
@@ -0,0 +1,131 @@
+import * as anchor from "@coral-xyz/anchor";
+import { expect } from "chai";
+import {
+  createMint,
+  getAccount,
+  getOrCreateAssociatedTokenAccount,
+  mintTo,
+  TOKEN_2022_PROGRAM_ID,
+} from "@solana/spl-token";
+import { MarketProgram } from "../target/types/market_program";
+import {
+  setupBuyBetTest,
+  buyBet,
+  setTreasury,
+  getAuthAddress,
+  getCollateralInfoAddress,
+  getVaultAddress,
+  getct1MintAddress,
+  getct2MintAddress,
+} from "./utils";
+
+const provider = anchor.AnchorProvider.env();
+anchor.setProvider(provider);
+const program = anchor.workspace.MarketProgram as anchor.Program<MarketProgram>;
+const connection = provider.connection;
+const walletKeypair = (provider.wallet as anchor.Wallet & { payer: anchor.web3.Keypair }).payer;
+
+describe("Reconcile Vault Tests", () => {
+  const betAmount = 1_000_000;
+  const donation = 250_000;
+  let collateralMint: anchor.web3.PublicKey;
+  let collateralAccount: anchor.web3.PublicKey;
+  let configAddress: anchor.web3.PublicKey;
+  let vaultStateAddress: anchor.web3.PublicKey;
+  let vault: anchor.web3.PublicKey;
+  let ct1Mint: anchor.web3.PublicKey;
+  let ct2Mint: anchor.web3.PublicKey;
+
+  const reconcile = async (sweep: boolean, treasury: anchor.web3.PublicKey | null) =>
+    program.methods
+      .reconcileVault(sweep)
+      .accountsPartial({
+        marketConfig: configAddress,
+        vaultState: vaultStateAddress,
+        authority: (await getAuthAddress(vaultStateAddress, program.programId))[0],
+        vault,
+        ct1Mint,
+        ct2Mint,
+        collateralMint,
+        collateralInfo: (await getCollateralInfoAddress(collateralMint, program.programId))[0],
+        treasury,
+        collateralTokenProgram: TOKEN_2022_PROGRAM_ID,
+      })
+      .rpc();
+
+  const vaultBalance = async () =>
+    Number((await getAccount(connection, vault, "confirmed", TOKEN_2022_PROGRAM_ID)).amount);
+
+  before(async () => {
+    const sig = await connection.requestAirdrop(provider.wallet.publicKey, anchor.web3.LAMPORTS_PER_SOL * 5);
+    await connection.confirmTransaction(sig, "confirmed");
+
+    collateralMint = await createMint(connection, walletKeypair, walletKeypair.publicKey, null, 6, undefined, undefined, TOKEN_2022_PROGRAM_ID);
+    collateralAccount = (await getOrCreateAssociatedTokenAccount(
+      connection, walletKeypair, collateralMint, walletKeypair.publicKey, false, "confirmed", undefined, TOKEN_2022_PROGRAM_ID
+    )).address;
+    await mintTo(connection, walletKeypair, collateralMint, collateralAccount, walletKeypair, 10_000_000, [], undefined, TOKEN_2022_PROGRAM_ID);
+
+    ({ configAddress, vaultStateAddress } = await setupBuyBetTest(
+      program, connection, walletKeypair, collateralMint, TOKEN_2022_PROGRAM_ID,
+      { name: "Reconcile", description: "Reconcile", expiration: new anchor.BN(Math.floor(Date.now() / 1000) + 86400) }
+    ));
+    [vault] = await getVaultAddress(vaultStateAddress, program.programId);
+    [ct1Mint] = await getct1MintAddress(vaultStateAddress, program.programId);
+    [ct2Mint] = await getct2MintAddress(vaultStateAddress, program.programId);
+    await buyBet(
+      program, walletKeypair, new anchor.BN(betAmount), configAddress, collateralMint, TOKEN_2022_PROGRAM_ID,
+      vaultStateAddress, vault, ct1Mint, ct2Mint
+    );
+  });
+
+  it("should report a solvent vault without moving funds", async () => {
+    await reconcile(false, null);
+    expect(await vaultBalance()).to.equal(betAmount);
+  });
+
+  it("should keep accepting bets after collateral is donated to the vault", async () => {
+    await mintTo(connection, walletKeypair, collateralMint, vault, walletKeypair, donation, [], undefined, TOKEN_2022_PROGRAM_ID);
+    await buyBet(
+      program, walletKeypair, new anchor.BN(betAmount), configAddress, collateralMint, TOKEN_2022_PROGRAM_ID,
+      vaultStateAddress, vault, ct1Mint, ct2Mint
+    );
+    const vaultState = await program.account.vaultState.fetch(vaultStateAddress);
+    expect(vaultState.vaultCollateralBalance.toNumber()).to.equal(2 * betAmount);
+    expect(await vaultBalance()).to.equal(2 * betAmount + donation);
+  });
+
+  it("should fail to sweep before a treasury is set", async () => {
+    try {
+      await reconcile(true, null);
+      expect.fail("Should have failed without a treasury");
+    } catch (err) {
+      expect(err.toString()).to.include("TreasuryNotSet");
+    }
+  });
+
+  it("should sweep only the surplus to the treasury", async () => {
+    const treasury = await setTreasury(program, connection, walletKeypair, collateralMint);
+
+    await reconcile(true, treasury);
+    expect(await vaultBalance()).to.equal(2 * betAmount);
+    const treasuryAccount = await getAccount(connection, treasury, "confirmed", TOKEN_2022_PROGRAM_ID);
+    expect(Number(treasuryAccount.amount)).to.equal(donation);
+
+    // nothing left to sweep
+    await reconcile(true, treasury);
+    expect(await vaultBalance()).to.equal(2 * betAmount);
+  });
+
+  it("should reject a treasury other than the configured one", async () => {
+    const other = (await getOrCreateAssociatedTokenAccount(
+      connection, walletKeypair, collateralMint, anchor.web3.Keypair.generate().publicKey, false, "confirmed", undefined, TOKEN_2022_PROGRAM_ID
+    )).address;
+    try {
+      await reconcile(true, other);
+      expect.fail("Should have failed with the wrong treasury");
+    } catch (err) {
+      expect(err.toString()).to.include("TreasuryNotSet");
+    }
+  });
+});
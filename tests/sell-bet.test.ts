@@ -64,7 +64,6 @@ describe("Sell Bet Tests", () => {
       const expiration = new anchor.BN(Math.floor(Date.now() / 1000) + 86400);
 
       const config = {
-        index,
         name,
         description,
         expiration
@@ -186,7 +185,6 @@ describe("Sell Bet Tests", () => {
       const expiration = new anchor.BN(Math.floor(Date.now() / 1000) + 86400);
 
       const config = {
-        index,
         name,
         description,
         expiration
@@ -295,7 +293,6 @@ describe("Sell Bet Tests", () => {
       const expiration = new anchor.BN(Math.floor(Date.now() / 1000) + 86400);
 
       const config = {
-        index,
         name,
         description,
         expiration
@@ -396,7 +393,6 @@ describe("Sell Bet Tests", () => {
       const expiration = new anchor.BN(Math.floor(Date.now() / 1000) + 86400);
 
       const config = {
-        index,
         name,
         description,
         expiration
@@ -0,0 +1,147 @@
+import * as anchor from "@coral-xyz/anchor";
+import { expect } from "chai";
+import { createMint, TOKEN_2022_PROGRAM_ID } from "@solana/spl-token";
+import { MarketProgram } from "../target/types/market_program";
+import {
+  setupBuyBetTest,
+  createMarketConfig,
+  setTreasury,
+  getAuthAddress,
+  getCollateralInfoAddress,
+  getProgramDataAddress,
+} from "./utils";
+import {
+  MarketHarness,
+  startMarketHarness,
+  createCollateralMint,
+  createBettor,
+  createResolvableMarket,
+  harnessBuyBet,
+  harnessGetReward,
+  harnessSweepUnclaimed,
+  resolveWithPrice,
+  setHarnessTreasury,
+  tokenBalance,
+  warpTo,
+} from "./utils/bankrun";
+
+const provider = anchor.AnchorProvider.env();
+anchor.setProvider(provider);
+const program = anchor.workspace.MarketProgram as anchor.Program<MarketProgram>;
+const connection = provider.connection;
+const walletKeypair = (provider.wallet as anchor.Wallet & { payer: anchor.web3.Keypair }).payer;
+
+// MIN_CLAIM_WINDOW of the market program
+const MIN_CLAIM_WINDOW = 7 * 86400;
+
+describe("Sweep Unclaimed Tests", () => {
+  const expiration = new anchor.BN(Math.floor(Date.now() / 1000) + 86400);
+  const claimWindow = new anchor.BN(30 * 86400);
+  let collateralMint: anchor.web3.PublicKey;
+  let configAddress: anchor.web3.PublicKey;
+  let vaultStateAddress: anchor.web3.PublicKey;
+  let treasury: anchor.web3.PublicKey;
+
+  before(async () => {
+    const sig = await connection.requestAirdrop(provider.wallet.publicKey, anchor.web3.LAMPORTS_PER_SOL * 5);
+    await connection.confirmTransaction(sig, "confirmed");
+
+    collateralMint = await createMint(connection, walletKeypair, walletKeypair.publicKey, null, 6, undefined, undefined, TOKEN_2022_PROGRAM_ID);
+    ({ configAddress, vaultStateAddress } = await setupBuyBetTest(
+      program, connection, walletKeypair, collateralMint, TOKEN_2022_PROGRAM_ID,
+      { name: "Sweepable", description: "Sweepable", expiration, claimWindow }
+    ));
+    treasury = await setTreasury(program, connection, walletKeypair, collateralMint);
+  });
+
+  it("should store the claim window and no resolution time", async () => {
+    const marketConfig = await program.account.marketConfig.fetch(configAddress);
+    expect(marketConfig.claimWindow.toNumber()).to.equal(claimWindow.toNumber());
+    expect(marketConfig.resolvedAt.toNumber()).to.equal(0);
+    expect(marketConfig.swept).to.be.false;
+  });
+
+  it("should reject a claim window shorter than the minimum", async () => {
+    try {
+      await createMarketConfig(
+        program, connection, walletKeypair, "Short window", "Short window", expiration, undefined,
+        { claimWindow: new anchor.BN(MIN_CLAIM_WINDOW - 1) }
+      );
+      expect.fail("Should have failed with a window below the minimum");
+    } catch (err) {
+      expect(err.toString()).to.include("InvalidTradingWindow");
+    }
+  });
+
+  it("should fail to sweep a market that is still trading", async () => {
+    const marketConfig = await program.account.marketConfig.fetch(configAddress);
+    try {
+      await program.methods
+        .sweepUnclaimed()
+        .accountsPartial({
+          admin: walletKeypair.publicKey,
+          marketProgram: program.programId,
+          programData: (await getProgramDataAddress(program.programId))[0],
+          marketConfig: configAddress,
+          vaultState: vaultStateAddress,
+          authority: (await getAuthAddress(vaultStateAddress, program.programId))[0],
+          vault: marketConfig.vault,
+          collateralMint,
+          collateralInfo: (await getCollateralInfoAddress(collateralMint, program.programId))[0],
+          treasury,
+          collateralTokenProgram: TOKEN_2022_PROGRAM_ID,
+        })
+        .rpc();
+      expect.fail("Should have failed before resolution");
+    } catch (err) {
+      expect(err.toString()).to.include("MarketNotResolved");
+    }
+    expect((await program.account.marketConfig.fetch(configAddress)).swept).to.be.false;
+  });
+
+  describe("after resolution", () => {
+    let h: MarketHarness;
+
+    before(async () => {
+      h = await startMarketHarness();
+    });
+
+    it("should sweep once the claim window ran out and then reject claims", async () => {
+      const collateral = await createCollateralMint(h);
+      const market = await createResolvableMarket(h, collateral, {
+        claimWindow: MIN_CLAIM_WINDOW,
+        gracePeriod: 2 * 86400,
+      });
+      const harnessTreasury = await setHarnessTreasury(h, collateral);
+      const bettor = await createBettor(h, collateral, new anchor.BN(1_000_000));
+      await harnessBuyBet(h, market, bettor, new anchor.BN(1_000_000));
+
+      // resolved a day late, the window counts from the resolution
+      await warpTo(h, market.resolvesAt + 86400);
+      await resolveWithPrice(h, market, new anchor.BN(101));
+      const resolvedAt = (await h.program.account.marketConfig.fetch(market.configAddress)).resolvedAt.toNumber();
+      expect(resolvedAt).to.be.greaterThan(market.resolvesAt + 86400);
+
+      await warpTo(h, market.resolvesAt + MIN_CLAIM_WINDOW + 1);
+      try {
+        await harnessSweepUnclaimed(h, market, harnessTreasury);
+        expect.fail("Should not sweep while the claim window is open");
+      } catch (err) {
+        expect(err.toString()).to.include("ClaimDeadlineNotReached");
+      }
+
+      await warpTo(h, resolvedAt + MIN_CLAIM_WINDOW);
+      await harnessSweepUnclaimed(h, market, harnessTreasury);
+      expect(await tokenBalance(h, harnessTreasury)).to.equal(1_000_000n);
+      expect(await tokenBalance(h, market.vault)).to.equal(0n);
+      expect((await h.program.account.marketConfig.fetch(market.configAddress)).swept).to.be.true;
+
+      try {
+        await harnessGetReward(h, market, bettor, market.ct1Mint, new anchor.BN(1_000_000));
+        expect.fail("Should not pay out after the sweep");
+      } catch (err) {
+        expect(err.toString()).to.include("ClaimDeadlinePassed");
+      }
+    });
+  });
+});
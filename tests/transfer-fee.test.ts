@@ -0,0 +1,167 @@
+import * as anchor from "@coral-xyz/anchor";
+import { expect } from "chai";
+import {
+  TOKEN_2022_PROGRAM_ID,
+  ExtensionType,
+  getMintLen,
+  createInitializeTransferFeeConfigInstruction,
+  createInitializeMintInstruction,
+  getOrCreateAssociatedTokenAccount,
+  mintTo,
+  getAccount,
+} from "@solana/spl-token";
+import { MarketProgram } from "../target/types/market_program";
+import {
+  createMarketConfig,
+  initialize,
+  buyBet,
+  sellBet,
+  setCollateralInfo,
+  defaultCollateralParams,
+  getVaultAddress,
+  getct1MintAddress,
+  getct2MintAddress,
+  getUserCtAccountInfo,
+  sendTransaction,
+} from "./utils";
+
+const provider = anchor.AnchorProvider.env();
+anchor.setProvider(provider);
+const program = anchor.workspace.MarketProgram as anchor.Program<MarketProgram>;
+const connection = provider.connection;
+const walletKeypair = (provider.wallet as anchor.Wallet & { payer: anchor.web3.Keypair }).payer;
+
+// 1% fee, capped well above the amounts used here
+const FEE_BASIS_POINTS = 100;
+const MAX_FEE = BigInt(1_000_000_000);
+const fee = (amount: bigint) => (amount * BigInt(FEE_BASIS_POINTS) + BigInt(9_999)) / BigInt(10_000);
+
+describe("Transfer Fee Collateral Tests", () => {
+  let collateralMint: anchor.web3.PublicKey;
+
+  before(async () => {
+    const sig = await connection.requestAirdrop(
+      provider.wallet.publicKey,
+      anchor.web3.LAMPORTS_PER_SOL * 5
+    );
+    await connection.confirmTransaction(sig, "confirmed");
+
+    const mintKeypair = anchor.web3.Keypair.generate();
+    collateralMint = mintKeypair.publicKey;
+    const mintLen = getMintLen([ExtensionType.TransferFeeConfig]);
+    await sendTransaction(
+      connection,
+      [
+        anchor.web3.SystemProgram.createAccount({
+          fromPubkey: walletKeypair.publicKey,
+          newAccountPubkey: collateralMint,
+          space: mintLen,
+          lamports: await connection.getMinimumBalanceForRentExemption(mintLen),
+          programId: TOKEN_2022_PROGRAM_ID,
+        }),
+        createInitializeTransferFeeConfigInstruction(
+          collateralMint,
+          walletKeypair.publicKey,
+          walletKeypair.publicKey,
+          FEE_BASIS_POINTS,
+          MAX_FEE,
+          TOKEN_2022_PROGRAM_ID
+        ),
+        createInitializeMintInstruction(collateralMint, 6, walletKeypair.publicKey, null, TOKEN_2022_PROGRAM_ID),
+      ],
+      [walletKeypair, mintKeypair]
+    );
+
+    await setCollateralInfo(program, walletKeypair, collateralMint, {
+      ...defaultCollateralParams(),
+      allowedExtensions: new anchor.BN(1 << ExtensionType.TransferFeeConfig),
+    });
+  });
+
+  it("should only mint conditional tokens for the collateral the vault received", async () => {
+    const configAddress = await createMarketConfig(
+      program,
+      connection,
+      walletKeypair,
+      "Fee collateral",
+      "Fee collateral",
+      new anchor.BN(Math.floor(Date.now() / 1000) + 86400)
+    );
+    const { vaultStateAddress } = await initialize(
+      program,
+      walletKeypair,
+      configAddress,
+      collateralMint,
+      TOKEN_2022_PROGRAM_ID
+    );
+    const [vaultAddress] = await getVaultAddress(vaultStateAddress, program.programId);
+    const [ct1Mint] = await getct1MintAddress(vaultStateAddress, program.programId);
+    const [ct2Mint] = await getct2MintAddress(vaultStateAddress, program.programId);
+
+    const userCollateralAccount = await getOrCreateAssociatedTokenAccount(
+      connection,
+      walletKeypair,
+      collateralMint,
+      walletKeypair.publicKey,
+      false,
+      "confirmed",
+      undefined,
+      TOKEN_2022_PROGRAM_ID
+    );
+    await mintTo(
+      connection,
+      walletKeypair,
+      collateralMint,
+      userCollateralAccount.address,
+      walletKeypair,
+      BigInt(100_000_000),
+      [],
+      undefined,
+      TOKEN_2022_PROGRAM_ID
+    );
+
+    const deposit = BigInt(10_000_000);
+    await buyBet(
+      program,
+      walletKeypair,
+      new anchor.BN(deposit.toString()),
+      configAddress,
+      collateralMint,
+      TOKEN_2022_PROGRAM_ID,
+      vaultStateAddress,
+      vaultAddress,
+      ct1Mint,
+      ct2Mint
+    );
+
+    const received = deposit - fee(deposit);
+    const { ct1Amount, ct2Amount } = await getUserCtAccountInfo(connection, walletKeypair.publicKey, ct1Mint, ct2Mint);
+    expect(ct1Amount).to.equal(received);
+    expect(ct2Amount).to.equal(received);
+    const vaultState = await program.account.vaultState.fetch(vaultStateAddress);
+    expect(BigInt(vaultState.vaultCollateralBalance.toString())).to.equal(received);
+    const vault = await getAccount(connection, vaultAddress, "confirmed", TOKEN_2022_PROGRAM_ID);
+    expect(vault.amount).to.equal(received);
+
+    // selling everything empties the vault, the user pays the fee on the way out too
+    const before = (await getAccount(connection, userCollateralAccount.address, "confirmed", TOKEN_2022_PROGRAM_ID)).amount;
+    await sellBet(
+      program,
+      walletKeypair,
+      new anchor.BN(received.toString()),
+      configAddress,
+      collateralMint,
+      TOKEN_2022_PROGRAM_ID,
+      vaultStateAddress,
+      vaultAddress,
+      ct1Mint,
+      ct2Mint
+    );
+    const after = (await getAccount(connection, userCollateralAccount.address, "confirmed", TOKEN_2022_PROGRAM_ID)).amount;
+    expect(after - before).to.equal(received - fee(received));
+    const vaultAfter = await getAccount(connection, vaultAddress, "confirmed", TOKEN_2022_PROGRAM_ID);
+    expect(vaultAfter.amount).to.equal(BigInt(0));
+    const vaultStateAfter = await program.account.vaultState.fetch(vaultStateAddress);
+    expect(vaultStateAfter.vaultCollateralBalance.toNumber()).to.equal(0);
+  });
+});
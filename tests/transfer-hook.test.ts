@@ -0,0 +1,214 @@
+import * as anchor from "@coral-xyz/anchor";
+import { expect } from "chai";
+import {
+  createMint,
+  TOKEN_2022_PROGRAM_ID,
+  getOrCreateAssociatedTokenAccount,
+  getAssociatedTokenAddressSync,
+  mintTo,
+  getAccount,
+  createTransferCheckedWithTransferHookInstruction,
+} from "@solana/spl-token";
+import { MarketProgram } from "../target/types/market_program";
+import { CtTransferHook } from "../target/types/ct_transfer_hook";
+import {
+  setupBuyBetTest,
+  buyBet,
+  getVaultAddress,
+  getct1MintAddress,
+  getct2MintAddress,
+  getExtraAccountMetaListAddress,
+  sendTransaction,
+} from "./utils";
+
+const provider = anchor.AnchorProvider.env();
+anchor.setProvider(provider);
+const program = anchor.workspace.MarketProgram as anchor.Program<MarketProgram>;
+const hookProgram = anchor.workspace.CtTransferHook as anchor.Program<CtTransferHook>;
+const connection = provider.connection;
+const walletKeypair = (provider.wallet as anchor.Wallet & { payer: anchor.web3.Keypair }).payer;
+
+describe("Conditional Token Transfer Hook Tests", () => {
+  const recipient = anchor.web3.Keypair.generate();
+  const decimals = 6;
+  let configAddress: anchor.web3.PublicKey;
+  let ct1MintAddress: anchor.web3.PublicKey;
+  let ct2MintAddress: anchor.web3.PublicKey;
+  let buyCt: (amount: anchor.BN) => Promise<string>;
+
+  const transferCt1 = async (amount: bigint) => {
+    const source = getAssociatedTokenAddressSync(ct1MintAddress, walletKeypair.publicKey, false, TOKEN_2022_PROGRAM_ID);
+    const destination = (await getOrCreateAssociatedTokenAccount(
+      connection,
+      walletKeypair,
+      ct1MintAddress,
+      recipient.publicKey,
+      false,
+      "confirmed",
+      undefined,
+      TOKEN_2022_PROGRAM_ID
+    )).address;
+    const ix = await createTransferCheckedWithTransferHookInstruction(
+      connection,
+      source,
+      ct1MintAddress,
+      destination,
+      walletKeypair.publicKey,
+      amount,
+      decimals,
+      [],
+      "confirmed",
+      TOKEN_2022_PROGRAM_ID
+    );
+    await sendTransaction(connection, [ix], [walletKeypair]);
+    return destination;
+  };
+
+  const setTransferRules = (transfersFrozen: boolean, restricted: boolean) =>
+    program.methods
+      .setTransferRules(transfersFrozen, restricted)
+      .accountsPartial({ owner: walletKeypair.publicKey, marketConfig: configAddress })
+      .rpc();
+
+  before(async () => {
+    const sig = await connection.requestAirdrop(
+      provider.wallet.publicKey,
+      anchor.web3.LAMPORTS_PER_SOL * 5
+    );
+    await connection.confirmTransaction(sig, "confirmed");
+
+    const collateralMint = await createMint(
+      connection,
+      walletKeypair,
+      walletKeypair.publicKey,
+      null,
+      decimals,
+      undefined,
+      undefined,
+      TOKEN_2022_PROGRAM_ID
+    );
+    const expiration = new anchor.BN(Math.floor(Date.now() / 1000) + 86400);
+    const setup = await setupBuyBetTest(
+      program,
+      connection,
+      walletKeypair,
+      collateralMint,
+      TOKEN_2022_PROGRAM_ID,
+      { name: "Hooked", description: "Hooked", expiration }
+    );
+    configAddress = setup.configAddress;
+    [ct1MintAddress] = await getct1MintAddress(setup.vaultStateAddress, program.programId);
+    [ct2MintAddress] = await getct2MintAddress(setup.vaultStateAddress, program.programId);
+    const [vaultAddress] = await getVaultAddress(setup.vaultStateAddress, program.programId);
+
+
+    const userCollateralAccount = await getOrCreateAssociatedTokenAccount(
+      connection,
+      walletKeypair,
+      collateralMint,
+      walletKeypair.publicKey,
+      false,
+      "confirmed",
+      undefined,
+      TOKEN_2022_PROGRAM_ID
+    );
+    await mintTo(
+      connection,
+      walletKeypair,
+      collateralMint,
+      userCollateralAccount.address,
+      walletKeypair,
+      BigInt(101_000_000),
+      [],
+      undefined,
+      TOKEN_2022_PROGRAM_ID
+    );
+    buyCt = (amount: anchor.BN) =>
+      buyBet(
+        program,
+        walletKeypair,
+        amount,
+        configAddress,
+        collateralMint,
+        TOKEN_2022_PROGRAM_ID,
+        setup.vaultStateAddress,
+        vaultAddress,
+        ct1MintAddress,
+        ct2MintAddress
+      );
+    await buyCt(new anchor.BN(100_000_000));
+  });
+
+  it("should create the extra account meta lists of both mints when the market is initialized", async () => {
+    for (const mint of [ct1MintAddress, ct2MintAddress]) {
+      const [metaList] = await getExtraAccountMetaListAddress(mint);
+      const info = await connection.getAccountInfo(metaList, "confirmed");
+      expect(info.owner.toString()).to.equal(hookProgram.programId.toString());
+
+      try {
+        await hookProgram.methods
+          .initializeExtraAccountMetaList()
+          .accountsPartial({ payer: walletKeypair.publicKey, marketConfig: configAddress, mint })
+          .rpc();
+        expect.fail("Should not create the list twice");
+      } catch (err) {
+        expect(err).to.exist;
+      }
+    }
+  });
+
+  it("should allow transfers of an unrestricted market", async () => {
+    const destination = await transferCt1(BigInt(1_000_000));
+    const account = await getAccount(connection, destination, "confirmed", TOKEN_2022_PROGRAM_ID);
+    expect(account.amount).to.equal(BigInt(1_000_000));
+  });
+
+  it("should block transfers while the market is frozen", async () => {
+    await setTransferRules(true, false);
+    try {
+      await transferCt1(BigInt(1_000_000));
+      expect.fail("Should have failed while transfers are frozen");
+    } catch (err) {
+      expect(err).to.exist;
+    } finally {
+      await setTransferRules(false, false);
+    }
+  });
+
+  it("should only allow transfers to allowlisted wallets of a restricted market", async () => {
+    await setTransferRules(false, true);
+    try {
+      await transferCt1(BigInt(1_000_000));
+      expect.fail("Should have failed for a wallet outside the allowlist");
+    } catch (err) {
+      expect(err).to.exist;
+    }
+
+    await hookProgram.methods
+      .addToAllowlist(recipient.publicKey)
+      .accountsPartial({ owner: walletKeypair.publicKey, marketConfig: configAddress })
+      .rpc();
+    const destination = await transferCt1(BigInt(1_000_000));
+    const account = await getAccount(connection, destination, "confirmed", TOKEN_2022_PROGRAM_ID);
+    expect(account.amount).to.equal(BigInt(2_000_000));
+  });
+
+  it("should only take bets from allowlisted wallets of a restricted market", async () => {
+    // the market is still restricted from the previous test
+    try {
+      await buyCt(new anchor.BN(1_000_000));
+      expect.fail("Should have failed for a bettor outside the allowlist");
+    } catch (err) {
+      expect(err.toString()).to.include("BettorNotAllowed");
+    }
+
+    await hookProgram.methods
+      .addToAllowlist(walletKeypair.publicKey)
+      .accountsPartial({ owner: walletKeypair.publicKey, marketConfig: configAddress })
+      .rpc();
+    await buyCt(new anchor.BN(1_000_000));
+    const ct1Account = getAssociatedTokenAddressSync(ct1MintAddress, walletKeypair.publicKey, false, TOKEN_2022_PROGRAM_ID);
+    const account = await getAccount(connection, ct1Account, "confirmed", TOKEN_2022_PROGRAM_ID);
+    expect(account.amount).to.equal(BigInt(100_000_000 - 2_000_000 + 1_000_000));
+  });
+});
@@ -0,0 +1,749 @@
+import * as fs from "fs";
+import * as path from "path";
+import { createHash } from "crypto";
+import * as anchor from "@coral-xyz/anchor";
+import { BN, Program } from "@coral-xyz/anchor";
+import { BankrunProvider } from "anchor-bankrun";
+import { Clock, ProgramTestContext, start } from "solana-bankrun";
+import {
+  AddressLookupTableAccount,
+  AddressLookupTableProgram,
+  Keypair,
+  LAMPORTS_PER_SOL,
+  PublicKey,
+  SystemProgram,
+  Transaction,
+  TransactionInstruction,
+} from "@solana/web3.js";
+import {
+  ExtensionType,
+  TOKEN_2022_PROGRAM_ID,
+  createAssociatedTokenAccountIdempotentInstruction,
+  createInitializeMint2Instruction,
+  createInitializeTransferFeeConfigInstruction,
+  createMintToInstruction,
+  getAssociatedTokenAddressSync,
+  getMintLen,
+} from "@solana/spl-token";
+import { MarketProgram } from "../../target/types/market_program";
+import { OracleAdapterContract } from "../../target/types/oracle_adapter_contract";
+import { emptyMetadata, defaultCollateralParams } from "./instructions";
+import {
+  BPF_LOADER_UPGRADEABLE_ID,
+  REGISTRY_PAGE_CAPACITY,
+  getAuthAddress,
+  getCollateralInfoAddress,
+  getIndexedMarketConfigAddress,
+  getMarketCounterAddress,
+  getMarketRegistryPageAddress,
+  getProgramDataAddress,
+  getTransferHookAccounts,
+  getResolutionConfigAddress,
+  getResolutionRecordAddress,
+  getVaultAddress,
+  getVaultStateAddress,
+  getct1MintAddress,
+  getct2MintAddress,
+} from "./pda";
+
+// bankrun runs the programs in-process, so tests can move the clock and write pyth price updates,
+// which is what resolving a market through the oracle adapter needs
+
+export const PYTH_RECEIVER_PROGRAM_ID = new PublicKey(
+  "rec5EKMGg6MxZYaMdyBfgwp4d5rB9T1VQH5pJv5LtFJ"
+);
+const DEPLOY_DIR = path.join(__dirname, "../../target/deploy");
+const IDL_DIR = path.join(__dirname, "../../target/idl");
+// PriceUpdateV2 sized for a partially verified update, a fully verified one leaves a spare byte
+const PRICE_UPDATE_LEN = 8 + 32 + 2 + 32 + 8 + 8 + 4 + 8 + 8 + 8 + 8 + 8;
+
+export type MarketHarness = {
+  context: ProgramTestContext;
+  provider: BankrunProvider;
+  program: Program<MarketProgram>;
+  adapter: Program<OracleAdapterContract>;
+  // the market program's upgrade authority, also pays for everything
+  admin: Keypair;
+};
+
+export type HarnessMarket = {
+  configAddress: PublicKey;
+  vaultStateAddress: PublicKey;
+  authority: PublicKey;
+  vault: PublicKey;
+  ct1Mint: PublicKey;
+  ct2Mint: PublicKey;
+  collateralMint: PublicKey;
+  collateralInfo: PublicKey;
+  resolutionConfig: PublicKey;
+  resolutionRecord: PublicKey;
+  registryPage: PublicKey;
+  // set for barrier markets only
+  barrierTracker?: PublicKey;
+  opensAt: number;
+  resolvesAt: number;
+};
+
+export const DEFAULT_FEED_ID = Array(32).fill(1);
+export const BENCHMARK_FEED_ID = Array(32).fill(2);
+export const DEFAULT_RULE = {
+  comparison: { above: {} },
+  strike: new BN(100),
+  upperStrike: new BN(0),
+  strikeExponent: 0,
+};
+
+function readIdl(name: string) {
+  return JSON.parse(fs.readFileSync(path.join(IDL_DIR, `${name}.json`), "utf8"));
+}
+
+// the market program is deployed through the upgradeable loader so that its ProgramData,
+// which the admin instructions check the upgrade authority against, exists
+function upgradeableProgramAccounts(programId: PublicKey, elf: Buffer, upgradeAuthority: PublicKey) {
+  const [programData] = PublicKey.findProgramAddressSync([programId.toBuffer()], BPF_LOADER_UPGRADEABLE_ID);
+
+  const program = Buffer.alloc(4 + 32);
+  program.writeUInt32LE(2, 0);
+  programData.toBuffer().copy(program, 4);
+
+  const header = Buffer.alloc(4 + 8 + 1 + 32);
+  header.writeUInt32LE(3, 0);
+  header.writeBigUInt64LE(0n, 4);
+  header.writeUInt8(1, 12);
+  upgradeAuthority.toBuffer().copy(header, 13);
+
+  return [
+    {
+      address: programId,
+      info: { executable: true, owner: BPF_LOADER_UPGRADEABLE_ID, lamports: LAMPORTS_PER_SOL, data: program },
+    },
+    {
+      address: programData,
+      info: {
+        executable: false,
+        owner: BPF_LOADER_UPGRADEABLE_ID,
+        lamports: 10 * LAMPORTS_PER_SOL,
+        data: Buffer.concat([header, elf]),
+      },
+    },
+  ];
+}
+
+export async function startMarketHarness(): Promise<MarketHarness> {
+  const marketIdl = readIdl("market_program");
+  const adapterIdl = readIdl("oracle_adapter_contract");
+  const hookIdl = readIdl("ct_transfer_hook");
+  const admin = Keypair.generate();
+
+  process.env.SBF_OUT_DIR = DEPLOY_DIR;
+  const context = await start(
+    [
+      { name: "oracle_adapter_contract", programId: new PublicKey(adapterIdl.address) },
+      { name: "ct_transfer_hook", programId: new PublicKey(hookIdl.address) },
+    ],
+    [
+      ...upgradeableProgramAccounts(
+        new PublicKey(marketIdl.address),
+        fs.readFileSync(path.join(DEPLOY_DIR, "market_program.so")),
+        admin.publicKey
+      ),
+      {
+        address: admin.publicKey,
+        info: { executable: false, owner: SystemProgram.programId, lamports: 1000 * LAMPORTS_PER_SOL, data: Buffer.alloc(0) },
+      },
+    ]
+  );
+  const provider = new BankrunProvider(context, new anchor.Wallet(admin));
+  return {
+    context,
+    provider,
+    program: new Program<MarketProgram>(marketIdl, provider),
+    adapter: new Program<OracleAdapterContract>(adapterIdl, provider),
+    admin,
+  };
+}
+
+export async function now(h: MarketHarness): Promise<number> {
+  return Number((await h.context.banksClient.getClock()).unixTimestamp);
+}
+
+// moves to the next slot, so a fresh blockhash is used, and sets the clock to `unixTimestamp`
+export async function warpTo(h: MarketHarness, unixTimestamp: number) {
+  const clock = await h.context.banksClient.getClock();
+  const slot = clock.slot + 1n;
+  h.context.warpToSlot(slot);
+  h.context.setClock(
+    new Clock(slot, clock.epochStartTimestamp, clock.epoch, clock.leaderScheduleEpoch, BigInt(unixTimestamp))
+  );
+}
+
+export async function sendInstructions(h: MarketHarness, ixs: TransactionInstruction[], signers: Keypair[] = []) {
+  const tx = new Transaction().add(...ixs);
+  return h.provider.sendAndConfirm(tx, signers);
+}
+
+// writes a fully verified pyth price update for `feedId` to `address`
+export function setPriceUpdate(
+  h: MarketHarness,
+  address: PublicKey,
+  feedId: number[],
+  price: BN,
+  exponent: number,
+  publishTime: number
+) {
+  const data = Buffer.alloc(PRICE_UPDATE_LEN);
+  let offset = 0;
+  createHash("sha256").update("account:PriceUpdateV2").digest().copy(data, offset, 0, 8);
+  offset += 8;
+  h.admin.publicKey.toBuffer().copy(data, offset);
+  offset += 32;
+  // VerificationLevel::Full
+  data.writeUInt8(1, offset);
+  offset += 1;
+  Buffer.from(feedId).copy(data, offset);
+  offset += 32;
+  data.writeBigInt64LE(BigInt(price.toString()), offset);
+  offset += 8;
+  // conf
+  data.writeBigUInt64LE(0n, offset);
+  offset += 8;
+  data.writeInt32LE(exponent, offset);
+  offset += 4;
+  data.writeBigInt64LE(BigInt(publishTime), offset);
+  offset += 8;
+  // prev_publish_time, ema_price, ema_conf and posted_slot are not read
+  data.writeBigInt64LE(BigInt(publishTime), offset);
+
+  h.context.setAccount(address, {
+    executable: false,
+    owner: PYTH_RECEIVER_PROGRAM_ID,
+    lamports: LAMPORTS_PER_SOL,
+    data,
+  });
+  return address;
+}
+
+// the harness lists every collateral with transfer fees allowed, so fee-bearing mints can back markets
+function harnessCollateralParams() {
+  return { ...defaultCollateralParams(), allowedExtensions: new BN(1 << ExtensionType.TransferFeeConfig) };
+}
+
+// a Token-2022 mint of the admin, with a transfer fee of `transferFeeBps` if it is not 0
+export async function createCollateralMint(h: MarketHarness, decimals = 6, transferFeeBps = 0): Promise<PublicKey> {
+  const mint = Keypair.generate();
+  const extensions = transferFeeBps > 0 ? [ExtensionType.TransferFeeConfig] : [];
+  const space = getMintLen(extensions);
+  const lamports = Number(
+    (await h.context.banksClient.getRent()).minimumBalance(BigInt(space))
+  );
+  await sendInstructions(
+    h,
+    [
+      SystemProgram.createAccount({
+        fromPubkey: h.admin.publicKey,
+        newAccountPubkey: mint.publicKey,
+        space,
+        lamports,
+        programId: TOKEN_2022_PROGRAM_ID,
+      }),
+      ...(transferFeeBps > 0
+        ? [createInitializeTransferFeeConfigInstruction(
+            mint.publicKey, h.admin.publicKey, h.admin.publicKey, transferFeeBps, BigInt(1_000_000_000), TOKEN_2022_PROGRAM_ID
+          )]
+        : []),
+      createInitializeMint2Instruction(mint.publicKey, decimals, h.admin.publicKey, null, TOKEN_2022_PROGRAM_ID),
+    ],
+    [mint]
+  );
+  return mint.publicKey;
+}
+
+// a funded wallet with `amount` collateral in its associated token account
+export async function createBettor(h: MarketHarness, collateralMint: PublicKey, amount: BN): Promise<Keypair> {
+  const bettor = Keypair.generate();
+  h.context.setAccount(bettor.publicKey, {
+    executable: false,
+    owner: SystemProgram.programId,
+    lamports: 10 * LAMPORTS_PER_SOL,
+    data: Buffer.alloc(0),
+  });
+  const collateralAccount = getAssociatedTokenAddressSync(collateralMint, bettor.publicKey, false, TOKEN_2022_PROGRAM_ID);
+  await sendInstructions(h, [
+    createAssociatedTokenAccountIdempotentInstruction(
+      h.admin.publicKey,
+      collateralAccount,
+      bettor.publicKey,
+      collateralMint,
+      TOKEN_2022_PROGRAM_ID
+    ),
+    createMintToInstruction(collateralMint, collateralAccount, h.admin.publicKey, BigInt(amount.toString()), [], TOKEN_2022_PROGRAM_ID),
+  ]);
+  return bettor;
+}
+
+export type ResolvableMarketOptions = {
+  // seconds from now until trading opens, 0 by default
+  opensIn?: number;
+  // seconds trading runs for
+  duration?: number;
+  rule?: typeof DEFAULT_RULE;
+  // a relative performance market of the default feed against BENCHMARK_FEED_ID instead of a price rule
+  relative?: boolean;
+  // a barrier market that resolves YES as soon as a submitted price satisfies the rule
+  barrier?: boolean;
+  gracePeriod?: number;
+  // skip the adapter's resolution config, leaving the market to invalidate_unconfigured_market
+  unconfigured?: boolean;
+  // seconds after resolution during which winning tokens can be redeemed
+  claimWindow?: number;
+};
+
+// creates a market owned by the admin that resolves `duration` seconds after trading opens,
+// and the adapter's resolution config for it
+export async function createResolvableMarket(
+  h: MarketHarness,
+  collateralMint: PublicKey,
+  options?: ResolvableMarketOptions
+): Promise<HarnessMarket> {
+  const { program, adapter, admin } = h;
+  const opensAt = (await now(h)) + (options?.opensIn ?? 0);
+  const resolvesAt = opensAt + (options?.duration ?? 3600);
+
+  const [counterAddress] = await getMarketCounterAddress(program.programId);
+  const counter = await program.account.marketCounter.fetchNullable(counterAddress);
+  const index = counter ? counter.count.toNumber() : 0;
+  const [configAddress] = await getIndexedMarketConfigAddress(index, program.programId);
+  const [registryPage] = await getMarketRegistryPageAddress(
+    Math.floor(index / REGISTRY_PAGE_CAPACITY),
+    program.programId
+  );
+  await program.methods
+    .createMarketConfig(`Market ${index}`, "bankrun market", { other: {} }, emptyMetadata(), {
+      tradingOpensAt: new BN(opensAt),
+      tradingClosesAt: new BN(resolvesAt),
+      resolvesAt: new BN(resolvesAt),
+      haltBuffer: new BN(0),
+      claimWindow: options?.claimWindow ? new BN(options.claimWindow) : null,
+    })
+    .accountsPartial({
+      signer: admin.publicKey,
+      marketCounter: counterAddress,
+      marketConfig: configAddress,
+      marketRegistryPage: registryPage,
+      systemProgram: SystemProgram.programId,
+    })
+    .rpc();
+
+  const [collateralInfo] = await getCollateralInfoAddress(collateralMint, program.programId);
+  if (!(await h.context.banksClient.getAccount(collateralInfo))) {
+    const [programData] = await getProgramDataAddress(program.programId);
+    await program.methods
+      .setCollateralInfo(harnessCollateralParams())
+      .accountsPartial({
+        admin: admin.publicKey,
+        marketProgram: program.programId,
+        programData,
+        collateralMint,
+        collateralInfo,
+        systemProgram: SystemProgram.programId,
+      })
+      .rpc();
+  }
+
+  const [vaultStateAddress] = await getVaultStateAddress(configAddress, collateralMint, program.programId);
+  const [authority] = await getAuthAddress(vaultStateAddress, program.programId);
+  const [vault] = await getVaultAddress(vaultStateAddress, program.programId);
+  const [ct1Mint] = await getct1MintAddress(vaultStateAddress, program.programId);
+  const [ct2Mint] = await getct2MintAddress(vaultStateAddress, program.programId);
+  await program.methods
+    .initialize()
+    .accountsPartial({
+      creator: admin.publicKey,
+      marketConfig: configAddress,
+      authority,
+      ct1Mint,
+      ct2Mint,
+      ct1TokenProgram: TOKEN_2022_PROGRAM_ID,
+      ct2TokenProgram: TOKEN_2022_PROGRAM_ID,
+      vaultState: vaultStateAddress,
+      vault,
+      collateralMint,
+      collateralInfo,
+      collateralTokenProgram: TOKEN_2022_PROGRAM_ID,
+      tokenProgram: TOKEN_2022_PROGRAM_ID,
+      systemProgram: SystemProgram.programId,
+      ...(await getTransferHookAccounts(ct1Mint, ct2Mint)),
+    })
+    .rpc();
+
+  const [resolutionConfig] = await getResolutionConfigAddress(configAddress, adapter.programId);
+  const gracePeriod = new BN(options?.gracePeriod ?? 3600);
+  let barrierTracker: PublicKey | undefined;
+  if (options?.unconfigured) {
+    // no resolution config, the adapter can never resolve this market
+  } else if (options?.relative) {
+    await adapter.methods
+      .initializeRelative(DEFAULT_FEED_ID, BENCHMARK_FEED_ID, gracePeriod)
+      .accountsPartial({ payer: admin.publicKey, marketConfig: configAddress, resolutionConfig })
+      .rpc();
+  } else if (options?.barrier) {
+    [barrierTracker] = PublicKey.findProgramAddressSync(
+      [Buffer.from("barrier_tracker"), configAddress.toBuffer()],
+      adapter.programId
+    );
+    await adapter.methods
+      .initializeBarrier(DEFAULT_FEED_ID, options?.rule ?? DEFAULT_RULE, gracePeriod)
+      .accountsPartial({ payer: admin.publicKey, marketConfig: configAddress, resolutionConfig, barrierTracker })
+      .rpc();
+  } else {
+    await adapter.methods
+      .initialize(DEFAULT_FEED_ID, options?.rule ?? DEFAULT_RULE, gracePeriod)
+      .accountsPartial({ payer: admin.publicKey, marketConfig: configAddress, resolutionConfig })
+      .rpc();
+  }
+
+  const [resolutionRecord] = await getResolutionRecordAddress(configAddress, program.programId);
+  return {
+    configAddress,
+    vaultStateAddress,
+    authority,
+    vault,
+    ct1Mint,
+    ct2Mint,
+    collateralMint,
+    collateralInfo,
+    resolutionConfig,
+    resolutionRecord,
+    registryPage,
+    barrierTracker,
+    opensAt,
+    resolvesAt,
+  };
+}
+
+export async function harnessBuyBet(h: MarketHarness, market: HarnessMarket, bettor: Keypair, amount: BN, delegateClaim = false) {
+  await h.program.methods
+    .buyBet(amount, delegateClaim)
+    .accountsPartial({
+      bettor: bettor.publicKey,
+      authority: market.authority,
+      collateralAccount: getAssociatedTokenAddressSync(market.collateralMint, bettor.publicKey, false, TOKEN_2022_PROGRAM_ID),
+      marketConfig: market.configAddress,
+      vaultState: market.vaultStateAddress,
+      vault: market.vault,
+      ct1Mint: market.ct1Mint,
+      ct2Mint: market.ct2Mint,
+      ct1Account: getAssociatedTokenAddressSync(market.ct1Mint, bettor.publicKey, false, TOKEN_2022_PROGRAM_ID),
+      ct2Account: getAssociatedTokenAddressSync(market.ct2Mint, bettor.publicKey, false, TOKEN_2022_PROGRAM_ID),
+      collateralMint: market.collateralMint,
+      collateralInfo: market.collateralInfo,
+      collateralTokenProgram: TOKEN_2022_PROGRAM_ID,
+      tokenProgram: TOKEN_2022_PROGRAM_ID,
+      systemProgram: SystemProgram.programId,
+    })
+    .signers([bettor])
+    .rpc();
+}
+
+// the accounts trigger, force_invalidate and submit_barrier_price share
+export function adapterResolveAccounts(h: MarketHarness, market: HarnessMarket) {
+  const [oracleAuthority] = PublicKey.findProgramAddressSync([Buffer.from("oracle-adapter-pda")], h.adapter.programId);
+  const [marketEventAuthority] = PublicKey.findProgramAddressSync([Buffer.from("__event_authority")], h.program.programId);
+  return {
+    payer: h.admin.publicKey,
+    marketConfig: market.configAddress,
+    resolutionConfig: market.resolutionConfig,
+    oracleAuthority,
+    resolutionRecord: market.resolutionRecord,
+    marketProgram: h.program.programId,
+    marketEventAuthority,
+    vaultState: market.vaultStateAddress,
+    marketRegistryPage: market.registryPage,
+    systemProgram: SystemProgram.programId,
+  };
+}
+
+// warps past the resolution time and resolves the market on a `price` for the default feed
+// published at the resolution time, trigger only accepts prices published right after it
+export async function resolveWithPrice(h: MarketHarness, market: HarnessMarket, price: BN, exponent = 0) {
+  const resolveTime = Math.max(await now(h), market.resolvesAt) + 1;
+  await warpTo(h, resolveTime);
+  const priceUpdate = setPriceUpdate(h, Keypair.generate().publicKey, DEFAULT_FEED_ID, price, exponent, market.resolvesAt);
+  await h.adapter.methods
+    .trigger()
+    .accountsPartial({ ...adapterResolveAccounts(h, market), priceUpdate, benchmarkPriceUpdate: null })
+    .rpc();
+}
+
+// submits a barrier market's price for the default feed published at `publishTime`, the current time by default
+export async function submitBarrierPrice(h: MarketHarness, market: HarnessMarket, price: BN, publishTime?: number) {
+  const priceUpdate = setPriceUpdate(
+    h,
+    Keypair.generate().publicKey,
+    DEFAULT_FEED_ID,
+    price,
+    0,
+    publishTime ?? (await now(h))
+  );
+  await h.adapter.methods
+    .submitBarrierPrice()
+    .accountsPartial({ ...adapterResolveAccounts(h, market), barrierTracker: market.barrierTracker, priceUpdate })
+    .rpc();
+}
+
+// records the start prices of a relative performance market at the current time
+export async function recordStartPrices(h: MarketHarness, market: HarnessMarket, price: BN, benchmarkPrice: BN) {
+  const publishTime = await now(h);
+  await h.adapter.methods
+    .recordStartPrice()
+    .accountsPartial({
+      payer: h.admin.publicKey,
+      marketConfig: market.configAddress,
+      resolutionConfig: market.resolutionConfig,
+      priceUpdate: setPriceUpdate(h, Keypair.generate().publicKey, DEFAULT_FEED_ID, price, 0, publishTime),
+      benchmarkPriceUpdate: setPriceUpdate(h, Keypair.generate().publicKey, BENCHMARK_FEED_ID, benchmarkPrice, 0, publishTime),
+    })
+    .rpc();
+}
+
+// warps past the grace period and invalidates the market
+export async function forceInvalidate(h: MarketHarness, market: HarnessMarket) {
+  const config = await h.adapter.account.resolutionConfig.fetch(market.resolutionConfig);
+  await warpTo(h, market.resolvesAt + config.gracePeriod.toNumber() + 1);
+  await h.adapter.methods.forceInvalidate().accountsPartial(adapterResolveAccounts(h, market)).rpc();
+}
+
+// invalidates a market that has no resolution config, once the fallback grace period is over
+export async function invalidateUnconfiguredMarket(h: MarketHarness, market: HarnessMarket) {
+  await h.program.methods
+    .invalidateUnconfiguredMarket()
+    .accountsPartial({
+      payer: h.admin.publicKey,
+      marketConfig: market.configAddress,
+      marketRegistryPage: market.registryPage,
+      vaultState: market.vaultStateAddress,
+      resolutionConfig: market.resolutionConfig,
+      resolutionRecord: market.resolutionRecord,
+      systemProgram: SystemProgram.programId,
+    })
+    .rpc();
+}
+
+// calls off a market whose trading has not opened yet
+export async function harnessCancelMarket(h: MarketHarness, market: HarnessMarket) {
+  await h.program.methods
+    .cancelMarket()
+    .accountsPartial({
+      owner: h.admin.publicKey,
+      marketConfig: market.configAddress,
+      marketRegistryPage: market.registryPage,
+    })
+    .rpc();
+}
+
+export async function finalizeMarket(h: MarketHarness, market: HarnessMarket) {
+  await h.program.methods
+    .finalizeMarket()
+    .accountsPartial({
+      owner: h.admin.publicKey,
+      marketConfig: market.configAddress,
+      marketRegistryPage: market.registryPage,
+    })
+    .rpc();
+}
+
+// redeems `amount` of the bettor's `ctMint` tokens of a resolved market
+export async function harnessGetReward(
+  h: MarketHarness,
+  market: HarnessMarket,
+  bettor: Keypair,
+  ctMint: PublicKey,
+  amount: BN
+) {
+  await h.program.methods
+    .getReward(amount)
+    .accountsPartial({
+      bettor: bettor.publicKey,
+      authority: market.authority,
+      collateralAccount: getAssociatedTokenAddressSync(market.collateralMint, bettor.publicKey, false, TOKEN_2022_PROGRAM_ID),
+      ctMint,
+      otherCtMint: ctMint.equals(market.ct1Mint) ? market.ct2Mint : market.ct1Mint,
+      ctAccount: getAssociatedTokenAddressSync(ctMint, bettor.publicKey, false, TOKEN_2022_PROGRAM_ID),
+      marketConfig: market.configAddress,
+      vaultState: market.vaultStateAddress,
+      vault: market.vault,
+      collateralMint: market.collateralMint,
+      collateralTokenProgram: TOKEN_2022_PROGRAM_ID,
+      tokenProgram: TOKEN_2022_PROGRAM_ID,
+    })
+    .signers([bettor])
+    .rpc();
+}
+
+// redeems `ctMint` of a resolved market for every holder that delegated to the market authority
+export async function harnessClaimFor(h: MarketHarness, market: HarnessMarket, ctMint: PublicKey, holders: PublicKey[]) {
+  await h.program.methods
+    .claimFor()
+    .accountsPartial({
+      authority: market.authority,
+      marketConfig: market.configAddress,
+      vaultState: market.vaultStateAddress,
+      vault: market.vault,
+      ct1Mint: market.ct1Mint,
+      ct2Mint: market.ct2Mint,
+      collateralMint: market.collateralMint,
+      collateralTokenProgram: TOKEN_2022_PROGRAM_ID,
+      tokenProgram: TOKEN_2022_PROGRAM_ID,
+    })
+    .remainingAccounts(
+      holders.flatMap((holder) => [
+        { pubkey: getAssociatedTokenAddressSync(ctMint, holder, false, TOKEN_2022_PROGRAM_ID), isSigner: false, isWritable: true },
+        { pubkey: getAssociatedTokenAddressSync(market.collateralMint, holder, false, TOKEN_2022_PROGRAM_ID), isSigner: false, isWritable: true },
+      ])
+    )
+    .rpc();
+}
+
+// burns what pays nothing and closes the bettor's CT accounts of a resolved market, `sides` picks which
+export async function harnessClosePosition(
+  h: MarketHarness,
+  market: HarnessMarket,
+  bettor: Keypair,
+  sides: { ct1: boolean; ct2: boolean } = { ct1: true, ct2: true }
+) {
+  await h.program.methods
+    .closePosition()
+    .accountsPartial({
+      bettor: bettor.publicKey,
+      marketConfig: market.configAddress,
+      vaultState: market.vaultStateAddress,
+      ct1Mint: market.ct1Mint,
+      ct2Mint: market.ct2Mint,
+      ct1Account: sides.ct1 ? getAssociatedTokenAddressSync(market.ct1Mint, bettor.publicKey, false, TOKEN_2022_PROGRAM_ID) : null,
+      ct2Account: sides.ct2 ? getAssociatedTokenAddressSync(market.ct2Mint, bettor.publicKey, false, TOKEN_2022_PROGRAM_ID) : null,
+      tokenProgram: TOKEN_2022_PROGRAM_ID,
+    })
+    .signers([bettor])
+    .rpc();
+}
+
+// makes a token account of a fresh owner the collateral's treasury
+export async function setHarnessTreasury(h: MarketHarness, collateralMint: PublicKey): Promise<PublicKey> {
+  const owner = Keypair.generate().publicKey;
+  const treasury = getAssociatedTokenAddressSync(collateralMint, owner, false, TOKEN_2022_PROGRAM_ID);
+  await sendInstructions(h, [
+    createAssociatedTokenAccountIdempotentInstruction(h.admin.publicKey, treasury, owner, collateralMint, TOKEN_2022_PROGRAM_ID),
+  ]);
+  const [collateralInfo] = await getCollateralInfoAddress(collateralMint, h.program.programId);
+  const [programData] = await getProgramDataAddress(h.program.programId);
+  await h.program.methods
+    .setCollateralInfo({ ...harnessCollateralParams(), treasury })
+    .accountsPartial({
+      admin: h.admin.publicKey,
+      marketProgram: h.program.programId,
+      programData,
+      collateralMint,
+      collateralInfo,
+      systemProgram: SystemProgram.programId,
+    })
+    .rpc();
+  return treasury;
+}
+
+// moves what is left in the vault of a market whose claim window ran out to the treasury
+export async function harnessSweepUnclaimed(h: MarketHarness, market: HarnessMarket, treasury: PublicKey) {
+  await h.program.methods
+    .sweepUnclaimed()
+    .accountsPartial({
+      admin: h.admin.publicKey,
+      marketProgram: h.program.programId,
+      programData: (await getProgramDataAddress(h.program.programId))[0],
+      marketConfig: market.configAddress,
+      vaultState: market.vaultStateAddress,
+      authority: market.authority,
+      vault: market.vault,
+      collateralMint: market.collateralMint,
+      collateralInfo: market.collateralInfo,
+      treasury,
+      collateralTokenProgram: TOKEN_2022_PROGRAM_ID,
+    })
+    .rpc();
+}
+
+// closes the adapter's accounts of a finalized or invalid market
+export async function closeResolutionConfig(h: MarketHarness, market: HarnessMarket) {
+  await h.adapter.methods
+    .closeResolutionConfig()
+    .accountsPartial({
+      owner: h.admin.publicKey,
+      marketConfig: market.configAddress,
+      resolutionConfig: market.resolutionConfig,
+      barrierTracker: market.barrierTracker ?? null,
+    })
+    .rpc();
+}
+
+export async function harnessCloseMarket(h: MarketHarness, market: HarnessMarket, treasury: PublicKey | null = null) {
+  await h.program.methods
+    .closeMarket()
+    .accountsPartial({
+      owner: h.admin.publicKey,
+      marketConfig: market.configAddress,
+      marketRegistryPage: market.registryPage,
+      vaultState: market.vaultStateAddress,
+      authority: market.authority,
+      vault: market.vault,
+      ct1Mint: market.ct1Mint,
+      ct2Mint: market.ct2Mint,
+      collateralMint: market.collateralMint,
+      collateralInfo: market.collateralInfo,
+      treasury,
+      resolutionConfig: market.resolutionConfig,
+      barrierTracker: PublicKey.findProgramAddressSync(
+        [Buffer.from("barrier_tracker"), market.configAddress.toBuffer()],
+        h.adapter.programId
+      )[0],
+      tokenProgram: TOKEN_2022_PROGRAM_ID,
+      collateralTokenProgram: TOKEN_2022_PROGRAM_ID,
+    })
+    .rpc();
+}
+
+// writes an active lookup table holding `addresses`, bankrun has no recent slot hashes to create one
+export async function setLookupTable(h: MarketHarness, addresses: PublicKey[]): Promise<AddressLookupTableAccount> {
+  const key = Keypair.generate().publicKey;
+  // never deactivated
+  const deactivationSlot = 0xffffffffffffffffn;
+  // type, deactivation slot, last extended slot, its start index, authority option, authority and padding
+  const meta = Buffer.alloc(56);
+  meta.writeUInt32LE(1, 0);
+  meta.writeBigUInt64LE(deactivationSlot, 4);
+  const data = Buffer.concat([meta, ...addresses.map((address) => address.toBuffer())]);
+  h.context.setAccount(key, {
+    executable: false,
+    owner: AddressLookupTableProgram.programId,
+    lamports: LAMPORTS_PER_SOL,
+    data,
+  });
+  // addresses extended in the current slot are not usable yet
+  h.context.warpToSlot((await h.context.banksClient.getSlot()) + 1n);
+  return new AddressLookupTableAccount({
+    key,
+    state: {
+      deactivationSlot,
+      lastExtendedSlot: 0,
+      lastExtendedSlotStartIndex: 0,
+      authority: undefined,
+      addresses,
+    },
+  });
+}
+
+export async function tokenBalance(h: MarketHarness, account: PublicKey): Promise<bigint> {
+  const info = await h.context.banksClient.getAccount(account);
+  if (!info) {
+    return 0n;
+  }
+  // the amount follows the mint and owner in every token account
+  return Buffer.from(info.data).readBigUInt64LE(64);
+}
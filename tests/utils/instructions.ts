@@ -1,8 +1,12 @@
 import { Program, BN } from "@coral-xyz/anchor";
 import { MarketProgram } from "../../target/types/market_program";
 import {
+  AccountMeta,
+  AddressLookupTableAccount,
+  ComputeBudgetProgram,
   Connection,
   ConfirmOptions,
+  Keypair,
   PublicKey,
   Signer,
   SystemProgram,
@@ -10,17 +14,28 @@ import {
 import {
   TOKEN_PROGRAM_ID,
   TOKEN_2022_PROGRAM_ID,
+  getAccount,
   getAssociatedTokenAddressSync,
+  getOrCreateAssociatedTokenAccount,
 } from "@solana/spl-token";
 import {
   accountExist,
   sendTransaction,
-  getMarketConfigAddress,
+  sendVersionedTransaction,
+  createLookupTable,
+  getIndexedMarketConfigAddress,
+  getMarketCounterAddress,
+  getMarketRegistryPageAddress,
+  getCollateralInfoAddress,
+  getProgramDataAddress,
+  REGISTRY_PAGE_CAPACITY,
   getAuthAddress,
   getVaultStateAddress,
   getVaultAddress,
   getct1MintAddress,
   getct2MintAddress,
+  getTransferHookAccounts,
+  getAllowlistEntryAddress,
 } from "./index";
 
 import { ASSOCIATED_PROGRAM_ID } from "@coral-xyz/anchor/dist/cjs/utils/token";
@@ -30,7 +45,6 @@ export async function setupInitializeMarketTest(
   connection: Connection,
   owner: Signer,
   config: {
-    index: number;
     name: string;
     description: string;
     expiration: BN;
@@ -41,7 +55,6 @@ export async function setupInitializeMarketTest(
     program,
     connection,
     owner,
-    config.index,
     config.name,
     config.description,
     config.expiration,
@@ -59,10 +72,10 @@ export async function setupBuyBetTest(
   collateralMint: PublicKey,
   collateralTokenProgram: PublicKey,
   config: {
-    index: number;
     name: string;
     description: string;
     expiration: BN;
+    claimWindow?: BN;
   },
   confirmOptions?: ConfirmOptions,
 ) {
@@ -70,11 +83,11 @@ export async function setupBuyBetTest(
     program,
     connection,
     bettor,
-    config.index,
     config.name,
     config.description,
     config.expiration,
-    confirmOptions
+    confirmOptions,
+    { claimWindow: config.claimWindow }
   );
 
   const { vaultState, vaultStateAddress } = await initialize(
@@ -96,7 +109,6 @@ export async function setupSellBetTest(
   buyAmount: BN,
   owner: Signer,
   config: {
-    index: number;
     name: string;
     description: string;
     expiration: BN;
@@ -107,7 +119,6 @@ export async function setupSellBetTest(
     program,
     connection,
     owner,
-    config.index,
     config.name,
     config.description,
     config.expiration,
@@ -161,34 +172,63 @@ export async function setupSellBetTest(
   };
 }
 
+export type MarketMetadata = {
+  metadataUri: string;
+  rulesHash: number[];
+  tags: string[];
+  imageUri: string;
+  resolutionSource: string;
+};
+
+export function emptyMetadata(): MarketMetadata {
+  return {
+    metadataUri: "",
+    rulesHash: Array(32).fill(0),
+    tags: [],
+    imageUri: "",
+    resolutionSource: "",
+  };
+}
+
 export async function createMarketConfig(
   program: Program<MarketProgram>,
   connection: Connection,
   owner: Signer,
-  index: number,
   name: string,
   description: string,
   expiration: BN,
-  confirmOptions?: ConfirmOptions
+  confirmOptions?: ConfirmOptions,
+  options?: { opensAt?: BN; metadata?: MarketMetadata; claimWindow?: BN }
 ): Promise<PublicKey> {
-  const [address, _] = await getMarketConfigAddress(
-    index,
+  const [counterAddress] = await getMarketCounterAddress(program.programId);
+  const counter = await program.account.marketCounter.fetchNullable(counterAddress);
+  const index = counter ? counter.count.toNumber() : 0;
+  const [address, _] = await getIndexedMarketConfigAddress(index, program.programId);
+  const [registryPage] = await getMarketRegistryPageAddress(
+    Math.floor(index / REGISTRY_PAGE_CAPACITY),
     program.programId
   );
-  if (await accountExist(connection, address)) {
-    return address;
-  }
-
+  // by default trading opens right away and runs until the market expires
+  const window = {
+    tradingOpensAt: options?.opensAt ?? new BN(Math.floor(Date.now() / 1000) - 60),
+    tradingClosesAt: expiration,
+    resolvesAt: expiration,
+    haltBuffer: new BN(0),
+    claimWindow: options?.claimWindow ?? null,
+  };
   const ix = await program.methods
     .createMarketConfig(
-      index,
       name,
       description,
-      expiration
+      { other: {} },
+      options?.metadata ?? emptyMetadata(),
+      window
     )
     .accountsPartial({
         signer: owner.publicKey,
+        marketCounter: counterAddress,
         marketConfig: address,
+        marketRegistryPage: registryPage,
         systemProgram: SystemProgram.programId,
     })
     .instruction();
@@ -198,6 +238,65 @@ export async function createMarketConfig(
   return address;
 }
 
+export type CollateralParams = {
+  enabled: boolean;
+  maxOpenInterest: BN;
+  minBet: BN;
+  allowedExtensions: BN;
+  allowFreezeAuthority: boolean;
+  treasury: PublicKey;
+};
+
+export function defaultCollateralParams(): CollateralParams {
+  return {
+    enabled: true,
+    maxOpenInterest: new BN(0),
+    minBet: new BN(0),
+    allowedExtensions: new BN(0),
+    allowFreezeAuthority: false,
+    treasury: PublicKey.default,
+  };
+}
+
+// lists a collateral mint, the admin must be the market program's upgrade authority
+export async function setCollateralInfo(
+  program: Program<MarketProgram>,
+  admin: Signer,
+  collateralMint: PublicKey,
+  params: CollateralParams = defaultCollateralParams(),
+  confirmOptions?: ConfirmOptions
+) {
+  const [collateralInfo] = await getCollateralInfoAddress(collateralMint, program.programId);
+  const [programData] = await getProgramDataAddress(program.programId);
+  await program.methods
+    .setCollateralInfo(params)
+    .accountsPartial({
+      admin: admin.publicKey,
+      marketProgram: program.programId,
+      programData,
+      collateralMint,
+      collateralInfo,
+      systemProgram: SystemProgram.programId,
+    })
+    .signers([admin])
+    .rpc(confirmOptions);
+  return collateralInfo;
+}
+
+// makes a token account of a fresh owner the collateral's treasury
+export async function setTreasury(
+  program: Program<MarketProgram>,
+  connection: Connection,
+  admin: Signer,
+  collateralMint: PublicKey
+): Promise<PublicKey> {
+  const treasury = (await getOrCreateAssociatedTokenAccount(
+    connection, admin, collateralMint, Keypair.generate().publicKey, false, "confirmed", undefined, TOKEN_2022_PROGRAM_ID
+  )).address;
+  await setCollateralInfo(program, admin, collateralMint, { ...defaultCollateralParams(), treasury });
+  return treasury;
+}
+
 export async function initialize(
   program: Program<MarketProgram>,
   creator: Signer,
@@ -206,12 +305,12 @@ export async function initialize(
   collateralTokenProgram: PublicKey,
   confirmOptions?: ConfirmOptions,
 ) {
-  const [authority] = await getAuthAddress(program.programId);
   const [vaultStateAddress] = await getVaultStateAddress(
     configAddress,
     collateralMint,
     program.programId
   );
+  const [authority] = await getAuthAddress(vaultStateAddress, program.programId);
   const [vaultAddress] = await getVaultAddress(
     vaultStateAddress,
     program.programId
@@ -224,6 +323,10 @@ export async function initialize(
     vaultStateAddress,
     program.programId
   );
+  const [collateralInfo] = await getCollateralInfoAddress(collateralMint, program.programId);
+  if (!(await accountExist(program.provider.connection, collateralInfo))) {
+    await setCollateralInfo(program, creator, collateralMint, defaultCollateralParams(), confirmOptions);
+  }
   
   try {
     const tx = await program.methods
@@ -239,9 +342,13 @@ export async function initialize(
         vaultState: vaultStateAddress,
         vault: vaultAddress,
         collateralMint: collateralMint,
+        collateralInfo,
         collateralTokenProgram: TOKEN_2022_PROGRAM_ID,
         tokenProgram: collateralTokenProgram,
         systemProgram: SystemProgram.programId,
+        ...(collateralTokenProgram.equals(TOKEN_2022_PROGRAM_ID)
+          ? await getTransferHookAccounts(ct1MintAddress, ct2MintAddress)
+          : {}),
       })
       .rpc(confirmOptions);
 
@@ -268,9 +375,10 @@ export async function buyBet(
   vaultAddress: PublicKey,
   ct1MintAddress: PublicKey,
   ct2MintAddress: PublicKey,
+  delegateClaim = false,
   confirmOptions?: ConfirmOptions
 ) {
-  const [authority] = await getAuthAddress(program.programId);
+  const [authority] = await getAuthAddress(vaultStateAddress, program.programId);
   const ct1Account = getAssociatedTokenAddressSync(
     ct1MintAddress,
     owner.publicKey,
@@ -292,12 +400,13 @@ export async function buyBet(
 
   try {
     const tx = await program.methods
-      .buyBet(amount)
+      .buyBet(amount, delegateClaim)
       .accountsPartial({
         bettor: owner.publicKey,
         authority,
         collateralAccount: collateralAccount,
         ct1Mint: ct1MintAddress,
+        marketConfig: configAddress,
         vaultState: vaultStateAddress,
         vault: vaultAddress,
         ct2Mint: ct2MintAddress,
@@ -338,7 +447,7 @@ export async function sellBet(
   ct2MintAddress: PublicKey,
   confirmOptions?: ConfirmOptions
 ) {
-  const [authority] = await getAuthAddress(program.programId);
+  const [authority] = await getAuthAddress(vaultStateAddress, program.programId);
 
   const ct1Account = getAssociatedTokenAddressSync(
     ct1MintAddress,
@@ -366,6 +475,7 @@ export async function sellBet(
         bettor: owner.publicKey,
         authority,
         collateralAccount,
+        marketConfig: configAddress,
         vaultState: vaultStateAddress,
         vault: vaultAddress,
         ct1Mint: ct1MintAddress,
@@ -398,16 +508,232 @@ export async function getReward(
   program: Program<MarketProgram>,
   owner: Signer,
   configAddress: PublicKey,
+  ctMint: PublicKey,
   amount: BN,
   confirmOptions?: ConfirmOptions
 ) {
-  const [auth] = await getAuthAddress(program.programId);
+  const marketConfig = await program.account.marketConfig.fetch(configAddress);
+  const vaultInfo = await program.provider.connection.getAccountInfo(marketConfig.vault);
+  const collateralTokenProgram = vaultInfo.owner;
+  const vault = await getAccount(program.provider.connection, marketConfig.vault, "confirmed", collateralTokenProgram);
+  const [authority] = await getAuthAddress(marketConfig.vaultState, program.programId);
+
   const tx = await program.methods
     .getReward(amount)
-    .accounts({
-      
+    .accountsPartial({
+      bettor: owner.publicKey,
+      authority,
+      collateralAccount: getAssociatedTokenAddressSync(vault.mint, owner.publicKey, false, collateralTokenProgram),
+      ctMint,
+      otherCtMint: ctMint.equals(marketConfig.ct1Mint) ? marketConfig.ct2Mint : marketConfig.ct1Mint,
+      ctAccount: getAssociatedTokenAddressSync(ctMint, owner.publicKey, false, TOKEN_2022_PROGRAM_ID),
+      marketConfig: configAddress,
+      vaultState: marketConfig.vaultState,
+      vault: marketConfig.vault,
+      collateralMint: vault.mint,
+      collateralTokenProgram,
+      tokenProgram: TOKEN_2022_PROGRAM_ID,
     })
+    .signers([owner])
     .rpc(confirmOptions);
 
   return tx;
 }
+
+// redeems `ctMint` for every holder in one call, each holder adds their ct ata and collateral ata
+export async function claimFor(
+  program: Program<MarketProgram>,
+  configAddress: PublicKey,
+  ctMint: PublicKey,
+  holders: PublicKey[],
+  confirmOptions?: ConfirmOptions
+) {
+  const marketConfig = await program.account.marketConfig.fetch(configAddress);
+  const vaultInfo = await program.provider.connection.getAccountInfo(marketConfig.vault);
+  const collateralTokenProgram = vaultInfo.owner;
+  const vault = await getAccount(program.provider.connection, marketConfig.vault, "confirmed", collateralTokenProgram);
+  const [authority] = await getAuthAddress(marketConfig.vaultState, program.programId);
+
+  return program.methods
+    .claimFor()
+    .accountsPartial({
+      authority,
+      marketConfig: configAddress,
+      vaultState: marketConfig.vaultState,
+      vault: marketConfig.vault,
+      ct1Mint: marketConfig.ct1Mint,
+      ct2Mint: marketConfig.ct2Mint,
+      collateralMint: vault.mint,
+      collateralTokenProgram,
+      tokenProgram: TOKEN_2022_PROGRAM_ID,
+    })
+    .remainingAccounts(
+      holders.flatMap((holder) => [
+        { pubkey: getAssociatedTokenAddressSync(ctMint, holder, false, TOKEN_2022_PROGRAM_ID), isSigner: false, isWritable: true },
+        { pubkey: getAssociatedTokenAddressSync(vault.mint, holder, false, collateralTokenProgram), isSigner: false, isWritable: true },
+      ])
+    )
+    .rpc(confirmOptions);
+}
+
+// burns what no longer pays out and closes the owner's ct accounts, an account left as null is skipped
+export async function closePosition(
+  program: Program<MarketProgram>,
+  owner: Signer,
+  configAddress: PublicKey,
+  accounts: { ct1Account: PublicKey | null; ct2Account: PublicKey | null },
+  confirmOptions?: ConfirmOptions
+) {
+  const marketConfig = await program.account.marketConfig.fetch(configAddress);
+  return program.methods
+    .closePosition()
+    .accountsPartial({
+      bettor: owner.publicKey,
+      marketConfig: configAddress,
+      vaultState: marketConfig.vaultState,
+      ct1Mint: marketConfig.ct1Mint,
+      ct2Mint: marketConfig.ct2Mint,
+      ct1Account: accounts.ct1Account,
+      ct2Account: accounts.ct2Account,
+      tokenProgram: TOKEN_2022_PROGRAM_ID,
+    })
+    .signers([owner])
+    .rpc(confirmOptions);
+}
+
+export type BatchBetEntry = { configAddress: PublicKey; amount: BN };
+
+// the eight remaining accounts batch_buy_bet and batch_sell_bet expect per market
+export async function batchBetMarketAccounts(
+  program: Program<MarketProgram>,
+  owner: PublicKey,
+  configAddress: PublicKey
+): Promise<AccountMeta[]> {
+  const marketConfig = await program.account.marketConfig.fetch(configAddress);
+  const [authority] = await getAuthAddress(marketConfig.vaultState, program.programId);
+  return [
+    configAddress,
+    marketConfig.vaultState,
+    authority,
+    marketConfig.vault,
+    marketConfig.ct1Mint,
+    marketConfig.ct2Mint,
+    getAssociatedTokenAddressSync(marketConfig.ct1Mint, owner, false, TOKEN_2022_PROGRAM_ID),
+    getAssociatedTokenAddressSync(marketConfig.ct2Mint, owner, false, TOKEN_2022_PROGRAM_ID),
+  ].map((pubkey, i) => ({ pubkey, isSigner: false, isWritable: i !== 2 }));
+}
+
+// a lookup table with every account a batch over `configAddresses` touches, see createLookupTable
+export async function createBatchBetLookupTable(
+  program: Program<MarketProgram>,
+  payer: Signer,
+  owner: PublicKey,
+  configAddresses: PublicKey[],
+  collateralMint: PublicKey,
+  collateralTokenProgram: PublicKey
+): Promise<AddressLookupTableAccount> {
+  const [collateralInfo] = await getCollateralInfoAddress(collateralMint, program.programId);
+  const [eventAuthority] = PublicKey.findProgramAddressSync([Buffer.from("__event_authority")], program.programId);
+  const shared = [
+    collateralMint,
+    getAssociatedTokenAddressSync(collateralMint, owner, false, collateralTokenProgram),
+    collateralInfo,
+    collateralTokenProgram,
+    TOKEN_2022_PROGRAM_ID,
+    ASSOCIATED_PROGRAM_ID,
+    SystemProgram.programId,
+    eventAuthority,
+    program.programId,
+  ];
+  const perMarket = await Promise.all(
+    configAddresses.map((configAddress) => batchBetMarketAccounts(program, owner, configAddress))
+  );
+  return createLookupTable(
+    program.provider.connection,
+    payer,
+    [...shared, ...perMarket.flat().map((meta) => meta.pubkey)]
+  );
+}
+
+async function sendBatchBet(
+  program: Program<MarketProgram>,
+  owner: Signer,
+  sell: boolean,
+  entries: BatchBetEntry[],
+  collateralMint: PublicKey,
+  collateralTokenProgram: PublicKey,
+  lookupTable: AddressLookupTableAccount,
+  delegateClaim = false
+) {
+  const remainingAccounts: AccountMeta[] = [];
+  const allowlistEntries: AccountMeta[] = [];
+  const args = [];
+  for (const entry of entries) {
+    const accounts = await batchBetMarketAccounts(program, owner.publicKey, entry.configAddress);
+    remainingAccounts.push(...accounts);
+    args.push({ vaultState: accounts[1].pubkey, amount: entry.amount });
+    // buying into a restricted market needs the bettor's allowlist entry after all entries' accounts
+    if (!sell && (await program.account.marketConfig.fetch(entry.configAddress)).restricted) {
+      const [allowlistEntry] = await getAllowlistEntryAddress(entry.configAddress, owner.publicKey);
+      allowlistEntries.push({ pubkey: allowlistEntry, isSigner: false, isWritable: false });
+    }
+  }
+  remainingAccounts.push(...allowlistEntries);
+  const method = sell ? program.methods.batchSellBet(args) : program.methods.batchBuyBet(args, delegateClaim);
+  const ix = await method
+    .accountsPartial({
+      bettor: owner.publicKey,
+      collateralAccount: getAssociatedTokenAddressSync(collateralMint, owner.publicKey, false, collateralTokenProgram),
+      collateralMint,
+      collateralInfo: (await getCollateralInfoAddress(collateralMint, program.programId))[0],
+      collateralTokenProgram,
+      tokenProgram: TOKEN_2022_PROGRAM_ID,
+      associatedTokenProgram: ASSOCIATED_PROGRAM_ID,
+      systemProgram: SystemProgram.programId,
+    })
+    .remainingAccounts(remainingAccounts)
+    .instruction();
+  // every entry costs about as much as a single buy_bet or sell_bet
+  const computeIx = ComputeBudgetProgram.setComputeUnitLimit({ units: 1_400_000 });
+  return sendVersionedTransaction(program.provider.connection, owner, [computeIx, ix], [owner], [lookupTable]);
+}
+
+export async function batchBuyBet(
+  program: Program<MarketProgram>,
+  owner: Signer,
+  entries: BatchBetEntry[],
+  collateralMint: PublicKey,
+  collateralTokenProgram: PublicKey,
+  lookupTable: AddressLookupTableAccount,
+  delegateClaim = false
+) {
+  return sendBatchBet(program, owner, false, entries, collateralMint, collateralTokenProgram, lookupTable, delegateClaim);
+}
+
+export async function batchSellBet(
+  program: Program<MarketProgram>,
+  owner: Signer,
+  entries: BatchBetEntry[],
+  collateralMint: PublicKey,
+  collateralTokenProgram: PublicKey,
+  lookupTable: AddressLookupTableAccount
+) {
+  return sendBatchBet(program, owner, true, entries, collateralMint, collateralTokenProgram, lookupTable);
+}
+
+// reads every registry page, one getMultipleAccounts call per 100 pages
+export async function listMarkets(program: Program<MarketProgram>) {
+  const [counterAddress] = await getMarketCounterAddress(program.programId);
+  const counter = await program.account.marketCounter.fetchNullable(counterAddress);
+  if (!counter) {
+    return [];
+  }
+  const pageCount = Math.ceil(counter.count.toNumber() / REGISTRY_PAGE_CAPACITY);
+  const addresses = await Promise.all(
+    [...Array(pageCount).keys()].map(async (page) =>
+      (await getMarketRegistryPageAddress(page, program.programId))[0]
+    )
+  );
+  const pages = await program.account.marketRegistryPage.fetchMultiple(addresses);
+  return pages.flatMap((page) => (page ? page.entries : []));
+}
@@ -17,6 +17,38 @@ export const CONDITIONAL_TOKEN_1_SEED = Buffer.from(
 export const CONDITIONAL_TOKEN_2_SEED = Buffer.from(
   anchor.utils.bytes.utf8.encode("conditional_token2")
 );
+// configs created through the market counter, first version configs keep MARKET_CONFIG_SEED
+export const INDEXED_MARKET_CONFIG_SEED = Buffer.from(
+  anchor.utils.bytes.utf8.encode("indexed_market_config")
+);
+export const MARKET_COUNTER_SEED = Buffer.from(
+  anchor.utils.bytes.utf8.encode("market_counter")
+);
+export const MARKET_REGISTRY_SEED = Buffer.from(
+  anchor.utils.bytes.utf8.encode("market_registry")
+);
+export const REGISTRY_PAGE_CAPACITY = 64;
+export const COLLATERAL_INFO_SEED = Buffer.from(
+  anchor.utils.bytes.utf8.encode("collateral_info")
+);
+export const BPF_LOADER_UPGRADEABLE_ID = new PublicKey(
+  "BPFLoaderUpgradeab1e11111111111111111111111"
+);
+export const RESOLUTION_RECORD_SEED = Buffer.from(
+  anchor.utils.bytes.utf8.encode("resolution_record")
+);
+export const CT_TRANSFER_HOOK_PROGRAM_ID = new PublicKey(
+  "HN7ixWKPXRgtcCkh3JWpvR8XxCVSpKoKggFnxmxEubKk"
+);
+export const EXTRA_ACCOUNT_METAS_SEED = Buffer.from(
+  anchor.utils.bytes.utf8.encode("extra-account-metas")
+);
+export const ALLOWLIST_SEED = Buffer.from(
+  anchor.utils.bytes.utf8.encode("allowlist")
+);
+export const RESOLUTION_CONFIG_SEED = Buffer.from(
+  anchor.utils.bytes.utf8.encode("resolution_config")
+);
 
 
 export function u16ToBytes(num: number) {
@@ -58,7 +90,41 @@ export async function getMarketConfigAddress(
   return [address, bump];
 }
 
+export async function getIndexedMarketConfigAddress(
+  index: number | anchor.BN,
+  programId: PublicKey
+): Promise<[PublicKey, number]> {
+  const [address, bump] = await PublicKey.findProgramAddress(
+    [INDEXED_MARKET_CONFIG_SEED, new anchor.BN(index).toArrayLike(Buffer, "be", 8)],
+    programId
+  );
+  return [address, bump];
+}
+
+export async function getMarketCounterAddress(
+  programId: PublicKey
+): Promise<[PublicKey, number]> {
+  const [address, bump] = await PublicKey.findProgramAddress(
+    [MARKET_COUNTER_SEED],
+    programId
+  );
+  return [address, bump];
+}
+
+// each market's mints and vault are controlled by an authority derived from its vault state
 export async function getAuthAddress(
+  vaultState: PublicKey,
+  programId: PublicKey
+): Promise<[PublicKey, number]> {
+  const [address, bump] = await PublicKey.findProgramAddress(
+    [AUTH_SEED, vaultState.toBuffer()],
+    programId
+  );
+  return [address, bump];
+}
+
+// the global authority markets used before migrate_market_authority
+export async function getLegacyAuthAddress(
   programId: PublicKey
 ): Promise<[PublicKey, number]> {
   const [address, bump] = await PublicKey.findProgramAddress(
@@ -117,6 +183,58 @@ export async function getct2MintAddress(
   return [address, bump];
 }
 
+export async function getExtraAccountMetaListAddress(
+  mint: PublicKey
+): Promise<[PublicKey, number]> {
+  const [address, bump] = await PublicKey.findProgramAddress(
+    [EXTRA_ACCOUNT_METAS_SEED, mint.toBuffer()],
+    CT_TRANSFER_HOOK_PROGRAM_ID
+  );
+  return [address, bump];
+}
+
+export async function getAllowlistEntryAddress(
+  marketConfig: PublicKey,
+  wallet: PublicKey
+): Promise<[PublicKey, number]> {
+  const [address, bump] = await PublicKey.findProgramAddress(
+    [ALLOWLIST_SEED, marketConfig.toBuffer(), wallet.toBuffer()],
+    CT_TRANSFER_HOOK_PROGRAM_ID
+  );
+  return [address, bump];
+}
+
+// the accounts initialize needs to create the transfer hook's lists of Token-2022 conditional tokens
+export async function getTransferHookAccounts(ct1Mint: PublicKey, ct2Mint: PublicKey) {
+  return {
+    transferHookProgram: CT_TRANSFER_HOOK_PROGRAM_ID,
+    ct1ExtraAccountMetaList: (await getExtraAccountMetaListAddress(ct1Mint))[0],
+    ct2ExtraAccountMetaList: (await getExtraAccountMetaListAddress(ct2Mint))[0],
+  };
+}
+
+export async function getResolutionRecordAddress(
+  marketConfig: PublicKey,
+  programId: PublicKey
+): Promise<[PublicKey, number]> {
+  const [address, bump] = await PublicKey.findProgramAddress(
+    [RESOLUTION_RECORD_SEED, marketConfig.toBuffer()],
+    programId
+  );
+  return [address, bump];
+}
+
+export async function getResolutionConfigAddress(
+  marketConfig: PublicKey,
+  programId: PublicKey
+): Promise<[PublicKey, number]> {
+  const [address, bump] = await PublicKey.findProgramAddress(
+    [RESOLUTION_CONFIG_SEED, marketConfig.toBuffer()],
+    programId
+  );
+  return [address, bump];
+}
+
 // export async function getOrcleAdapterPdaAccountAddress(
 //   pool: PublicKey,
 //   programId: PublicKey
@@ -127,3 +245,35 @@ export async function getct2MintAddress(
 //   );
 //   return [address, bump];
 // }
+
+export async function getMarketRegistryPageAddress(
+  page: number | anchor.BN,
+  programId: PublicKey
+): Promise<[PublicKey, number]> {
+  const [address, bump] = await PublicKey.findProgramAddress(
+    [MARKET_REGISTRY_SEED, new anchor.BN(page).toArrayLike(Buffer, "be", 8)],
+    programId
+  );
+  return [address, bump];
+}
+
+export async function getCollateralInfoAddress(
+  collateralMint: PublicKey,
+  programId: PublicKey
+): Promise<[PublicKey, number]> {
+  const [address, bump] = await PublicKey.findProgramAddress(
+    [COLLATERAL_INFO_SEED, collateralMint.toBuffer()],
+    programId
+  );
+  return [address, bump];
+}
+
+export async function getProgramDataAddress(
+  programId: PublicKey
+): Promise<[PublicKey, number]> {
+  const [address, bump] = await PublicKey.findProgramAddress(
+    [programId.toBuffer()],
+    BPF_LOADER_UPGRADEABLE_ID
+  );
+  return [address, bump];
+}
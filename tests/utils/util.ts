@@ -12,13 +12,14 @@ import { MarketProgram } from "../../target/types/market_program";
 
 export async function marketResolutionInstruction(
   program: Program<MarketProgram>,
+  marketConfig: PublicKey,
   vaultState: PublicKey,
 ) {
+  const config = await program.account.marketConfig.fetch(marketConfig);
   const accountInfo = await program.account.vaultState.fetch(vaultState);
-  const resolution = accountInfo.resolution;
   const winnerCtMint = accountInfo.winningCtMint;
 
-  if (resolution === 1) {
+  if (config.status.resolved !== undefined || config.status.finalized !== undefined) {
     return winnerCtMint;
   } else {
     return null;
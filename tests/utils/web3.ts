@@ -1,11 +1,16 @@
 import * as anchor from "@coral-xyz/anchor";
 import {
+  AddressLookupTableAccount,
+  AddressLookupTableProgram,
   Connection,
   ConfirmOptions,
+  PublicKey,
   Signer,
   Transaction,
   TransactionInstruction,
+  TransactionMessage,
   TransactionSignature,
+  VersionedTransaction,
 } from "@solana/web3.js";
 
 
@@ -58,6 +63,74 @@ export async function sendTransaction(
   return signature;
 }
 
+// a v0 transaction, so that accounts found in `lookupTables` cost one byte each
+export async function sendVersionedTransaction(
+  connection: Connection,
+  payer: Signer,
+  ixs: TransactionInstruction[],
+  signers: Array<Signer>,
+  lookupTables: AddressLookupTableAccount[]
+): Promise<TransactionSignature> {
+  const { blockhash, lastValidBlockHeight } = await connection.getLatestBlockhash();
+  const message = new TransactionMessage({
+    payerKey: payer.publicKey,
+    recentBlockhash: blockhash,
+    instructions: ixs,
+  }).compileToV0Message(lookupTables);
+  const tx = new VersionedTransaction(message);
+  tx.sign([payer, ...signers.filter((signer) => !signer.publicKey.equals(payer.publicKey))]);
+
+  const signature = await connection.sendTransaction(tx, { preflightCommitment: "confirmed" });
+  const status = (
+    await connection.confirmTransaction({ signature, blockhash, lastValidBlockHeight }, "confirmed")
+  ).value;
+  if (status.err) {
+    throw new Error(
+      `Raw transaction ${signature} failed (${JSON.stringify(status)})`
+    );
+  }
+  return signature;
+}
+
+// creates an address lookup table holding `addresses` and waits until it can be used
+export async function createLookupTable(
+  connection: Connection,
+  payer: Signer,
+  addresses: PublicKey[]
+): Promise<AddressLookupTableAccount> {
+  const unique = addresses.filter(
+    (address, i) => addresses.findIndex((other) => other.equals(address)) === i
+  );
+  const [createIx, lookupTable] = AddressLookupTableProgram.createLookupTable({
+    authority: payer.publicKey,
+    payer: payer.publicKey,
+    recentSlot: await connection.getSlot("finalized"),
+  });
+  await sendTransaction(connection, [createIx], [payer]);
+
+  // an extend instruction fits about 30 addresses
+  for (let i = 0; i < unique.length; i += 30) {
+    const extendIx = AddressLookupTableProgram.extendLookupTable({
+      lookupTable,
+      authority: payer.publicKey,
+      payer: payer.publicKey,
+      addresses: unique.slice(i, i + 30),
+    });
+    await sendTransaction(connection, [extendIx], [payer]);
+  }
+
+  // new addresses become usable one slot after they were added
+  const extendedAt = await connection.getSlot("confirmed");
+  while ((await connection.getSlot("confirmed")) <= extendedAt) {
+    await new Promise((resolve) => setTimeout(resolve, 200));
+  }
+  const account = (await connection.getAddressLookupTable(lookupTable)).value;
+  if (!account) {
+    throw new Error(`Lookup table ${lookupTable} not found`);
+  }
+  return account;
+}
+
 export async function getBlockTimestamp(
   connection: Connection
 ): Promise<number> {